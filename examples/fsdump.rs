@@ -12,10 +12,16 @@ use io_unity::unityfs::UnityFS;
 use std::collections::HashSet;
 use std::fs::{create_dir_all, File, OpenOptions};
 use std::io::{BufReader, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use io_unity::{
-    classes::ClassIDType, type_tree::type_tree_json::set_info_json_tar_reader,
+    addressables::ContentCatalog,
+    classes::ClassIDType,
+    load_report::LoadReport,
+    type_tree::{
+        managed::ManagedAssemblyTypeTreeProvider, provider::add_type_tree_provider,
+        type_tree_json::set_info_json_tar_reader, type_tree_tpk::TpkTypeTreeProvider,
+    },
     unity_asset_view::UnityAssetViewer,
 };
 
@@ -29,6 +35,18 @@ pub struct Args {
     /// The dir contain data files.
     #[arg(short, long)]
     data_dir: Option<String>,
+    /// The apk, obb or other zip package contain Unity files under assets/.
+    #[arg(long)]
+    zip_package: Option<String>,
+    /// The AssetBundleManifest bundle of a bundle build, used with --asset-bundle.
+    #[arg(long)]
+    asset_bundle_manifest: Option<String>,
+    /// Bundle listed by the manifest, added with its dependencies.
+    #[arg(long, requires = "asset_bundle_manifest")]
+    asset_bundle: Vec<String>,
+    /// The Addressables catalog.json or catalog.bin, addresses are listed next to container paths.
+    #[arg(long)]
+    addressables_catalog: Option<String>,
     /// The serialized file.
     #[arg(short, long)]
     serialized_file: Option<String>,
@@ -42,6 +60,30 @@ pub struct Args {
     /// contain file path like /InfoJson/x.x.x.json.
     #[arg(short, long)]
     info_json_tar_path: Option<String>,
+    /// The AssetRipper tpk file contain type tree info
+    /// for read file without typetree info.
+    /// see https://github.com/AssetRipper/Tpk.
+    #[arg(short, long)]
+    tpk_path: Option<String>,
+    /// The Managed dir contain game assemblies,
+    /// used to read MonoBehaviour fields of files without typetree info.
+    #[arg(short, long)]
+    managed_dir: Option<String>,
+    /// The il2cpp global-metadata.dat and native binary (GameAssembly.dll or libil2cpp.so),
+    /// used like managed dir for IL2CPP build.
+    #[arg(long, num_args = 2, value_names = ["METADATA", "BINARY"])]
+    il2cpp: Option<Vec<String>>,
+    /// Parse serialized files on first access and keep at most this many files open.
+    #[arg(long)]
+    lazy: Option<usize>,
+    /// Index file of the dirs read, unchanged files are restored from it on the next run.
+    /// The viewer is lazy with 64 open files when --lazy is not given.
+    /// List do not parse files for MonoBehaviour script names then.
+    #[arg(long)]
+    index: Option<String>,
+    /// Stop at the first file which can not be loaded.
+    #[arg(long)]
+    strict: bool,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -50,7 +92,7 @@ pub struct Args {
 pub enum Commands {
     /// List container path
     List {
-        /// filter path
+        /// filter path, a prefix or a glob like assets/**/*.png
         #[arg(value_parser)]
         filter_path: Option<String>,
     },
@@ -73,51 +115,131 @@ fn main() -> anyhow::Result<()> {
         set_info_json_tar_reader(Box::new(BufReader::new(tar_file)));
     }
 
+    if let Some(path) = args.tpk_path {
+        let tpk_file = File::open(path)?;
+        let provider = TpkTypeTreeProvider::read(&mut BufReader::new(tpk_file))?;
+        add_type_tree_provider(Box::new(provider));
+    }
+
+    if let Some(path) = args.managed_dir {
+        let mut provider = ManagedAssemblyTypeTreeProvider::new();
+        provider.read_managed_dir(path)?;
+        add_type_tree_provider(Box::new(provider));
+    }
+
+    if let Some(paths) = args.il2cpp {
+        let mut provider = ManagedAssemblyTypeTreeProvider::new();
+        provider.read_il2cpp(
+            &mut BufReader::new(File::open(&paths[0])?),
+            &mut BufReader::new(File::open(&paths[1])?),
+        )?;
+        add_type_tree_provider(Box::new(provider));
+    }
+
     let time = std::time::Instant::now();
 
-    let mut unity_asset_viewer = UnityAssetViewer::new();
+    let mut unity_asset_viewer = match (args.lazy, &args.index) {
+        (Some(max_open_files), _) => UnityAssetViewer::new_lazy(max_open_files),
+        (None, Some(_)) => UnityAssetViewer::new_lazy(64),
+        (None, None) => UnityAssetViewer::new(),
+    };
+    if let Some(index) = args
+        .index
+        .as_ref()
+        .filter(|index| Path::new(index).exists())
+    {
+        if let Err(err) = unity_asset_viewer.load_index(index) {
+            eprintln!("Index {:?} not used : {}", index, err);
+        }
+    }
+    unity_asset_viewer.set_strict(args.strict);
+    let mut report = LoadReport::default();
     if let Some(bundle_dir) = args.bundle_dir {
-        unity_asset_viewer.read_bundle_dir(&bundle_dir)?;
-
+        report.extend(unity_asset_viewer.read_bundle_dir(&bundle_dir)?);
     }
     if let Some(data_dir) = args.data_dir {
-        unity_asset_viewer.read_data_dir(data_dir)?;
+        report.extend(unity_asset_viewer.read_data_dir(data_dir)?);
+    }
+    if let Some(zip_package) = args.zip_package {
+        report.extend(unity_asset_viewer.read_zip_package(zip_package)?);
+    }
+    if let Some(asset_bundle_manifest) = args.asset_bundle_manifest {
+        unity_asset_viewer.read_asset_bundle_manifest(asset_bundle_manifest)?;
+        for asset_bundle in &args.asset_bundle {
+            report.extend(unity_asset_viewer.load_asset_bundle(asset_bundle)?);
+        }
+    }
+    for failed_file in report.get_failed_files() {
+        if let Some(error) = &failed_file.error {
+            eprintln!("Failed to load {:?} : {}", failed_file.path, error);
+        }
     }
     if let Some(serialized_file) = args.serialized_file {
         let file = OpenOptions::new().read(true).open(serialized_file).unwrap();
         unity_asset_viewer
             .add_serialized_file(Box::new(BufReader::new(file)), Some(".".to_owned()))?;
     }
-    println!("Read use {:?}", time.elapsed());
+    let addresses = if let Some(path) = &args.addressables_catalog {
+        ContentCatalog::read(&mut BufReader::new(File::open(path)?))?
+            .get_addresses_by_container_name()
+    } else {
+        Default::default()
+    };
+    println!(
+        "Read use {:?}, {} files from index",
+        time.elapsed(),
+        report.files.iter().filter(|file| file.from_index).count()
+    );
 
     match &args.command {
         Commands::List { filter_path } => {
-            for (container_path, _) in &unity_asset_viewer.container_maps {
-                if let Some(filter_path) = filter_path {
-                    if container_path.starts_with(filter_path) {
-                        println!("{}", container_path);
-                    }
-                } else {
-                    println!("{}", container_path);
+            let entries: Box<dyn Iterator<Item = _>> = match filter_path {
+                Some(filter_path) if filter_path.contains(['*', '?']) => {
+                    Box::new(unity_asset_viewer.iter_containers_by_glob(filter_path))
+                }
+                Some(filter_path) => {
+                    Box::new(unity_asset_viewer.iter_containers_by_prefix(filter_path))
+                }
+                None => Box::new(unity_asset_viewer.iter_containers()),
+            };
+            for entry in entries {
+                let class = entry
+                    .get_class_id()
+                    .and_then(|class_id| ClassIDType::try_from(class_id).ok());
+                match addresses.get(entry.container_name) {
+                    Some(addresses) => println!(
+                        "{}\t{:?}\t{}",
+                        entry.container_name,
+                        class,
+                        addresses.join(", ")
+                    ),
+                    None => println!("{}\t{:?}", entry.container_name, class),
                 }
             }
 
             let mut object_types = HashSet::new();
             let mut mono_behaviour_calss_types = HashSet::new();
-            for (_, sf) in &unity_asset_viewer.serialized_file_map {
-                for (pathid, obj) in sf.get_object_map() {
-                    if obj.class == ClassIDType::MonoScript as i32 {
+            // object tables of files restored from the index are listed without parsing them,
+            // script names of MonoBehaviour need the objects so they are only read without index.
+            for serialized_file_id in unity_asset_viewer.get_serialized_file_ids() {
+                for (pathid, class_id) in
+                    unity_asset_viewer.get_object_class_ids(serialized_file_id)?
+                {
+                    if class_id == ClassIDType::MonoScript as i32 {
                         // let tt_o = sf.get_tt_object_by_path_id(*pathid).unwrap().unwrap();
                         // println!("name\t{:?}", tt_o.get_value_by_path("/Base/m_Name"));
                         // println!("\t{:?}", tt_o.get_value_by_path("/Base/m_ClassName"));
                         // println!("\t{:?}", tt_o.get_value_by_path("/Base/m_Namespace"));
                         // println!("\t{:?}", tt_o.get_value_by_path("/Base/m_AssemblyName"));
-                    } else if obj.class == ClassIDType::MonoBehaviour as i32 {
+                    } else if class_id == ClassIDType::MonoBehaviour as i32 && args.index.is_none()
+                    {
+                        let sf =
+                            unity_asset_viewer.get_serialized_file_by_id(serialized_file_id)?;
                         let obj = sf
-                            .get_tt_object_by_path_id(*pathid)
+                            .get_tt_object_by_path_id(pathid)
                             .map_err(|err| {
                                 let fs_path = unity_asset_viewer
-                                    .get_unity_fs_by_serialized_file(&sf)
+                                    .get_unity_fs_by_serialized_file(sf)
                                     .and_then(|fs| {
                                         dump_unity_fs(fs);
                                         fs.resource_search_path.clone()
@@ -148,7 +270,7 @@ fn main() -> anyhow::Result<()> {
                     }
 
                     object_types
-                        .insert(ClassIDType::try_from(obj.class).unwrap_or(ClassIDType::Object));
+                        .insert(ClassIDType::try_from(class_id).unwrap_or(ClassIDType::Object));
                 }
             }
             println!("object_types : {:?}", object_types);
@@ -188,7 +310,8 @@ fn main() -> anyhow::Result<()> {
                 return out_path;
             };
 
-            for (serialized_file_id, sf) in &unity_asset_viewer.serialized_file_map {
+            for (serialized_file_id, sf) in unity_asset_viewer.iter_serialized_files() {
+                let sf = sf?;
                 for (path_id, obj_meta) in sf.get_object_map() {
                     let obj = sf
                         .get_tt_object_by_path_id(*path_id)
@@ -210,7 +333,7 @@ fn main() -> anyhow::Result<()> {
 
                     let container_name = unity_asset_viewer
                         .get_container_name_by_serialized_file_id_and_path_id(
-                            *serialized_file_id,
+                            serialized_file_id,
                             *path_id,
                         );
 
@@ -245,6 +368,14 @@ fn main() -> anyhow::Result<()> {
         }
     }
 
+    if let Some(index) = &args.index {
+        let report = unity_asset_viewer.save_index(index)?;
+        for failed_file in report.get_failed_files() {
+            if let Some(error) = &failed_file.error {
+                eprintln!("Failed to index {:?} : {}", failed_file.path, error);
+            }
+        }
+    }
     Ok(())
 }
 
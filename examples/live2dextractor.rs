@@ -19,6 +19,7 @@ use io_unity::{
         transform::{get_bone_path_hash_map, Transform},
         ClassIDType,
     },
+    load_report::LoadReport,
     type_tree::{type_tree_json::set_info_json_tar_reader, TypeTreeObjectRef},
     unity_asset_view::UnityAssetViewer,
 };
@@ -440,18 +441,24 @@ fn main() -> anyhow::Result<()> {
 
     let mut unity_asset_viewer = UnityAssetViewer::new();
 
+    let mut report = LoadReport::default();
     if let Some(bundle_dir) = args.bundle_dir {
-        unity_asset_viewer.read_bundle_dir(bundle_dir)?;
+        report.extend(unity_asset_viewer.read_bundle_dir(bundle_dir)?);
     }
     if let Some(data_dir) = args.data_dir {
-        unity_asset_viewer.read_data_dir(data_dir)?;
+        report.extend(unity_asset_viewer.read_data_dir(data_dir)?);
+    }
+    for failed_file in report.get_failed_files() {
+        if let Some(error) = &failed_file.error {
+            eprintln!("Failed to load {:?} : {}", failed_file.path, error);
+        }
     }
 
     println!("Read use {:?}", time.elapsed());
 
     match &args.command {
         Commands::List { filter_path } => {
-            for (container_path, _) in &unity_asset_viewer.container_maps {
+            for (container_path, _) in unity_asset_viewer.get_container_maps() {
                 if let Some(filter_path) = filter_path {
                     if container_path.starts_with(filter_path) {
                         println!("{}", container_path);
@@ -471,7 +478,7 @@ fn main() -> anyhow::Result<()> {
             let mut cubism_physics3_json = None;
             let mut cubism_exp3_json_map = HashMap::new();
 
-            for (container_path, _) in &unity_asset_viewer.container_maps {
+            for (container_path, _) in unity_asset_viewer.get_container_maps() {
                 if container_path.starts_with(filter_path) {
                     println!("Get {}", container_path);
                     let obj = unity_asset_viewer
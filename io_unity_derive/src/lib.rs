@@ -0,0 +1,299 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, GenericArgument, LitStr,
+    PathArguments, Type,
+};
+
+/// Generate a wrapper of `TypeTreeObjectRef` like `def_unity_class!`, with a getter for every field
+/// marked by `#[unity(..)]`.
+///
+/// The struct need a `inner: &'a TypeTreeObjectRef` field, other fields are `UnityField<T>` and
+/// get a `get_<field>(&self) -> ReadResult<T>` getter with the visibility of the field.
+///
+/// * `path = "m_StreamData/offset"` path of the field under the root of the object.
+/// * `since = "5.4.2"`, `before = "2019.1"` only try this path for files of these unity versions,
+///   a broken version or an empty range is a compile error.
+/// * `read = "i64"` read the field as this type and convert it with `TryFrom`.
+/// * `with = "some::function"` read the field and convert it with `fn(read) -> ReadResult<T>`.
+///
+/// A field can have more than one `#[unity(..)]`, they are tried in order.
+#[proc_macro_derive(UnityClass, attributes(unity))]
+pub fn derive_unity_class(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct Alternate {
+    path: LitStr,
+    since: Option<LitStr>,
+    before: Option<LitStr>,
+    read: Option<Type>,
+    with: Option<syn::Path>,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let lifetime = input
+        .generics
+        .lifetimes()
+        .next()
+        .map(|lifetime| lifetime.lifetime.clone())
+        .ok_or_else(|| {
+            Error::new(
+                input.generics.span(),
+                "UnityClass need a lifetime like <'a>",
+            )
+        })?;
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.span(),
+            "UnityClass can only be derived for struct",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new(
+            data.fields.span(),
+            "UnityClass need named fields",
+        ));
+    };
+
+    let mut has_inner = false;
+    let mut field_inits = Vec::new();
+    let mut getters = Vec::new();
+    for field in &fields.named {
+        let field_name = field.ident.as_ref().unwrap();
+        let alternates = parse_alternates(field)?;
+        if field_name == "inner" && alternates.is_empty() {
+            has_inner = true;
+            continue;
+        }
+        if alternates.is_empty() {
+            return Err(Error::new(
+                field.span(),
+                "field need #[unity(path = \"..\")]",
+            ));
+        }
+        field_inits.push(quote!(#field_name: ::core::marker::PhantomData));
+        getters.push(expand_getter(field, &alternates)?);
+    }
+    if !has_inner {
+        return Err(Error::new(
+            fields.span(),
+            "UnityClass need a `inner: &'a TypeTreeObjectRef` field",
+        ));
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub fn new(inner: &#lifetime ::io_unity::type_tree::TypeTreeObjectRef) -> Self {
+                Self {
+                    inner,
+                    #(#field_inits,)*
+                }
+            }
+
+            pub fn inner(&self) -> &::io_unity::type_tree::TypeTreeObjectRef {
+                self.inner
+            }
+
+            #(#getters)*
+        }
+
+        impl #impl_generics ::io_unity::classes::SerializedFileRef for #name #ty_generics #where_clause {
+            fn get_serialized_file_id(&self) -> i64 {
+                self.inner.get_serialized_file_id()
+            }
+        }
+
+        impl #impl_generics ::io_unity::classes::CastRef<#name #ty_generics>
+            for &#lifetime ::io_unity::type_tree::TypeTreeObjectRef #where_clause
+        {
+            fn cast_as(&self) -> #name #ty_generics {
+                #name::new(*self)
+            }
+        }
+    })
+}
+
+fn parse_alternates(field: &syn::Field) -> syn::Result<Vec<Alternate>> {
+    let mut alternates = Vec::new();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("unity"))
+    {
+        let mut path = None;
+        let mut alternate = Alternate {
+            path: LitStr::new("", attr.span()),
+            since: None,
+            before: None,
+            read: None,
+            with: None,
+        };
+        attr.parse_nested_meta(|meta| {
+            let value: LitStr = meta.value()?.parse()?;
+            if meta.path.is_ident("path") {
+                path = Some(value);
+            } else if meta.path.is_ident("since") {
+                alternate.since = Some(value);
+            } else if meta.path.is_ident("before") {
+                alternate.before = Some(value);
+            } else if meta.path.is_ident("read") {
+                alternate.read = Some(value.parse()?);
+            } else if meta.path.is_ident("with") {
+                alternate.with = Some(value.parse()?);
+            } else {
+                return Err(meta.error("unknown unity attribute"));
+            }
+            Ok(())
+        })?;
+        alternate.path =
+            path.ok_or_else(|| Error::new(attr.span(), "unity attribute need a path"))?;
+        let since = alternate
+            .since
+            .as_ref()
+            .map(parse_unity_version)
+            .transpose()?;
+        let before = alternate
+            .before
+            .as_ref()
+            .map(parse_unity_version)
+            .transpose()?;
+        if let (Some(since), Some(before)) = (since, before) {
+            if since >= before {
+                return Err(Error::new(
+                    alternate.before.span(),
+                    "`before` need to be a version after `since`",
+                ));
+            }
+        }
+        alternates.push(alternate);
+    }
+    Ok(alternates)
+}
+
+/// Numbers of a version like `2019.1` or `5.4.2f1`, checked here as `UnityVersion::from_str`
+/// panic on a broken version when the getter is called.
+fn parse_unity_version(version: &LitStr) -> syn::Result<Vec<u32>> {
+    let value = version.value();
+    let (numbers, build) = match value.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(index) => value.split_at(index),
+        None => (value.as_str(), ""),
+    };
+    let build_type_len = build.chars().next().map_or(0, char::len_utf8);
+    let build_number = &build[build_type_len..];
+    let mut version_numbers = numbers
+        .split('.')
+        .map(|number| number.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>();
+    if !build.is_empty() {
+        let build_number = build_number.parse::<u32>().ok();
+        version_numbers = version_numbers
+            .zip(build_number)
+            .map(|(mut numbers, build_number)| {
+                numbers.push(build_number);
+                numbers
+            });
+    }
+    version_numbers.ok_or_else(|| {
+        Error::new(
+            version.span(),
+            format!(
+                "invalid unity version {value:?}, expect a version like \"2019.1\" or \"5.4.2f1\""
+            ),
+        )
+    })
+}
+
+/// T of `UnityField<T>` or `PhantomData<T>`.
+fn get_value_type(ty: &Type) -> syn::Result<&Type> {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(GenericArgument::Type(ty)) = args.args.first() {
+                    return Ok(ty);
+                }
+            }
+        }
+    }
+    Err(Error::new(ty.span(), "field type need to be UnityField<T>"))
+}
+
+fn expand_getter(field: &syn::Field, alternates: &[Alternate]) -> syn::Result<TokenStream2> {
+    let field_name = field.ident.as_ref().unwrap();
+    let vis = &field.vis;
+    let getter_name = format_ident!("get_{}", field_name);
+    let value_type = get_value_type(&field.ty)?;
+
+    let tries = alternates.iter().map(|alternate| {
+        let path = LitStr::new(
+            &format!("/Base/{}", alternate.path.value().trim_start_matches('/')),
+            alternate.path.span(),
+        );
+        let since = option_tokens(&alternate.since);
+        let before = option_tokens(&alternate.before);
+        let read_type = alternate.read.as_ref().unwrap_or(value_type);
+        let convert = match (&alternate.with, &alternate.read) {
+            (Some(with), _) => quote!(#with(value)),
+            (None, Some(read_type)) => quote! {
+                <#value_type as ::core::convert::TryFrom<#read_type>>::try_from(value)
+                    .map_err(|e| ::io_unity::error::Error::Other(e.to_string()))
+            },
+            (None, None) => quote!(Ok(value)),
+        };
+        quote! {
+            if ::io_unity::classes::is_unity_version_in(self.inner, #since, #before) {
+                match <#read_type as ::io_unity::type_tree::convert::TryCastFrom<
+                    &::io_unity::type_tree::TypeTreeObjectRef,
+                    &str,
+                >>::try_cast_from(self.inner, #path)
+                {
+                    Ok(value) => return #convert,
+                    Err(e) => {
+                        error.get_or_insert(e);
+                    }
+                }
+            }
+        }
+    });
+    let first_path = alternates[0].path.value();
+
+    Ok(quote! {
+        #vis fn #getter_name(&self) -> ::io_unity::error::ReadResult<#value_type> {
+            let mut error = None;
+            #(#tries)*
+            Err(error.unwrap_or_else(|| {
+                ::io_unity::error::Error::FieldNotFound(vec![#first_path.to_owned()])
+            }))
+        }
+    })
+}
+
+fn option_tokens(value: &Option<LitStr>) -> TokenStream2 {
+    match value {
+        Some(value) => quote!(::core::option::Option::Some(#value)),
+        None => quote!(::core::option::Option::None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro2::Span;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_unity_version() {
+        let parse = |version: &str| parse_unity_version(&LitStr::new(version, Span::call_site()));
+        assert_eq!(parse("2019.1").unwrap(), [2019, 1]);
+        assert_eq!(parse("5.4.2f1").unwrap(), [5, 4, 2, 1]);
+        for version in ["", "5.4.", "5..4", "5.4.2f", "5.4.2f1x", "latest"] {
+            assert!(parse(version).is_err(), "{version:?} is parsed");
+        }
+    }
+}
@@ -0,0 +1,990 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    io::Read,
+};
+
+use crate::{
+    error::{Error, ReadResult},
+    unity_asset_view::{ContainerEntry, UnityAssetViewer},
+};
+
+mod catalog_json {
+    use serde::Deserialize;
+
+    #[derive(Default, Deserialize)]
+    #[serde(default)]
+    pub struct ContentCatalogData {
+        #[serde(rename = "m_LocatorId")]
+        pub locator_id: String,
+        #[serde(rename = "m_ProviderIds")]
+        pub provider_ids: Vec<String>,
+        #[serde(rename = "m_InternalIds")]
+        pub internal_ids: Vec<String>,
+        #[serde(rename = "m_KeyDataString")]
+        pub key_data_string: String,
+        #[serde(rename = "m_BucketDataString")]
+        pub bucket_data_string: String,
+        #[serde(rename = "m_EntryDataString")]
+        pub entry_data_string: String,
+        #[serde(rename = "m_ExtraDataString")]
+        pub extra_data_string: String,
+        #[serde(rename = "m_resourceTypes")]
+        pub resource_types: Vec<SerializedType>,
+        #[serde(rename = "m_InternalIdPrefixes")]
+        pub internal_id_prefixes: Vec<String>,
+    }
+
+    #[derive(Default, Deserialize)]
+    #[serde(default)]
+    pub struct SerializedType {
+        #[serde(rename = "m_AssemblyName")]
+        pub assembly_name: String,
+        #[serde(rename = "m_ClassName")]
+        pub class_name: String,
+    }
+}
+
+/// Key or extra data of a catalog, stored as a type byte followed by the value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CatalogValue {
+    String(String),
+    UInt16(u16),
+    UInt32(u32),
+    Int32(i32),
+    /// Only in binary catalogs.
+    Int64(i64),
+    /// Only in binary catalogs.
+    Bool(bool),
+    Hash128(String),
+    Type(String),
+    /// Object serialized by JsonUtility, like AssetBundleRequestOptions of bundle locations.
+    Json {
+        assembly_name: String,
+        class_name: String,
+        json: String,
+    },
+}
+
+impl fmt::Display for CatalogValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogValue::String(value)
+            | CatalogValue::Hash128(value)
+            | CatalogValue::Type(value) => write!(f, "{value}"),
+            CatalogValue::UInt16(value) => write!(f, "{value}"),
+            CatalogValue::UInt32(value) => write!(f, "{value}"),
+            CatalogValue::Int32(value) => write!(f, "{value}"),
+            CatalogValue::Int64(value) => write!(f, "{value}"),
+            CatalogValue::Bool(value) => write!(f, "{value}"),
+            CatalogValue::Json { json, .. } => write!(f, "{json}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ResourceLocation {
+    /// Asset path for assets, bundle path for bundles. Prefixes are expanded.
+    pub internal_id: String,
+    pub provider_id: String,
+    /// Key whose locations must be loaded first, the bundles of an asset.
+    pub dependency_key_index: Option<usize>,
+    pub dependency_hash: i32,
+    pub data: Option<CatalogValue>,
+    /// Key the location is known by, the address for assets.
+    pub primary_key_index: usize,
+    pub resource_type: Option<String>,
+}
+
+impl ResourceLocation {
+    pub fn is_asset_bundle(&self) -> bool {
+        self.provider_id.ends_with("AssetBundleProvider")
+    }
+
+    /// File name of a bundle location.
+    pub fn get_bundle_file_name(&self) -> Option<&str> {
+        if !self.is_asset_bundle() {
+            return None;
+        }
+        self.internal_id.rsplit(['/', '\\']).next()
+    }
+
+    /// Name of the asset in m_Container of its bundle, sub asset names like "[icon]" are removed.
+    pub fn get_container_name(&self) -> Option<String> {
+        if self.is_asset_bundle() || !self.internal_id.contains('/') {
+            return None;
+        }
+        let path = match self.internal_id.find('[') {
+            Some(index) if self.internal_id.ends_with(']') => &self.internal_id[..index],
+            _ => &self.internal_id,
+        };
+        Some(path.to_lowercase())
+    }
+}
+
+/// Addressables content catalog, catalog.json or catalog.bin of a build.
+#[derive(Debug, Default)]
+pub struct ContentCatalog {
+    pub locator_id: String,
+    pub provider_ids: Vec<String>,
+    keys: Vec<CatalogValue>,
+    /// Locations of each key, indexed like keys.
+    key_locations: Vec<Vec<usize>>,
+    locations: Vec<ResourceLocation>,
+}
+
+impl ContentCatalog {
+    /// Read catalog.json or the binary catalog.bin of Addressables 2, detected by its magic.
+    pub fn read<R: Read>(reader: &mut R) -> ReadResult<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        if data.starts_with(&BINARY_CATALOG_MAGIC.to_le_bytes()) {
+            return Self::from_binary(&data);
+        }
+        let text = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&data);
+        let text = std::str::from_utf8(text)
+            .map_err(|err| Error::Other(format!("content catalog is not utf-8 : {err}")))?;
+        Self::from_json_str(text)
+    }
+
+    pub fn from_json_str(text: &str) -> ReadResult<Self> {
+        let catalog: catalog_json::ContentCatalogData = serde_json::from_str(text)
+            .map_err(|err| Error::Other(format!("invalid content catalog json : {err}")))?;
+        let key_data = decode_base64(&catalog.key_data_string)?;
+        let bucket_data = decode_base64(&catalog.bucket_data_string)?;
+        let entry_data = decode_base64(&catalog.entry_data_string)?;
+        let extra_data = decode_base64(&catalog.extra_data_string)?;
+
+        let mut keys = Vec::new();
+        let mut key_locations = Vec::new();
+        let bucket_count = read_i32(&bucket_data, 0)?;
+        let mut offset = 4;
+        for _ in 0..bucket_count {
+            let data_offset = read_i32(&bucket_data, offset)?;
+            let entry_count = read_i32(&bucket_data, offset + 4)?;
+            offset += 8;
+            let mut entries = Vec::new();
+            for _ in 0..entry_count {
+                entries.push(read_i32(&bucket_data, offset)? as usize);
+                offset += 4;
+            }
+            keys.push(read_value(&key_data, data_offset as usize)?);
+            key_locations.push(entries);
+        }
+
+        let get_string = |strings: &[String], index: i32| {
+            strings.get(index as usize).cloned().ok_or_else(|| {
+                Error::Other(format!("content catalog string index {index} out of range"))
+            })
+        };
+        let mut locations = Vec::new();
+        let entry_count = read_i32(&entry_data, 0)?;
+        for i in 0..entry_count as usize {
+            let mut values = [0i32; 7];
+            for (j, value) in values.iter_mut().enumerate() {
+                *value = read_i32(&entry_data, 4 + (i * 7 + j) * 4)?;
+            }
+            let [internal_id, provider_index, dependency_key_index, dependency_hash, data_index, primary_key, resource_type] =
+                values;
+            let internal_id = get_string(&catalog.internal_ids, internal_id)?;
+            locations.push(ResourceLocation {
+                internal_id: expand_internal_id(&catalog.internal_id_prefixes, internal_id),
+                provider_id: get_string(&catalog.provider_ids, provider_index)?,
+                dependency_key_index: (dependency_key_index >= 0)
+                    .then_some(dependency_key_index as usize),
+                dependency_hash,
+                data: if data_index >= 0 {
+                    Some(read_value(&extra_data, data_index as usize)?)
+                } else {
+                    None
+                },
+                primary_key_index: primary_key as usize,
+                resource_type: catalog
+                    .resource_types
+                    .get(resource_type as usize)
+                    .map(|resource_type| resource_type.class_name.clone()),
+            });
+        }
+
+        Ok(ContentCatalog {
+            locator_id: catalog.locator_id,
+            provider_ids: catalog.provider_ids,
+            keys,
+            key_locations,
+            locations,
+        })
+    }
+
+    /// Read catalog.bin of Addressables 2. Keys without locations are added for primary keys
+    /// and dependency sets which are not keys, like catalog.json have.
+    pub fn from_binary(data: &[u8]) -> ReadResult<Self> {
+        let version = read_i32(data, 4)?;
+        if !(1..=2).contains(&version) {
+            return Err(Error::Other(format!(
+                "unsupported binary content catalog version {version}"
+            )));
+        }
+        let reader = BinaryCatalogReader { data, version };
+        let keys_offset = reader.read_u32(8)?;
+        let locator_id = reader.read_string(reader.read_u32(12)?, None)?;
+
+        let mut catalog = ContentCatalog {
+            locator_id,
+            ..Default::default()
+        };
+        let mut location_indexes = LocationIndexes::default();
+        let mut key_indexes_by_location_set = HashMap::new();
+        let key_data = reader.read_u32_array(keys_offset)?;
+        for key in key_data.chunks_exact(2) {
+            let (key_offset, location_set_offset) = (key[0], key[1]);
+            key_indexes_by_location_set
+                .entry(location_set_offset)
+                .or_insert(catalog.keys.len());
+            catalog.keys.push(reader.read_object(key_offset)?);
+            catalog
+                .key_locations
+                .push(location_indexes.get_all(&reader.read_u32_array(location_set_offset)?));
+        }
+
+        let mut string_key_indexes = HashMap::new();
+        for (key_index, key) in catalog.keys.iter().enumerate() {
+            if let CatalogValue::String(key) = key {
+                string_key_indexes.entry(key.clone()).or_insert(key_index);
+            }
+        }
+        let mut provider_indexes = HashMap::new();
+        // dependencies add locations while they are read.
+        let mut index = 0;
+        while let Some(&offset) = location_indexes.offsets.get(index) {
+            let offset = offset as usize;
+            let mut values = [0u32; 7];
+            for (i, value) in values.iter_mut().enumerate() {
+                *value = reader.read_u32(offset + i * 4)?;
+            }
+            let [primary_key, internal_id, provider_id, dependency_set, dependency_hash, data, resource_type] =
+                values;
+            let primary_key = reader.read_string(primary_key, Some('/'))?;
+            let provider_id = reader.read_string(provider_id, Some('.'))?;
+            if !provider_indexes.contains_key(&provider_id) {
+                provider_indexes.insert(provider_id.clone(), catalog.provider_ids.len());
+                catalog.provider_ids.push(provider_id.clone());
+            }
+            let dependency_key_index = if dependency_set == NULL_OFFSET {
+                None
+            } else if let Some(key_index) = key_indexes_by_location_set.get(&dependency_set) {
+                Some(*key_index)
+            } else {
+                let key_index = catalog.keys.len();
+                key_indexes_by_location_set.insert(dependency_set, key_index);
+                catalog
+                    .keys
+                    .push(CatalogValue::Int32(dependency_hash as i32));
+                catalog
+                    .key_locations
+                    .push(location_indexes.get_all(&reader.read_u32_array(dependency_set)?));
+                Some(key_index)
+            };
+            let primary_key_index = match string_key_indexes.get(&primary_key) {
+                Some(key_index) => *key_index,
+                None => {
+                    let key_index = catalog.keys.len();
+                    string_key_indexes.insert(primary_key.clone(), key_index);
+                    catalog.keys.push(CatalogValue::String(primary_key));
+                    catalog.key_locations.push(vec![index]);
+                    key_index
+                }
+            };
+            catalog.locations.push(ResourceLocation {
+                internal_id: reader.read_string(internal_id, Some('/'))?,
+                provider_id,
+                dependency_key_index,
+                dependency_hash: dependency_hash as i32,
+                data: if data == NULL_OFFSET {
+                    None
+                } else {
+                    Some(reader.read_object(data)?)
+                },
+                primary_key_index,
+                resource_type: if resource_type == NULL_OFFSET {
+                    None
+                } else {
+                    Some(reader.read_type(resource_type)?.1)
+                },
+            });
+            index += 1;
+        }
+        Ok(catalog)
+    }
+
+    /// Addresses, labels, guids and dependency keys.
+    pub fn get_keys(&self) -> &[CatalogValue] {
+        &self.keys
+    }
+
+    pub fn get_locations(&self) -> &[ResourceLocation] {
+        &self.locations
+    }
+
+    /// Locations of an address, label or guid.
+    pub fn get_locations_by_key(&self, key: &str) -> Vec<&ResourceLocation> {
+        self.keys
+            .iter()
+            .zip(&self.key_locations)
+            .filter(|(catalog_key, _)| catalog_key.to_string() == key)
+            .flat_map(|(_, entries)| entries.iter())
+            .filter_map(|entry| self.locations.get(*entry))
+            .collect()
+    }
+
+    pub fn get_primary_key(&self, location: &ResourceLocation) -> Option<&CatalogValue> {
+        self.keys.get(location.primary_key_index)
+    }
+
+    /// Locations loaded before location, the bundle of an asset and the bundles it depend on.
+    pub fn get_dependencies(&self, location: &ResourceLocation) -> Vec<&ResourceLocation> {
+        location
+            .dependency_key_index
+            .and_then(|key_index| self.key_locations.get(key_index))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| self.locations.get(*entry))
+            .collect()
+    }
+
+    /// File names of the bundles location is loaded from.
+    pub fn get_bundle_file_names(&self, location: &ResourceLocation) -> Vec<&str> {
+        self.get_dependencies(location)
+            .into_iter()
+            .filter_map(|dependency| dependency.get_bundle_file_name())
+            .collect()
+    }
+
+    /// Address of every asset location by its m_Container name.
+    pub fn get_addresses_by_container_name(&self) -> BTreeMap<String, Vec<String>> {
+        let mut addresses: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for location in &self.locations {
+            if let (Some(container_name), Some(address)) = (
+                location.get_container_name(),
+                self.get_primary_key(location),
+            ) {
+                let address = address.to_string();
+                let container_addresses = addresses.entry(container_name).or_default();
+                if !container_addresses.contains(&address) {
+                    container_addresses.push(address);
+                }
+            }
+        }
+        addresses
+    }
+
+    /// Objects of the assets an address or label refer to, their bundles must be added to the viewer.
+    pub fn get_container_entries<'a>(
+        &self,
+        viewer: &'a UnityAssetViewer,
+        key: &str,
+    ) -> Vec<ContainerEntry<'a>> {
+        let mut seen_container_names = HashSet::new();
+        let container_names: Vec<_> = self
+            .get_locations_by_key(key)
+            .into_iter()
+            .filter_map(|location| location.get_container_name())
+            .filter(|container_name| seen_container_names.insert(container_name.clone()))
+            .collect();
+        container_names
+            .iter()
+            .flat_map(|container_name| viewer.get_container_entries(container_name))
+            .collect()
+    }
+}
+
+/// Magic of catalog.bin, the first i32 of its header.
+const BINARY_CATALOG_MAGIC: u32 = 0x0de38942;
+/// Offset of a string in catalog.bin may have these flags, the rest is the offset.
+const UNICODE_STRING_FLAG: u32 = 0x8000_0000;
+const DYNAMIC_STRING_FLAG: u32 = 0x4000_0000;
+const OFFSET_MASK: u32 = 0x3fff_ffff;
+const NULL_OFFSET: u32 = u32::MAX;
+
+/// Index of each location of a binary catalog by its offset, in the order they are found.
+#[derive(Default)]
+struct LocationIndexes {
+    indexes: HashMap<u32, usize>,
+    offsets: Vec<u32>,
+}
+
+impl LocationIndexes {
+    fn get_all(&mut self, offsets: &[u32]) -> Vec<usize> {
+        offsets
+            .iter()
+            .map(|offset| {
+                *self.indexes.entry(*offset).or_insert_with(|| {
+                    self.offsets.push(*offset);
+                    self.offsets.len() - 1
+                })
+            })
+            .collect()
+    }
+}
+
+/// Objects of catalog.bin are found by offset, arrays and strings have their byte size before them.
+struct BinaryCatalogReader<'a> {
+    data: &'a [u8],
+    version: i32,
+}
+
+impl BinaryCatalogReader<'_> {
+    fn read_u32(&self, offset: usize) -> ReadResult<u32> {
+        Ok(read_i32(self.data, offset)? as u32)
+    }
+
+    fn read_size(&self, offset: usize) -> ReadResult<usize> {
+        let size_offset = offset.checked_sub(4).ok_or_else(|| {
+            Error::Other(format!(
+                "content catalog data out of range. offset : {offset}"
+            ))
+        })?;
+        Ok(self.read_u32(size_offset)? as usize)
+    }
+
+    fn read_u32_array(&self, offset: u32) -> ReadResult<Vec<u32>> {
+        let offset = offset as usize;
+        let size = self.read_size(offset)?;
+        read_bytes(self.data, offset, size - size % 4)?
+            .chunks_exact(4)
+            .map(|value| Ok(u32::from_le_bytes(value.try_into().unwrap())))
+            .collect()
+    }
+
+    /// Dynamic strings are parts joined by sep, stored as a list of (part, next) from the last part
+    /// since version 2. Other strings are ascii or utf-16.
+    fn read_string(&self, encoded_offset: u32, sep: Option<char>) -> ReadResult<String> {
+        let offset = (encoded_offset & OFFSET_MASK) as usize;
+        if let (Some(sep), true) = (sep, encoded_offset & DYNAMIC_STRING_FLAG != 0) {
+            let mut parts = Vec::new();
+            let mut part_offset = offset;
+            loop {
+                if parts.len() > self.data.len() / 8 {
+                    return Err(Error::Other(
+                        "content catalog dynamic string has a loop".to_owned(),
+                    ));
+                }
+                parts.push(self.read_string(self.read_u32(part_offset)?, None)?);
+                match self.read_u32(part_offset + 4)? {
+                    NULL_OFFSET => break,
+                    next_offset => part_offset = next_offset as usize,
+                }
+            }
+            if self.version > 1 {
+                parts.reverse();
+            }
+            return Ok(parts.join(&sep.to_string()));
+        }
+        let size = self.read_size(offset)?;
+        if encoded_offset & UNICODE_STRING_FLAG != 0 {
+            read_unicode(self.data, offset, size)
+        } else {
+            read_ascii(self.data, offset, size)
+        }
+    }
+
+    /// Assembly and class name of a type.
+    fn read_type(&self, offset: u32) -> ReadResult<(String, String)> {
+        let offset = offset as usize;
+        Ok((
+            self.read_string(self.read_u32(offset)?, Some('.'))?,
+            self.read_string(self.read_u32(offset + 4)?, Some('.'))?,
+        ))
+    }
+
+    /// Key or extra data, stored as offsets of its type and of its value.
+    fn read_object(&self, offset: u32) -> ReadResult<CatalogValue> {
+        let offset = offset as usize;
+        let (assembly_name, class_name) = self.read_type(self.read_u32(offset)?)?;
+        let object_offset = self.read_u32(offset + 4)?;
+        let value_offset = object_offset as usize;
+        Ok(match class_name.as_str() {
+            "System.Int32" => CatalogValue::Int32(read_i32(self.data, value_offset)?),
+            "System.Int64" => CatalogValue::Int64(i64::from_le_bytes(
+                read_bytes(self.data, value_offset, 8)?.try_into().unwrap(),
+            )),
+            "System.Boolean" => CatalogValue::Bool(read_bytes(self.data, value_offset, 1)?[0] != 0),
+            "System.String" => CatalogValue::String(self.read_string(object_offset, Some('/'))?),
+            "UnityEngine.Hash128" => {
+                CatalogValue::Hash128(encode_hex(read_bytes(self.data, value_offset, 16)?))
+            }
+            "UnityEngine.ResourceManagement.ResourceProviders.AssetBundleRequestOptions" => {
+                CatalogValue::Json {
+                    json: self.read_asset_bundle_request_options(value_offset)?,
+                    assembly_name,
+                    class_name,
+                }
+            }
+            _ => {
+                return Err(Error::Other(format!(
+                    "unsupported content catalog object type {class_name}"
+                )))
+            }
+        })
+    }
+
+    /// Written like the JsonUtility json of catalog.json.
+    fn read_asset_bundle_request_options(&self, offset: usize) -> ReadResult<String> {
+        let hash = encode_hex(read_bytes(self.data, self.read_u32(offset)? as usize, 16)?);
+        let bundle_name = self.read_string(self.read_u32(offset + 4)?, Some('_'))?;
+        let crc = self.read_u32(offset + 8)?;
+        let bundle_size = self.read_u32(offset + 12)?;
+        let common_info = self.read_u32(offset + 16)? as usize;
+        let common_info = read_bytes(self.data, common_info, 8)?;
+        let timeout = i16::from_le_bytes([common_info[0], common_info[1]]);
+        let flags = u32::from_le_bytes(common_info[4..8].try_into().unwrap());
+        Ok(serde_json::json!({
+            "m_Hash": hash,
+            "m_Crc": crc,
+            "m_Timeout": timeout,
+            "m_ChunkedTransfer": flags & 2 != 0,
+            "m_RedirectLimit": common_info[2],
+            "m_RetryCount": common_info[3],
+            "m_BundleName": bundle_name,
+            "m_AssetLoadMode": flags & 1,
+            "m_BundleSize": bundle_size,
+            "m_UseCrcForCachedBundles": flags & 4 != 0,
+            "m_UseUWRForLocalBundles": flags & 8 != 0,
+            "m_ClearOtherCachedVersionsWhenLoaded": flags & 16 != 0,
+        })
+        .to_string())
+    }
+}
+
+fn encode_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Internal ids may start with "<prefix index>#" to share common prefixes.
+fn expand_internal_id(prefixes: &[String], internal_id: String) -> String {
+    if let Some((index, rest)) = internal_id.rsplit_once('#') {
+        if let Some(prefix) = index
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| prefixes.get(index))
+        {
+            return format!("{prefix}{rest}");
+        }
+    }
+    internal_id
+}
+
+fn read_bytes(data: &[u8], offset: usize, size: usize) -> ReadResult<&[u8]> {
+    data.get(offset..offset + size).ok_or_else(|| {
+        Error::Other(format!(
+            "content catalog data out of range. offset : {offset} size : {size}"
+        ))
+    })
+}
+
+fn read_i32(data: &[u8], offset: usize) -> ReadResult<i32> {
+    Ok(i32::from_le_bytes(
+        read_bytes(data, offset, 4)?.try_into().unwrap(),
+    ))
+}
+
+fn read_ascii(data: &[u8], offset: usize, size: usize) -> ReadResult<String> {
+    Ok(String::from_utf8_lossy(read_bytes(data, offset, size)?).to_string())
+}
+
+fn read_unicode(data: &[u8], offset: usize, size: usize) -> ReadResult<String> {
+    let units: Vec<u16> = read_bytes(data, offset, size)?
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .collect();
+    Ok(String::from_utf16_lossy(&units))
+}
+
+fn read_value(data: &[u8], offset: usize) -> ReadResult<CatalogValue> {
+    let object_type = read_bytes(data, offset, 1)?[0];
+    let offset = offset + 1;
+    Ok(match object_type {
+        0 => CatalogValue::String(read_ascii(
+            data,
+            offset + 4,
+            read_i32(data, offset)? as usize,
+        )?),
+        1 => CatalogValue::String(read_unicode(
+            data,
+            offset + 4,
+            read_i32(data, offset)? as usize,
+        )?),
+        2 => CatalogValue::UInt16(u16::from_le_bytes(
+            read_bytes(data, offset, 2)?.try_into().unwrap(),
+        )),
+        3 => CatalogValue::UInt32(read_i32(data, offset)? as u32),
+        4 => CatalogValue::Int32(read_i32(data, offset)?),
+        5 | 6 => {
+            let size = read_bytes(data, offset, 1)?[0] as usize;
+            let value = read_ascii(data, offset + 1, size)?;
+            if object_type == 5 {
+                CatalogValue::Hash128(value)
+            } else {
+                CatalogValue::Type(value)
+            }
+        }
+        7 => {
+            let assembly_name_size = read_bytes(data, offset, 1)?[0] as usize;
+            let assembly_name = read_ascii(data, offset + 1, assembly_name_size)?;
+            let offset = offset + 1 + assembly_name_size;
+            let class_name_size = read_bytes(data, offset, 1)?[0] as usize;
+            let class_name = read_ascii(data, offset + 1, class_name_size)?;
+            let offset = offset + 1 + class_name_size;
+            let json = read_unicode(data, offset + 4, read_i32(data, offset)? as usize)?;
+            CatalogValue::Json {
+                assembly_name,
+                class_name,
+                json,
+            }
+        }
+        object_type => {
+            return Err(Error::Other(format!(
+                "unknown content catalog object type {object_type}"
+            )))
+        }
+    })
+}
+
+fn decode_base64(text: &str) -> ReadResult<Vec<u8>> {
+    let mut data = Vec::with_capacity(text.len() / 4 * 3);
+    let mut bits = 0u32;
+    let mut bit_count = 0;
+    for c in text.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' | b'\r' | b'\n' => continue,
+            c => {
+                return Err(Error::Other(format!(
+                    "invalid base64 character {:?}",
+                    c as char
+                )))
+            }
+        };
+        bits = (bits << 6) | value as u32;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            data.push((bits >> bit_count) as u8);
+        }
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, sync::Arc};
+
+    use super::*;
+    use crate::{
+        asset_source::MemorySource,
+        test_data::{PICKUP, PLAYER, PREFABS_BUNDLE},
+    };
+
+    fn encode_base64(data: &[u8]) -> String {
+        const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut text = String::new();
+        for chunk in data.chunks(3) {
+            let bits = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |bits, (i, b)| bits | (*b as u32) << (16 - i * 8));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    text.push(CHARS[(bits >> (18 - i * 6)) as usize & 63] as char);
+                } else {
+                    text.push('=');
+                }
+            }
+        }
+        text
+    }
+
+    fn ascii_key(key: &str) -> Vec<u8> {
+        let mut data = vec![0];
+        data.extend((key.len() as i32).to_le_bytes());
+        data.extend(key.as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_content_catalog() {
+        // key 0 : address of the asset, key 1 : the bundle, key 2 : dependency key of the asset.
+        let mut key_data = 3i32.to_le_bytes().to_vec();
+        let mut key_offsets = Vec::new();
+        for key in [
+            ascii_key("Hero"),
+            ascii_key("hero_bundle"),
+            vec![4, 7, 0, 0, 0],
+        ] {
+            key_offsets.push(key_data.len() as i32);
+            key_data.extend(key);
+        }
+        let mut bucket_data = 3i32.to_le_bytes().to_vec();
+        for (key_offset, entry) in key_offsets.iter().zip([0, 1, 1]) {
+            for value in [*key_offset, 1, entry] {
+                bucket_data.extend(value.to_le_bytes());
+            }
+        }
+        let mut entry_data = 2i32.to_le_bytes().to_vec();
+        for value in [0i32, 0, 2, 0, -1, 0, 0, 1, 1, -1, 0, -1, 1, -1] {
+            entry_data.extend(value.to_le_bytes());
+        }
+        let json = format!(
+            r#"{{"m_LocatorId":"AddressablesMainContentCatalog",
+            "m_ProviderIds":["UnityEngine.ResourceManagement.ResourceProviders.BundledAssetProvider",
+                "UnityEngine.ResourceManagement.ResourceProviders.AssetBundleProvider"],
+            "m_InternalIds":["Assets/Prefabs/Hero.prefab","0#/hero_bundle.bundle"],
+            "m_InternalIdPrefixes":["{{UnityEngine.AddressableAssets.Addressables.RuntimePath}}/Android"],
+            "m_KeyDataString":"{}","m_BucketDataString":"{}","m_EntryDataString":"{}",
+            "m_ExtraDataString":"",
+            "m_resourceTypes":[{{"m_AssemblyName":"UnityEngine.CoreModule","m_ClassName":"UnityEngine.GameObject"}}]}}"#,
+            encode_base64(&key_data),
+            encode_base64(&bucket_data),
+            encode_base64(&entry_data)
+        );
+        let catalog = ContentCatalog::read(&mut json.as_bytes()).unwrap();
+
+        assert_eq!(catalog.get_keys()[2], CatalogValue::Int32(7));
+        let hero = catalog.get_locations_by_key("Hero");
+        assert_eq!(hero.len(), 1);
+        assert_eq!(
+            hero[0].get_container_name().as_deref(),
+            Some("assets/prefabs/hero.prefab")
+        );
+        assert_eq!(
+            hero[0].resource_type.as_deref(),
+            Some("UnityEngine.GameObject")
+        );
+        assert_eq!(
+            catalog.get_dependencies(hero[0])[0].internal_id,
+            "{UnityEngine.AddressableAssets.Addressables.RuntimePath}/Android/hero_bundle.bundle"
+        );
+        assert_eq!(
+            catalog.get_bundle_file_names(hero[0]),
+            ["hero_bundle.bundle"]
+        );
+        assert_eq!(
+            catalog.get_addresses_by_container_name()["assets/prefabs/hero.prefab"],
+            ["Hero"]
+        );
+        assert!(ContentCatalog::read(&mut &[0x42u8, 0x89, 0xe3, 0x0d][..]).is_err());
+    }
+
+    /// Write objects like the BinaryStorageBuffer of Addressables 2.
+    struct BinaryCatalogWriter {
+        data: Vec<u8>,
+    }
+
+    impl BinaryCatalogWriter {
+        fn new() -> Self {
+            let mut data = BINARY_CATALOG_MAGIC.to_le_bytes().to_vec();
+            data.extend(2i32.to_le_bytes());
+            data.resize(32, 0xff);
+            BinaryCatalogWriter { data }
+        }
+
+        fn buffer(&mut self, bytes: &[u8]) -> u32 {
+            self.data.extend((bytes.len() as u32).to_le_bytes());
+            let offset = self.data.len() as u32;
+            self.data.extend(bytes);
+            self.data.resize(self.data.len().next_multiple_of(4), 0);
+            offset
+        }
+
+        fn values(&mut self, values: &[u32]) -> u32 {
+            let bytes: Vec<u8> = values
+                .iter()
+                .flat_map(|value| value.to_le_bytes())
+                .collect();
+            self.buffer(&bytes)
+        }
+
+        fn unicode_string(&mut self, text: &str) -> u32 {
+            let bytes: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+            self.buffer(&bytes) | UNICODE_STRING_FLAG
+        }
+
+        /// Parts are linked from the last one, non ascii parts are utf-16.
+        fn dynamic_string(&mut self, text: &str, sep: char) -> u32 {
+            let mut next_offset = NULL_OFFSET;
+            for part in text.split(sep) {
+                let part_offset = if part.is_ascii() {
+                    self.buffer(part.as_bytes())
+                } else {
+                    self.unicode_string(part)
+                };
+                next_offset = self.values(&[part_offset, next_offset]);
+            }
+            next_offset | DYNAMIC_STRING_FLAG
+        }
+
+        fn type_offset(&mut self, assembly_name: &str, class_name: &str) -> u32 {
+            let assembly_name = self.dynamic_string(assembly_name, '.');
+            let class_name = self.dynamic_string(class_name, '.');
+            self.values(&[assembly_name, class_name])
+        }
+
+        fn string_object(&mut self, text: &str) -> u32 {
+            let type_offset = self.type_offset("mscorlib", "System.String");
+            let text = self.dynamic_string(text, '/');
+            self.values(&[type_offset, text])
+        }
+
+        fn location(
+            &mut self,
+            primary_key: &str,
+            internal_id: &str,
+            provider_id: &str,
+            dependency_set: u32,
+            data: u32,
+            resource_type: u32,
+        ) -> u32 {
+            let values = [
+                self.dynamic_string(primary_key, '/'),
+                self.dynamic_string(internal_id, '/'),
+                self.dynamic_string(provider_id, '.'),
+                dependency_set,
+                7,
+                data,
+                resource_type,
+            ];
+            self.values(&values)
+        }
+    }
+
+    #[test]
+    fn test_binary_content_catalog() {
+        let mut writer = BinaryCatalogWriter::new();
+        let hash = writer.buffer(&(0..16).collect::<Vec<u8>>());
+        let bundle_name = writer.dynamic_string("hero_bundle_0f1e", '_');
+        let common_info = writer.buffer(&[0, 0, 32, 3, 4 | 1, 0, 0, 0]);
+        let options = writer.values(&[hash, bundle_name, 1234, 5678, common_info]);
+        let options_type = writer.type_offset(
+            "Unity.ResourceManager, Version=0.0.0.0",
+            "UnityEngine.ResourceManagement.ResourceProviders.AssetBundleRequestOptions",
+        );
+        let options = writer.values(&[options_type, options]);
+        let bundle = writer.location(
+            "hero_bundle",
+            "{UnityEngine.AddressableAssets.Addressables.RuntimePath}/Android/hero_bündle.bundle",
+            "UnityEngine.ResourceManagement.ResourceProviders.AssetBundleProvider",
+            NULL_OFFSET,
+            options,
+            NULL_OFFSET,
+        );
+        let dependency_set = writer.values(&[bundle]);
+        let game_object = writer.type_offset("UnityEngine.CoreModule", "UnityEngine.GameObject");
+        let [player, pickup, player_root] = [
+            ("Player", "Assets/Prefabs/Player.prefab"),
+            ("Pickup", "Assets/Prefabs/Pickup.prefab"),
+            ("PlayerRoot", "Assets/Prefabs/Player.prefab[Player]"),
+        ]
+        .map(|(address, internal_id)| {
+            writer.location(
+                address,
+                internal_id,
+                "UnityEngine.ResourceManagement.ResourceProviders.BundledAssetProvider",
+                dependency_set,
+                NULL_OFFSET,
+                game_object,
+            )
+        });
+
+        let mut key_data = Vec::new();
+        for (key, locations) in [
+            ("Player", vec![player]),
+            ("Pickup", vec![pickup]),
+            ("hero_bundle", vec![bundle]),
+            ("prefabs", vec![player, pickup, player_root]),
+        ] {
+            key_data.push(writer.string_object(key));
+            key_data.push(writer.values(&locations));
+        }
+        let hash_type = writer.type_offset("UnityEngine.CoreModule", "UnityEngine.Hash128");
+        key_data.push(writer.values(&[hash_type, hash]));
+        key_data.push(writer.values(&[player]));
+        let keys_offset = writer.values(&key_data);
+        let locator_id = writer.buffer(b"AddressablesMainContentCatalog");
+        writer.data[8..12].copy_from_slice(&keys_offset.to_le_bytes());
+        writer.data[12..16].copy_from_slice(&locator_id.to_le_bytes());
+
+        let catalog = ContentCatalog::read(&mut writer.data.as_slice()).unwrap();
+        assert_eq!(catalog.locator_id, "AddressablesMainContentCatalog");
+        assert_eq!(catalog.provider_ids.len(), 2);
+        let hash = "000102030405060708090a0b0c0d0e0f";
+        assert_eq!(
+            catalog.get_keys()[4],
+            CatalogValue::Hash128(hash.to_owned())
+        );
+        // the dependency set is not a key, it is added like catalog.json do.
+        assert_eq!(catalog.get_keys()[5], CatalogValue::Int32(7));
+        assert_eq!(
+            catalog.get_keys()[6],
+            CatalogValue::String("PlayerRoot".to_owned())
+        );
+
+        let player = catalog.get_locations_by_key(hash);
+        assert_eq!(player.len(), 1);
+        assert_eq!(
+            player[0].get_container_name().as_deref(),
+            Some("assets/prefabs/player.prefab")
+        );
+        assert_eq!(
+            player[0].resource_type.as_deref(),
+            Some("UnityEngine.GameObject")
+        );
+        assert_eq!(
+            catalog.get_primary_key(player[0]),
+            Some(&CatalogValue::String("Player".to_owned()))
+        );
+        assert_eq!(
+            catalog.get_bundle_file_names(player[0]),
+            ["hero_bündle.bundle"]
+        );
+        let bundle = catalog.get_dependencies(player[0])[0];
+        let Some(CatalogValue::Json {
+            assembly_name,
+            json,
+            ..
+        }) = &bundle.data
+        else {
+            panic!("{:?}", bundle.data);
+        };
+        assert_eq!(assembly_name, "Unity.ResourceManager, Version=0.0.0.0");
+        let options: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(options["m_Hash"], hash);
+        assert_eq!(options["m_BundleName"], "hero_bundle_0f1e");
+        assert_eq!(options["m_Crc"], 1234);
+        assert_eq!(options["m_BundleSize"], 5678);
+        assert_eq!(options["m_RetryCount"], 3);
+        assert_eq!(options["m_AssetLoadMode"], 1);
+        assert_eq!(options["m_UseCrcForCachedBundles"], true);
+
+        // the sub asset of the player has the same container.
+        let mut viewer = UnityAssetViewer::new();
+        let source = MemorySource::new();
+        source.insert("hero_bundle", PREFABS_BUNDLE.to_vec());
+        viewer
+            .add_asset_source(Arc::new(source), "", false)
+            .unwrap();
+        let path_ids: Vec<_> = catalog
+            .get_container_entries(&viewer, "prefabs")
+            .iter()
+            .map(|entry| entry.get_path_id().unwrap())
+            .collect();
+        assert_eq!(path_ids, [PLAYER, PICKUP]);
+
+        let mut data = writer.data.clone();
+        data[keys_offset as usize - 4..keys_offset as usize]
+            .copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(ContentCatalog::read(&mut Cursor::new(data)).is_err());
+    }
+}
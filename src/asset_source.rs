@@ -0,0 +1,311 @@
+use std::{
+    collections::BTreeMap,
+    fs::OpenOptions,
+    io::{BufReader, Cursor, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    time::SystemTime,
+};
+
+use walkdir::WalkDir;
+
+use crate::{
+    error::{Error, ReadResult},
+    unityfs::{UnityFS, UnityResource},
+    until::file_pool::{FilePool, PooledFile},
+    zip_package::ZipPackage,
+};
+
+/// Where the viewer read files from.
+/// Paths are relative to the root of the source and separated by '/'.
+pub trait AssetSource: Send + Sync {
+    /// Paths of all files in the source.
+    fn list(&self) -> ReadResult<Vec<String>>;
+
+    fn open(&self, path: &str) -> ReadResult<Box<dyn UnityResource + Send + Sync>>;
+
+    fn exists(&self, path: &str) -> bool;
+
+    /// Join a path relative to dir, like a stream data path relative to its serialized file.
+    fn join(&self, dir: &str, path: &str) -> String {
+        join_path(dir, path)
+    }
+
+    /// Read at most size bytes from the start of a file.
+    fn read_header(&self, path: &str, size: usize) -> ReadResult<Vec<u8>> {
+        let mut header = Vec::with_capacity(size);
+        self.open(path)?
+            .take(size as u64)
+            .read_to_end(&mut header)?;
+        Ok(header)
+    }
+
+    fn get_file_size(&self, path: &str) -> ReadResult<u64> {
+        Ok(self.open(path)?.seek(SeekFrom::End(0))?)
+    }
+
+    /// Modified time of a file, files without it are not saved to a viewer index.
+    fn get_modified_time(&self, _path: &str) -> Option<SystemTime> {
+        None
+    }
+
+    /// Path of a file shown in load reports.
+    fn get_display_path(&self, path: &str) -> PathBuf {
+        PathBuf::from(path)
+    }
+}
+
+/// Join path to dir, "." and ".." are resolved.
+pub fn join_path(dir: &str, path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in dir.split('/').chain(path.split('/')) {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Open path when the source has it.
+pub fn open_if_exists(
+    asset_source: &dyn AssetSource,
+    path: &str,
+) -> Option<Box<dyn UnityResource + Send + Sync>> {
+    if asset_source.exists(path) {
+        asset_source.open(path).ok()
+    } else {
+        None
+    }
+}
+
+/// Files under a dir of the real file system.
+#[derive(Debug)]
+pub struct DirectorySource {
+    root: PathBuf,
+    file_pool: Option<Arc<Mutex<FilePool>>>,
+}
+
+impl DirectorySource {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        DirectorySource {
+            root: root.as_ref().to_path_buf(),
+            file_pool: None,
+        }
+    }
+
+    /// Files opened by the source are kept open by the pool.
+    pub fn new_pooled<P: AsRef<Path>>(root: P, file_pool: Arc<Mutex<FilePool>>) -> Self {
+        DirectorySource {
+            root: root.as_ref().to_path_buf(),
+            file_pool: Some(file_pool),
+        }
+    }
+
+    pub fn get_root(&self) -> &Path {
+        &self.root
+    }
+
+    fn get_full_path(&self, path: &str) -> PathBuf {
+        self.root.join(path)
+    }
+}
+
+impl AssetSource for DirectorySource {
+    fn list(&self) -> ReadResult<Vec<String>> {
+        let mut paths = Vec::new();
+        for entry in WalkDir::new(&self.root) {
+            let entry = entry.map_err(std::io::Error::from)?;
+            if entry.file_type().is_file() {
+                if let Ok(path) = entry.path().strip_prefix(&self.root) {
+                    let segments: Vec<_> = path
+                        .components()
+                        .map(|component| component.as_os_str().to_string_lossy())
+                        .collect();
+                    paths.push(segments.join("/"));
+                }
+            }
+        }
+        Ok(paths)
+    }
+
+    fn open(&self, path: &str) -> ReadResult<Box<dyn UnityResource + Send + Sync>> {
+        let path = self.get_full_path(path);
+        Ok(if let Some(file_pool) = &self.file_pool {
+            Box::new(BufReader::new(PooledFile::open(file_pool, path)?))
+        } else {
+            Box::new(BufReader::new(OpenOptions::new().read(true).open(path)?))
+        })
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.get_full_path(path).is_file()
+    }
+
+    fn get_file_size(&self, path: &str) -> ReadResult<u64> {
+        Ok(std::fs::metadata(self.get_full_path(path))?.len())
+    }
+
+    fn get_modified_time(&self, path: &str) -> Option<SystemTime> {
+        std::fs::metadata(self.get_full_path(path))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    fn get_display_path(&self, path: &str) -> PathBuf {
+        self.get_full_path(path)
+    }
+}
+
+/// Data and modified time of a file of a `MemorySource`.
+type MemoryFile = (Arc<[u8]>, SystemTime);
+
+/// Files kept in memory, the modified time of a file is when it was inserted.
+/// Files can be replaced or removed while a viewer use the source.
+#[derive(Debug, Default)]
+pub struct MemorySource {
+    files: RwLock<BTreeMap<String, MemoryFile>>,
+}
+
+impl MemorySource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, path: &str, data: Vec<u8>) {
+        if let Ok(mut files) = self.files.write() {
+            files.insert(join_path("", path), (data.into(), SystemTime::now()));
+        }
+    }
+
+    pub fn remove(&self, path: &str) -> bool {
+        self.files
+            .write()
+            .is_ok_and(|mut files| files.remove(&join_path("", path)).is_some())
+    }
+
+    fn get_file(&self, path: &str) -> ReadResult<MemoryFile> {
+        self.files
+            .read()
+            .map_err(|e| Error::Other(e.to_string()))?
+            .get(path)
+            .cloned()
+            .ok_or_else(|| Error::ResourceNotFound(path.to_owned()))
+    }
+}
+
+impl AssetSource for MemorySource {
+    fn list(&self) -> ReadResult<Vec<String>> {
+        Ok(self
+            .files
+            .read()
+            .map_err(|e| Error::Other(e.to_string()))?
+            .keys()
+            .cloned()
+            .collect())
+    }
+
+    fn open(&self, path: &str) -> ReadResult<Box<dyn UnityResource + Send + Sync>> {
+        let (data, _) = self.get_file(path)?;
+        Ok(Box::new(Cursor::new(data)))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.get_file(path).is_ok()
+    }
+
+    fn get_file_size(&self, path: &str) -> ReadResult<u64> {
+        Ok(self.get_file(path)?.0.len() as u64)
+    }
+
+    fn get_modified_time(&self, path: &str) -> Option<SystemTime> {
+        self.get_file(path).ok().map(|(_, modified)| modified)
+    }
+}
+
+impl AssetSource for ZipPackage {
+    fn list(&self) -> ReadResult<Vec<String>> {
+        Ok(self.get_file_paths())
+    }
+
+    fn open(&self, path: &str) -> ReadResult<Box<dyn UnityResource + Send + Sync>> {
+        self.get_file_reader_by_path(path)
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.get_file_size(path).is_some()
+    }
+
+    fn read_header(&self, path: &str, size: usize) -> ReadResult<Vec<u8>> {
+        self.read_file_header(path, size)
+    }
+
+    fn get_file_size(&self, path: &str) -> ReadResult<u64> {
+        ZipPackage::get_file_size(self, path)
+            .ok_or_else(|| Error::ResourceNotFound(path.to_owned()))
+    }
+}
+
+/// Nodes of a bundle.
+impl AssetSource for UnityFS {
+    fn list(&self) -> ReadResult<Vec<String>> {
+        Ok(self.get_file_paths())
+    }
+
+    fn open(&self, path: &str) -> ReadResult<Box<dyn UnityResource + Send + Sync>> {
+        match self.get_file_reader_by_path(&path.to_owned()) {
+            Some(node) => Ok(Box::new(node)),
+            None => Err(Error::ResourceNotFound(path.to_owned())),
+        }
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.get_file_paths().iter().any(|p| p == path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_report::FileKind, unity_asset_view::UnityAssetViewer};
+
+    #[test]
+    fn test_memory_source() {
+        assert_eq!(
+            join_path("assets/bin/Data", "../x.resS"),
+            "assets/bin/x.resS"
+        );
+        assert_eq!(join_path("", "./Data/level0"), "Data/level0");
+
+        let source = MemorySource::new();
+        source.insert("/Data/level0", vec![1, 2, 3]);
+        source.insert("Data/level0.resS", vec![4; 16]);
+        assert_eq!(source.list().unwrap(), ["Data/level0", "Data/level0.resS"]);
+        assert!(source.exists(&source.join("Data", "level0")));
+        assert!(!source.exists("Data/level1"));
+        assert_eq!(source.read_header("Data/level0.resS", 4).unwrap(), [4; 4]);
+        assert_eq!(source.get_file_size("Data/level0.resS").unwrap(), 16);
+        assert!(source.get_modified_time("Data/level0").is_some());
+        source.insert("Data/level1", vec![]);
+        assert!(source.remove("Data/level1"));
+        assert!(!source.remove("Data/level1"));
+
+        let mut data = Vec::new();
+        open_if_exists(&source, "Data/level0")
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+        assert_eq!(data, [1, 2, 3]);
+
+        let mut viewer = UnityAssetViewer::new();
+        let report = viewer
+            .add_asset_source(Arc::new(source), "Data", true)
+            .unwrap();
+        let kinds: Vec<_> = report.files.iter().map(|file| file.kind).collect();
+        assert_eq!(kinds, [FileKind::Unknown, FileKind::Resource]);
+        assert!(!report.has_error());
+    }
+}
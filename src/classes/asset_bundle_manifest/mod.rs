@@ -0,0 +1,27 @@
+pub mod type_tree;
+
+use std::collections::BTreeMap;
+
+use crate::{def_unity_class, error::ReadResult};
+
+def_unity_class!(AssetBundleManifest);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetBundleInfo {
+    pub hash: [u8; 16],
+    /// Names of bundles this bundle depend on directly.
+    pub dependencies: Vec<String>,
+}
+
+impl AssetBundleInfo {
+    pub fn get_hash_string(&self) -> String {
+        self.hash.iter().map(|b| format!("{b:02x}")).collect()
+    }
+}
+
+pub trait AssetBundleManifestObject {
+    fn get_asset_bundle_names(&self) -> ReadResult<BTreeMap<i32, String>>;
+    fn get_asset_bundles_with_variant(&self) -> ReadResult<Vec<String>>;
+    /// Bundle name to its hash and dependencies.
+    fn get_asset_bundle_infos(&self) -> ReadResult<BTreeMap<String, AssetBundleInfo>>;
+}
@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+
+use super::{AssetBundleInfo, AssetBundleManifest, AssetBundleManifestObject};
+use crate::{
+    error::{Error, ReadResult},
+    type_tree::{convert::TryCastFrom, TypeTreeObjectRef},
+};
+
+impl AssetBundleManifest<'_> {
+    fn get_asset_bundle_name_list(
+        &self,
+        names: &BTreeMap<i32, String>,
+        path: &str,
+        item: &TypeTreeObjectRef,
+    ) -> ReadResult<Vec<String>> {
+        let mut list = Vec::new();
+        for index in <Vec<TypeTreeObjectRef>>::try_cast_from(item, path)? {
+            let index = i32::try_cast_from(&index, "/Base")?;
+            let name = names
+                .get(&index)
+                .ok_or_else(|| Error::Other(format!("asset bundle index {index} has no name")))?;
+            list.push(name.clone());
+        }
+        Ok(list)
+    }
+}
+
+impl AssetBundleManifestObject for AssetBundleManifest<'_> {
+    fn get_asset_bundle_names(&self) -> ReadResult<BTreeMap<i32, String>> {
+        let mut names = BTreeMap::new();
+        for pair in
+            <Vec<TypeTreeObjectRef>>::try_cast_from(self.inner, "/Base/AssetBundleNames/Array")?
+        {
+            names.insert(
+                i32::try_cast_from(&pair, "/Base/first")?,
+                String::try_cast_from(&pair, "/Base/second")?,
+            );
+        }
+        Ok(names)
+    }
+
+    fn get_asset_bundles_with_variant(&self) -> ReadResult<Vec<String>> {
+        let names = self.get_asset_bundle_names()?;
+        self.get_asset_bundle_name_list(&names, "/Base/AssetBundlesWithVariant/Array", self.inner)
+    }
+
+    fn get_asset_bundle_infos(&self) -> ReadResult<BTreeMap<String, AssetBundleInfo>> {
+        let names = self.get_asset_bundle_names()?;
+        let mut infos = BTreeMap::new();
+        for pair in
+            <Vec<TypeTreeObjectRef>>::try_cast_from(self.inner, "/Base/AssetBundleInfos/Array")?
+        {
+            let index = i32::try_cast_from(&pair, "/Base/first")?;
+            let name = names
+                .get(&index)
+                .ok_or_else(|| Error::Other(format!("asset bundle index {index} has no name")))?;
+            let mut hash = [0u8; 16];
+            for (i, b) in hash.iter_mut().enumerate() {
+                *b = u8::try_cast_from(
+                    &pair,
+                    format!("/Base/second/AssetBundleHash/bytes[{i}]").as_str(),
+                )?;
+            }
+            let dependencies = self.get_asset_bundle_name_list(
+                &names,
+                "/Base/second/AssetBundleDependencies/Array",
+                &pair,
+            )?;
+            infos.insert(name.clone(), AssetBundleInfo { hash, dependencies });
+        }
+        Ok(infos)
+    }
+}
@@ -0,0 +1,63 @@
+use super::{UnityClass, UnityField};
+use crate::type_tree::TypeTreeObjectRef;
+
+#[derive(Debug, UnityClass)]
+pub struct Material<'a> {
+    inner: &'a TypeTreeObjectRef,
+    #[unity(path = "m_Name")]
+    pub name: UnityField<String>,
+    /// PPtr of the Shader.
+    #[unity(path = "m_Shader")]
+    pub shader: UnityField<TypeTreeObjectRef>,
+    #[unity(path = "m_CustomRenderQueue", read = "i32")]
+    pub custom_render_queue: UnityField<i64>,
+    /// m_TexEnvs, m_Floats and m_Colors of the material.
+    #[unity(path = "m_SavedProperties")]
+    pub saved_properties: UnityField<TypeTreeObjectRef>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        classes::p_ptr::{PPtr, PPtrObject},
+        test_data::{read_object, MATERIAL, SPRITES_ASSETS},
+        type_tree::convert::TryCastFrom,
+    };
+
+    #[test]
+    fn test_material() {
+        let object = read_object(SPRITES_ASSETS.to_vec(), MATERIAL);
+        let material = Material::new(&object);
+        assert_eq!(material.get_name().unwrap(), "Outline");
+        assert_eq!(material.get_custom_render_queue().unwrap(), 3000);
+        let shader = material.get_shader().unwrap();
+        let shader = PPtr::new(&shader);
+        assert_eq!(
+            (shader.get_file_id().unwrap(), shader.get_path_id().unwrap()),
+            (2, 46)
+        );
+
+        let saved_properties = material.get_saved_properties().unwrap();
+        // paths of a ref are relative to its field.
+        let property = |path: &str| -> Vec<String> { path.split('/').map(str::to_owned).collect() };
+        assert_eq!(
+            String::try_cast_from(&saved_properties, &property("m_TexEnvs/Array/0/first")[..])
+                .unwrap(),
+            "_MainTex"
+        );
+        assert_eq!(
+            f32::try_cast_from(&saved_properties, &property("m_Floats/Array/0/second")[..])
+                .unwrap(),
+            0.125
+        );
+        assert_eq!(
+            f32::try_cast_from(
+                &saved_properties,
+                &property("m_Colors/Array/0/second/g")[..]
+            )
+            .unwrap(),
+            0.5
+        );
+    }
+}
@@ -1,19 +1,28 @@
 #[cfg(feature = "external-class-handle")]
 pub mod animation_clip;
+pub mod asset_bundle_manifest;
 #[cfg(feature = "external-class-handle")]
 pub mod audio_clip;
+pub mod material;
 #[cfg(feature = "external-class-handle")]
 pub mod mesh;
 #[cfg(feature = "external-class-handle")]
 pub mod named_object;
 pub mod p_ptr;
+pub mod sprite;
 #[cfg(feature = "external-class-handle-texture2d")]
 pub mod texture2d;
 #[cfg(feature = "external-class-handle")]
 pub mod transform;
 
+use std::marker::PhantomData;
+
 use num_enum::TryFromPrimitive;
 
+use crate::{type_tree::TypeTreeObjectRef, until::UnityVersion};
+
+pub use io_unity_derive::UnityClass;
+
 #[macro_export]
 macro_rules! def_unity_class {
     (  $x:ident  ) => {
@@ -54,6 +63,28 @@ pub trait SerializedFileRef {
     fn get_serialized_file_id(&self) -> i64;
 }
 
+/// Field of a `#[derive(UnityClass)]` wrapper, only declare a getter returning `T`.
+pub type UnityField<T> = PhantomData<fn() -> T>;
+
+/// Whether the unity version of the object is in `since..before`.
+/// Object of unknown or broken unity version is in any range.
+#[doc(hidden)]
+pub fn is_unity_version_in(
+    inner: &TypeTreeObjectRef,
+    since: Option<&str>,
+    before: Option<&str>,
+) -> bool {
+    let Some(version) = inner
+        .get_unity_version()
+        .and_then(|version| UnityVersion::from_str(&version).ok())
+    else {
+        return true;
+    };
+    let parse = |version: &str| UnityVersion::from_str(version).ok();
+    since.and_then(parse).is_none_or(|since| version >= since)
+        && before.and_then(parse).is_none_or(|before| version < before)
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, TryFromPrimitive, Hash)]
 #[repr(i32)]
 pub enum ClassIDType {
@@ -430,3 +461,76 @@ pub enum ClassIDType {
     LocalizationAsset = 2083778819,
     ScriptedImporter = 2089858483,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::ReadResult,
+        test_data::{
+            read_game_object_yaml, read_prefabs_object, GAME_OBJECT_YAML, PLAYER, PLAYER_TRANSFORM,
+        },
+    };
+
+    #[derive(UnityClass)]
+    struct TestObject<'a> {
+        inner: &'a TypeTreeObjectRef,
+        #[unity(path = "m_Name")]
+        name: UnityField<String>,
+        #[unity(path = "m_Layer", read = "u32")]
+        layer: UnityField<u8>,
+        #[unity(path = "m_LocalScale/y", before = "2018.1")]
+        #[unity(path = "m_LocalScale/x")]
+        scale: UnityField<f32>,
+        #[unity(path = "m_Missing")]
+        missing: UnityField<i32>,
+    }
+
+    #[test]
+    fn test_derive_unity_class() {
+        let mut object = read_game_object_yaml(GAME_OBJECT_YAML);
+        object.unity_version = Some("2017.4.0f1".to_owned());
+        let object = TypeTreeObjectRef::from(object);
+        let test_object: TestObject = (&object).cast_as();
+        assert_eq!(test_object.get_name().unwrap(), "Hero");
+        assert_eq!(test_object.get_layer().unwrap(), 5);
+        assert_eq!(test_object.get_scale().unwrap(), 2.0);
+        assert!(test_object.get_missing().is_err());
+
+        object.inner().write().unwrap().unity_version = Some("2019.4.0f1".to_owned());
+        assert_eq!(test_object.get_scale().unwrap(), 1.0);
+
+        // an unknown version is in any range, the first path is read.
+        for unity_version in ["", "2019.x"] {
+            object.inner().write().unwrap().unity_version = Some(unity_version.to_owned());
+            assert_eq!(test_object.get_scale().unwrap(), 2.0);
+            assert_eq!(test_object.get_name().unwrap(), "Hero");
+        }
+    }
+
+    fn is_root(father_path_id: i64) -> ReadResult<bool> {
+        Ok(father_path_id == 0)
+    }
+
+    #[derive(UnityClass)]
+    struct TestTransform<'a> {
+        inner: &'a TypeTreeObjectRef,
+        #[unity(path = "m_GameObject/m_PathID")]
+        game_object: UnityField<i64>,
+        #[unity(path = "m_LocalPosition/x", before = "2018.1")]
+        #[unity(path = "m_LocalPosition/z", since = "2018.1", before = "2019.1")]
+        position: UnityField<f32>,
+        #[unity(path = "m_Father/m_PathID", read = "i64", with = "is_root")]
+        is_root: UnityField<bool>,
+    }
+
+    #[test]
+    fn test_derive_unity_class_prefabs_bundle() {
+        let object = read_prefabs_object(PLAYER_TRANSFORM);
+        assert_eq!(object.get_unity_version().unwrap(), "2018.4.36f1");
+        let transform: TestTransform = (&object).cast_as();
+        assert_eq!(transform.get_game_object().unwrap(), PLAYER);
+        assert_eq!(transform.get_position().unwrap(), 3.0);
+        assert!(transform.get_is_root().unwrap());
+    }
+}
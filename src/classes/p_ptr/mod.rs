@@ -55,6 +55,18 @@ pub trait PPtrObject: SerializedFileRef {
     ) -> Result<Option<TypeTreeObject>, Error> {
         let path_id = self.get_path_id()?;
         let serialized_file = self.get_serialized_file(self_serialized_file, viewer)?;
+        if let Some(viewer) = viewer {
+            let serialized_file_id = serialized_file.get_serialized_file_id();
+            if viewer
+                .get_serialized_file_by_id(serialized_file_id)
+                .is_ok_and(|f| std::ptr::eq(f, serialized_file))
+            {
+                return viewer.get_type_tree_object_by_serialized_file_id_and_path_id(
+                    serialized_file_id,
+                    path_id,
+                );
+            }
+        }
         serialized_file.get_tt_object_by_path_id(path_id)
     }
 
@@ -62,10 +74,8 @@ pub trait PPtrObject: SerializedFileRef {
         &self,
         viewer: &UnityAssetViewer,
     ) -> Result<Option<TypeTreeObject>, Error> {
-        let self_serialized_file = viewer
-            .serialized_file_map
-            .get(&self.get_serialized_file_id())
-            .ok_or(Error::SerializedFileNotFound)?;
+        let self_serialized_file =
+            viewer.get_serialized_file_by_id(self.get_serialized_file_id())?;
         self.get_type_tree_object(self_serialized_file, Some(viewer))
     }
 }
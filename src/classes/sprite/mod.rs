@@ -0,0 +1,68 @@
+use super::{UnityClass, UnityField};
+use crate::type_tree::TypeTreeObjectRef;
+
+#[derive(Debug, UnityClass)]
+pub struct Sprite<'a> {
+    inner: &'a TypeTreeObjectRef,
+    #[unity(path = "m_Name")]
+    pub name: UnityField<String>,
+    #[unity(path = "m_Rect")]
+    pub rect: UnityField<TypeTreeObjectRef>,
+    #[unity(path = "m_Offset")]
+    pub offset: UnityField<glam::Vec2>,
+    #[unity(path = "m_PixelsToUnits")]
+    pub pixels_to_units: UnityField<f32>,
+    /// Sprite before 5.4.2 are always pivoted at the center.
+    #[unity(path = "m_Pivot", since = "5.4.2")]
+    pub pivot: UnityField<glam::Vec2>,
+    /// PPtr of the Texture2D.
+    #[unity(path = "m_RD/texture")]
+    pub texture: UnityField<TypeTreeObjectRef>,
+    #[unity(path = "m_RD/textureRect")]
+    pub texture_rect: UnityField<TypeTreeObjectRef>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        classes::p_ptr::{PPtr, PPtrObject},
+        test_data::{read_object, SPRITE, SPRITES_ASSETS},
+        type_tree::convert::TryCastFrom,
+    };
+
+    #[test]
+    fn test_sprite() {
+        let object = read_object(SPRITES_ASSETS.to_vec(), SPRITE);
+        let sprite = Sprite::new(&object);
+        assert_eq!(sprite.get_name().unwrap(), "hero_idle");
+        assert_eq!(sprite.get_offset().unwrap(), glam::Vec2::new(0.5, -1.0));
+        assert_eq!(sprite.get_pixels_to_units().unwrap(), 100.0);
+        assert_eq!(sprite.get_pivot().unwrap(), glam::Vec2::new(0.5, 0.25));
+        let texture = sprite.get_texture().unwrap();
+        let texture = PPtr::new(&texture);
+        assert_eq!(
+            (
+                texture.get_file_id().unwrap(),
+                texture.get_path_id().unwrap()
+            ),
+            (1, 2)
+        );
+        let texture_rect = sprite.get_texture_rect().unwrap();
+        assert_eq!(
+            f32::try_cast_from(&texture_rect, &["width".to_owned()][..]).unwrap(),
+            16.0
+        );
+        let rect = sprite.get_rect().unwrap();
+        assert_eq!(
+            f32::try_cast_from(&rect, &["y".to_owned()][..]).unwrap(),
+            64.0
+        );
+
+        // m_Pivot is not read before 5.4.2, an unknown version read it.
+        object.inner().write().unwrap().unity_version = Some("5.3.4f1".to_owned());
+        assert!(sprite.get_pivot().is_err());
+        object.inner().write().unwrap().unity_version = Some(String::new());
+        assert_eq!(sprite.get_pivot().unwrap(), glam::Vec2::new(0.5, 0.25));
+    }
+}
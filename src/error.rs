@@ -31,10 +31,29 @@ pub enum Error {
     ArrayFieldNotFound(Vec<String>),
     #[error("can not find serialized file")]
     SerializedFileNotFound,
+    #[error("can not find bundle")]
+    UnityFSNotFound,
     #[error(
         "cannot find external serialized file. The serialized file may not has add to Viewer."
     )]
     ExternalSerializedFileNotFound,
+    #[error("resource stream not found. path : {0:?}")]
+    ResourceNotFound(String),
+    #[error("asset bundle not found in AssetBundleManifest. name : {0:?}")]
+    AssetBundleNotFound(String),
+    #[error("error while load file. path : {path:?} error : {source}")]
+    FileLoadError {
+        source: Box<Error>,
+        path: std::path::PathBuf,
+    },
+    #[error("yaml error at line {line} : {message}")]
+    YamlError { line: usize, message: String },
+    #[error("can not write yaml value. path : {path:?} error : {message}")]
+    YamlValueError { path: Vec<String>, message: String },
+    #[error("invalid query {query:?} : {message}")]
+    QueryError { query: String, message: String },
+    #[error("value {value} is out of range of {type_name}")]
+    ValueOutOfRange { value: String, type_name: String },
     #[error("{0}")]
     AsSliceError(&'static str),
     #[error("ArrayItemOffset use without field offset.")]
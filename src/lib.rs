@@ -1,8 +1,20 @@
+extern crate self as io_unity;
+
+#[cfg(feature = "addressables")]
+pub mod addressables;
+pub mod asset_source;
 pub mod classes;
 pub mod error;
+pub mod load_report;
+pub mod reference_graph;
 pub mod serialized_file;
 pub mod type_tree;
 pub mod unity_asset_view;
 pub mod unityfs;
+pub mod viewer_index;
+pub mod zip_package;
 pub mod assets;
 mod until;
+
+#[cfg(test)]
+mod test_data;
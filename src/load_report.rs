@@ -0,0 +1,210 @@
+use std::{
+    fs::OpenOptions,
+    io::{ErrorKind, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use binrw::binrw;
+
+use crate::error::{Error, ReadResult};
+
+/// Bytes needed by detect_file_kind_by_header.
+pub const FILE_HEADER_SIZE: usize = 48;
+
+#[binrw]
+#[brw(repr = u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum FileKind {
+    UnityFS,
+    SerializedFile,
+    /// Stream data like .resS and .resource.
+    Resource,
+    Unknown,
+}
+
+/// Detect file kind by header, resource files are detected by extension.
+pub fn detect_file_kind<R: Read + Seek>(
+    reader: &mut R,
+    path: Option<&Path>,
+) -> ReadResult<FileKind> {
+    let file_size = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+    let mut header = [0u8; FILE_HEADER_SIZE];
+    let header_size = read_at_most(reader, &mut header)?;
+    reader.seek(SeekFrom::Start(0))?;
+    Ok(detect_file_kind_by_header(
+        &header[..header_size],
+        file_size,
+        path,
+    ))
+}
+
+/// Detect file kind by the first FILE_HEADER_SIZE bytes of the file.
+pub fn detect_file_kind_by_header(header: &[u8], file_size: u64, path: Option<&Path>) -> FileKind {
+    if header.starts_with(b"UnityFS\0") {
+        return FileKind::UnityFS;
+    }
+    if is_serialized_file_header(header, file_size) {
+        return FileKind::SerializedFile;
+    }
+    if path
+        .and_then(|path| path.extension())
+        .is_some_and(|extension| extension == "resS" || extension == "resource")
+    {
+        return FileKind::Resource;
+    }
+    FileKind::Unknown
+}
+
+pub fn detect_file_kind_by_path(path: &Path) -> ReadResult<FileKind> {
+    let mut file = OpenOptions::new().read(true).open(path)?;
+    detect_file_kind(&mut file, Some(path))
+}
+
+fn read_at_most<R: Read>(reader: &mut R, buf: &mut [u8]) -> ReadResult<usize> {
+    let mut size = 0;
+    while size < buf.len() {
+        match reader.read(&mut buf[size..]) {
+            Ok(0) => break,
+            Ok(n) => size += n,
+            Err(err) if err.kind() == ErrorKind::Interrupted => (),
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(size)
+}
+
+fn is_serialized_file_header(header: &[u8], file_size: u64) -> bool {
+    let read_u32 = |offset: usize| {
+        header
+            .get(offset..offset + 4)
+            .map(|b| u32::from_be_bytes(b.try_into().unwrap()) as u64)
+    };
+    let read_u64 = |offset: usize| {
+        header
+            .get(offset..offset + 8)
+            .map(|b| u64::from_be_bytes(b.try_into().unwrap()))
+    };
+    let (Some(metadata_size), Some(size), Some(version), Some(data_offset)) =
+        (read_u32(0), read_u32(4), read_u32(8), read_u32(12))
+    else {
+        return false;
+    };
+    match version {
+        // LargeFilesSupport, sizes are moved after the endianess.
+        22..=50 => match (read_u64(24), read_u64(32)) {
+            (Some(size), Some(data_offset)) => size == file_size && data_offset <= file_size,
+            _ => false,
+        },
+        1..=21 => size == file_size && metadata_size < file_size && data_offset <= file_size,
+        _ => false,
+    }
+}
+
+/// What happened to a file tried by a viewer loader.
+#[derive(Debug)]
+pub struct LoadedFile {
+    pub path: PathBuf,
+    pub kind: FileKind,
+    pub unity_fs_id: Option<i64>,
+    pub serialized_file_ids: Vec<i64>,
+    /// Restored from a viewer index instead of being read.
+    pub from_index: bool,
+    /// Archive paths of bundle nodes which a bundle added before already has,
+    /// like the same CAB in two bundles. The node of the first bundle is used.
+    pub duplicate_nodes: Vec<String>,
+    pub error: Option<Error>,
+}
+
+impl LoadedFile {
+    pub fn new(path: PathBuf) -> Self {
+        LoadedFile {
+            path,
+            kind: FileKind::Unknown,
+            unity_fs_id: None,
+            serialized_file_ids: Vec::new(),
+            from_index: false,
+            duplicate_nodes: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.unity_fs_id.is_some() || !self.serialized_file_ids.is_empty()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    pub files: Vec<LoadedFile>,
+}
+
+impl LoadReport {
+    /// Record a tried file, in strict mode the error of the file is returned instead.
+    pub fn push(&mut self, mut file: LoadedFile, strict: bool) -> ReadResult<()> {
+        if strict {
+            if let Some(error) = file.error.take() {
+                return Err(Error::FileLoadError {
+                    path: file.path,
+                    source: Box::new(error),
+                });
+            }
+        }
+        self.files.push(file);
+        Ok(())
+    }
+
+    pub fn get_failed_files(&self) -> impl Iterator<Item = &LoadedFile> {
+        self.files.iter().filter(|file| file.error.is_some())
+    }
+
+    pub fn get_loaded_files(&self) -> impl Iterator<Item = &LoadedFile> {
+        self.files.iter().filter(|file| file.is_loaded())
+    }
+
+    pub fn has_error(&self) -> bool {
+        self.get_failed_files().next().is_some()
+    }
+
+    pub fn extend(&mut self, other: LoadReport) {
+        self.files.extend(other.files);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_detect_file_kind() {
+        let mut bundle = b"UnityFS\0".to_vec();
+        bundle.resize(64, 0);
+        assert_eq!(
+            detect_file_kind(&mut Cursor::new(bundle), None).unwrap(),
+            FileKind::UnityFS
+        );
+
+        let mut serialized_file = Vec::new();
+        for value in [16u32, 64, 17, 32] {
+            serialized_file.extend(value.to_be_bytes());
+        }
+        serialized_file.resize(64, 0);
+        assert_eq!(
+            detect_file_kind(&mut Cursor::new(serialized_file.clone()), None).unwrap(),
+            FileKind::SerializedFile
+        );
+        serialized_file.push(0);
+        assert_eq!(
+            detect_file_kind(&mut Cursor::new(serialized_file), None).unwrap(),
+            FileKind::Unknown
+        );
+
+        assert_eq!(
+            detect_file_kind(&mut Cursor::new(vec![1u8; 8]), Some(Path::new("a.resS"))).unwrap(),
+            FileKind::Resource
+        );
+    }
+}
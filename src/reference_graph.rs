@@ -0,0 +1,193 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::error::Error;
+
+/// Object in a viewer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectId {
+    pub serialized_file_id: i64,
+    pub path_id: i64,
+}
+
+impl ObjectId {
+    pub fn new(serialized_file_id: i64, path_id: i64) -> Self {
+        ObjectId {
+            serialized_file_id,
+            path_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// From an object to objects it references.
+    Forward,
+    /// From an object to objects referencing it.
+    Backward,
+}
+
+/// PPtr whose file is not added to the viewer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedReference {
+    pub from: ObjectId,
+    pub external_path: String,
+    pub path_id: i64,
+}
+
+/// References between objects made by PPtr fields.
+#[derive(Debug, Default)]
+pub struct ReferenceGraph {
+    objects: BTreeSet<ObjectId>,
+    forward: BTreeMap<ObjectId, BTreeSet<ObjectId>>,
+    backward: BTreeMap<ObjectId, BTreeSet<ObjectId>>,
+    pub unresolved_references: Vec<UnresolvedReference>,
+    /// Objects which can not be read, their references are unknown.
+    pub errors: Vec<(ObjectId, Error)>,
+}
+
+impl ReferenceGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_object(&mut self, object: ObjectId) {
+        self.objects.insert(object);
+    }
+
+    pub fn add_reference(&mut self, from: ObjectId, to: ObjectId) {
+        self.objects.insert(from);
+        self.objects.insert(to);
+        self.forward.entry(from).or_default().insert(to);
+        self.backward.entry(to).or_default().insert(from);
+    }
+
+    pub fn get_objects(&self) -> impl Iterator<Item = ObjectId> + '_ {
+        self.objects.iter().copied()
+    }
+
+    /// Objects referenced by object directly.
+    pub fn get_references(&self, object: ObjectId) -> impl Iterator<Item = ObjectId> + '_ {
+        self.get_edges(object, Direction::Forward)
+    }
+
+    /// Objects referencing object directly.
+    pub fn get_referenced_by(&self, object: ObjectId) -> impl Iterator<Item = ObjectId> + '_ {
+        self.get_edges(object, Direction::Backward)
+    }
+
+    pub fn get_edges(
+        &self,
+        object: ObjectId,
+        direction: Direction,
+    ) -> impl Iterator<Item = ObjectId> + '_ {
+        let edges = match direction {
+            Direction::Forward => &self.forward,
+            Direction::Backward => &self.backward,
+        };
+        edges.get(&object).into_iter().flatten().copied()
+    }
+
+    /// Breadth first walk from roots, each object is visited once so cycles are safe.
+    /// The edges of an object are not followed when visit return false.
+    pub fn walk<I, F>(&self, roots: I, direction: Direction, mut visit: F)
+    where
+        I: IntoIterator<Item = ObjectId>,
+        F: FnMut(ObjectId, usize) -> bool,
+    {
+        let mut visited = BTreeSet::new();
+        let mut queue = VecDeque::new();
+        for root in roots {
+            if visited.insert(root) {
+                queue.push_back((root, 0));
+            }
+        }
+        while let Some((object, depth)) = queue.pop_front() {
+            if !visit(object, depth) {
+                continue;
+            }
+            for next in self.get_edges(object, direction) {
+                if visited.insert(next) {
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+    }
+
+    /// Objects reachable from roots, the roots are not included unless they are in a cycle.
+    pub fn get_closure<I: IntoIterator<Item = ObjectId>>(
+        &self,
+        roots: I,
+        direction: Direction,
+    ) -> BTreeSet<ObjectId> {
+        let mut closure = BTreeSet::new();
+        let mut queue: VecDeque<ObjectId> = roots.into_iter().collect();
+        while let Some(object) = queue.pop_front() {
+            for next in self.get_edges(object, direction) {
+                if closure.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        closure
+    }
+
+    /// Everything object pull in, directly or not.
+    pub fn get_dependencies(&self, object: ObjectId) -> BTreeSet<ObjectId> {
+        self.get_closure([object], Direction::Forward)
+    }
+
+    /// Everything which need object, directly or not.
+    pub fn get_dependents(&self, object: ObjectId) -> BTreeSet<ObjectId> {
+        self.get_closure([object], Direction::Backward)
+    }
+
+    /// Objects not reachable from roots, like assets no scene or container use.
+    pub fn get_unreachable<I: IntoIterator<Item = ObjectId>>(&self, roots: I) -> Vec<ObjectId> {
+        let mut reachable = BTreeSet::new();
+        self.walk(roots, Direction::Forward, |object, _| {
+            reachable.insert(object)
+        });
+        self.objects
+            .iter()
+            .filter(|object| !reachable.contains(object))
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_graph() {
+        let ids: Vec<_> = (0..5).map(|i| ObjectId::new(0, i)).collect();
+        let mut graph = ReferenceGraph::new();
+        graph.add_reference(ids[0], ids[1]);
+        graph.add_reference(ids[1], ids[2]);
+        graph.add_reference(ids[2], ids[1]);
+        graph.add_reference(ids[3], ids[2]);
+        graph.add_object(ids[4]);
+
+        assert_eq!(
+            graph.get_dependencies(ids[0]),
+            BTreeSet::from([ids[1], ids[2]])
+        );
+        assert_eq!(
+            graph.get_dependents(ids[2]),
+            BTreeSet::from([ids[0], ids[1], ids[2], ids[3]])
+        );
+        assert_eq!(
+            graph.get_referenced_by(ids[2]).collect::<Vec<_>>(),
+            [ids[1], ids[3]]
+        );
+        assert_eq!(graph.get_unreachable([ids[0]]), [ids[3], ids[4]]);
+
+        let mut visited = Vec::new();
+        graph.walk([ids[0]], Direction::Forward, |object, depth| {
+            visited.push((object.path_id, depth));
+            true
+        });
+        assert_eq!(visited, [(0, 0), (1, 1), (2, 2)]);
+    }
+}
@@ -36,7 +36,8 @@ use crate::error::Error;
 #[cfg(feature = "type-tree-json")]
 use crate::type_tree::type_tree_json::get_type_object_args_by_version_class_id;
 use crate::type_tree::{
-    reader::TypeTreeObjectBinReadArgs, reader::TypeTreeObjectBinReadClassArgs, TypeTreeObject,
+    provider, reader::TypeTreeObjectBinReadArgs, reader::TypeTreeObjectBinReadClassArgs,
+    TypeTreeObject,
 };
 use crate::unityfs::UnityResource;
 use crate::until::{Endian, UnityVersion};
@@ -453,10 +454,53 @@ impl SerializedFile {
             .transpose()
     }
 
+    /// Read object with the given type tree instead of the one found for its class.
+    pub fn get_tt_object_by_path_id_with_class_args(
+        &self,
+        path_id: i64,
+        class_args: TypeTreeObjectBinReadClassArgs,
+    ) -> Result<Option<TypeTreeObject>, Error> {
+        self.object_map
+            .get(&path_id)
+            .map(|obj| {
+                self.content
+                    .read_type_tree_object(
+                        &mut self.file_reader.borrow_mut(),
+                        obj,
+                        self.serialized_file_id,
+                        path_id,
+                        class_args,
+                    )
+                    .map_err(|err| Error::ObjectReadError {
+                        source: err.into(),
+                        data_offset: self.content.get_data_offset(),
+                        object_meta: obj.clone(),
+                    })
+            })
+            .transpose()
+    }
+
+    pub fn get_type_object_args_by_path_id(
+        &self,
+        path_id: i64,
+    ) -> Option<TypeTreeObjectBinReadClassArgs> {
+        self.object_map
+            .get(&path_id)
+            .and_then(|obj| self.content.get_type_object_args(obj))
+    }
+
     pub fn get_externals(&self) -> Cow<Vec<FileIdentifier>> {
         self.content.get_externals()
     }
 
+    pub fn get_unity_version(&self) -> String {
+        self.content.get_unity_version()
+    }
+
+    pub fn get_enable_type_tree(&self) -> bool {
+        self.content.get_enable_type_tree()
+    }
+
     pub fn get_serialized_file_id(&self) -> i64 {
         self.serialized_file_id
     }
@@ -480,20 +524,16 @@ pub trait Serialized: fmt::Debug {
         SerializedFileMetadata {
             version: self.get_serialized_file_version().clone(),
             endianess: self.get_endianess().clone(),
-            unity_version: UnityVersion::from_str(&self.get_unity_version()).unwrap(),
+            // a broken version is kept as an empty one, it is older than any version.
+            unity_version: UnityVersion::from_str(&self.get_unity_version())
+                .unwrap_or_else(|_| UnityVersion::new(Vec::new(), None)),
             target_platform: self.get_target_platform().clone(),
             enable_type_tree: self.get_enable_type_tree(),
             serialized_file_id: 0,
         }
     }
 
-    fn get_type_tree_object(
-        &self,
-        reader: &mut Box<dyn UnityResource + Send + Sync>,
-        obj: &Object,
-        serialized_file_id: i64,
-        path_id: i64,
-    ) -> Result<TypeTreeObject, Error> {
+    fn get_type_object_args(&self, obj: &Object) -> Option<TypeTreeObjectBinReadClassArgs> {
         let class_args = if self.get_enable_type_tree() {
             self.get_type_object_args_by_type_id(obj.type_id)
         } else {
@@ -506,14 +546,39 @@ pub trait Serialized: fmt::Debug {
             obj.class,
         ));
 
-        let class_args = class_args.ok_or(Error::TypeTreeObjectBinReadArgsBuild)?;
+        class_args.or_else(|| {
+            provider::get_type_object_args_by_version_class_id(&self.get_unity_version(), obj.class)
+        })
+    }
 
+    fn get_type_tree_object(
+        &self,
+        reader: &mut Box<dyn UnityResource + Send + Sync>,
+        obj: &Object,
+        serialized_file_id: i64,
+        path_id: i64,
+    ) -> Result<TypeTreeObject, Error> {
+        let class_args = self
+            .get_type_object_args(obj)
+            .ok_or(Error::TypeTreeObjectBinReadArgsBuild)?;
+        self.read_type_tree_object(reader, obj, serialized_file_id, path_id, class_args)
+    }
+
+    fn read_type_tree_object(
+        &self,
+        reader: &mut Box<dyn UnityResource + Send + Sync>,
+        obj: &Object,
+        serialized_file_id: i64,
+        path_id: i64,
+        class_args: TypeTreeObjectBinReadClassArgs,
+    ) -> Result<TypeTreeObject, Error> {
         let args = TypeTreeObjectBinReadArgs::new(serialized_file_id, path_id, class_args);
 
         reader.seek(SeekFrom::Start(self.get_data_offset() + obj.byte_start))?;
 
         let mut type_tree_object =
             TypeTreeObject::read_options(reader, self.get_endianess().into(), args)?;
+        type_tree_object.unity_version = Some(self.get_unity_version());
         let apos = reader.stream_position()?;
         if apos - (self.get_data_offset() + obj.byte_start) != obj.byte_size as u64 {
             let mut external_data = vec![
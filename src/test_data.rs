@@ -0,0 +1,259 @@
+//! Fixtures under tests/data, see tests/data/make_fixtures.py.
+
+use std::{io::Cursor, sync::Arc};
+
+use binrw::Endian;
+
+use crate::{
+    serialized_file::SerializedFile,
+    type_tree::{
+        reader::TypeTreeObjectBinReadClassArgs, yaml_import::parse_unity_yaml, TypeField,
+        TypeTreeObject, TypeTreeObjectRef,
+    },
+    unityfs::UnityFS,
+};
+
+/// Uncompressed asset bundle of unity 2018.4 with type trees, it hold the prefabs
+/// assets/prefabs/pickup.prefab and assets/prefabs/player.prefab, the player has a weapon child.
+pub const PREFABS_BUNDLE: &[u8] = include_bytes!("../tests/data/prefabs.bundle");
+pub const PREFABS_CAB: &str = "CAB-2f790fefd8aafe5f68e738377611db09";
+pub const PLAYER: i64 = -6838291376052283548;
+pub const PICKUP: i64 = 5216790040817766452;
+pub const PICKUP_TRANSFORM: i64 = -1470312270960719185;
+pub const PLAYER_TRANSFORM: i64 = 3429483095702384135;
+pub const WEAPON: i64 = -2473851946298134412;
+pub const WEAPON_TRANSFORM: i64 = 8124637702648359025;
+pub const ASSET_BUNDLE: i64 = 1;
+/// Player data bundle of unity 2018.4 without type trees, the nodes level0 and sharedassets0.assets
+/// are serialized files without CAB names, level0 refer to sharedassets0.assets.
+pub const DATA_UNITY3D: &[u8] = include_bytes!("../tests/data/data.unity3d");
+/// AssetBundleManifest bundle of a build with the bundles prefabs and ui, ui depend on prefabs.
+pub const MANIFEST_BUNDLE: &[u8] = include_bytes!("../tests/data/manifest.bundle");
+/// Serialized file of unity 2018.4 with type trees, a Material at path id 1 and a Sprite at path id 2,
+/// their texture is in the external sharedassets0.assets.
+pub const SPRITES_ASSETS: &[u8] = include_bytes!("../tests/data/sprites.assets");
+pub const MATERIAL: i64 = 1;
+pub const SPRITE: i64 = 2;
+/// Managed assembly with the script class Game.Hero, a MonoBehaviour with a nested Item class.
+pub const GAME_DLL: &[u8] = include_bytes!("../tests/data/Game.dll");
+/// Serialized file of unity 2018.4 without type trees, a MonoScript of Game.Hero at path id 1
+/// and a MonoBehaviour using it at path id 2.
+pub const SCRIPTS_ASSETS: &[u8] = include_bytes!("../tests/data/scripts.assets");
+/// scripts.assets with type trees and without the script fields, for the base type trees.
+pub const SCRIPT_TYPES_ASSETS: &[u8] = include_bytes!("../tests/data/script_types.assets");
+
+pub fn read_prefabs_cab() -> Vec<u8> {
+    UnityFS::read(Box::new(Cursor::new(PREFABS_BUNDLE)), None)
+        .unwrap()
+        .get_file_data_by_path(&PREFABS_CAB.to_owned())
+        .unwrap()
+}
+
+pub fn read_prefabs_object(path_id: i64) -> TypeTreeObjectRef {
+    read_object(read_prefabs_cab(), path_id)
+}
+
+pub fn read_object(serialized_file: Vec<u8>, path_id: i64) -> TypeTreeObjectRef {
+    let serialized_file =
+        SerializedFile::read(Box::new(Cursor::new(serialized_file)), 0, None).unwrap();
+    serialized_file
+        .get_tt_object_by_path_id(path_id)
+        .unwrap()
+        .unwrap()
+        .into()
+}
+
+/// Document of the GameObject like class of build_game_object_class_args.
+pub const GAME_OBJECT_YAML: &str = r#"--- !u!1 &1
+GameObject:
+  m_Component:
+  - component: {fileID: 4}
+  - component: {fileID: 5}
+  m_Layer: 5
+  m_Name: Hero
+  m_IsActive: 1
+  m_LocalScale: {x: 1, y: 2, z: 3}
+  m_Data: 0102
+  m_Tags:
+  - Body: 1
+  - Head: 2
+"#;
+
+#[derive(Debug)]
+struct TestTypeField {
+    level: u8,
+    index: i32,
+    is_array: bool,
+    byte_size: i32,
+    meta_flag: i32,
+    type_name: String,
+    name: String,
+}
+
+impl TypeField for TestTypeField {
+    fn get_version(&self) -> u16 {
+        1
+    }
+
+    fn get_level(&self) -> u8 {
+        self.level
+    }
+
+    fn is_array(&self) -> bool {
+        self.is_array
+    }
+
+    fn get_byte_size(&self) -> i32 {
+        self.byte_size
+    }
+
+    fn get_index(&self) -> i32 {
+        self.index
+    }
+
+    fn get_meta_flag(&self) -> i32 {
+        self.meta_flag
+    }
+
+    fn is_align(&self) -> bool {
+        self.meta_flag & 0x4000 != 0
+    }
+
+    fn get_ref_type_hash(&self) -> Option<u64> {
+        None
+    }
+
+    fn get_type(&self) -> &String {
+        &self.type_name
+    }
+
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+}
+
+/// (level, type, name, byte size, meta flag), Array nodes are arrays.
+fn build_class_args(nodes: &[(u8, &str, &str, i32, i32)]) -> TypeTreeObjectBinReadClassArgs {
+    let type_fields = nodes
+        .iter()
+        .enumerate()
+        .map(|(index, (level, type_name, name, byte_size, meta_flag))| {
+            Arc::new(Box::new(TestTypeField {
+                level: *level,
+                index: index as i32,
+                is_array: *type_name == "Array",
+                byte_size: *byte_size,
+                meta_flag: *meta_flag,
+                type_name: type_name.to_string(),
+                name: name.to_string(),
+            }) as Box<dyn TypeField + Send + Sync>)
+        })
+        .collect();
+    TypeTreeObjectBinReadClassArgs::new(1, type_fields)
+}
+
+/// GameObject like class with every kind of field, m_LocalScale is written as a flow mapping.
+pub fn build_game_object_class_args() -> TypeTreeObjectBinReadClassArgs {
+    build_class_args(&[
+        (0, "GameObject", "Base", -1, 0),
+        (1, "vector", "m_Component", -1, 0),
+        (2, "Array", "Array", -1, 0x4000),
+        (3, "int", "size", 4, 0),
+        (3, "ComponentPair", "data", 12, 0),
+        (4, "PPtr<Component>", "component", 12, 0),
+        (5, "int", "m_FileID", 4, 0),
+        (5, "SInt64", "m_PathID", 8, 0),
+        (1, "unsigned int", "m_Layer", 4, 0),
+        (1, "string", "m_Name", -1, 0),
+        (2, "Array", "Array", -1, 0x4000),
+        (3, "int", "size", 4, 0),
+        (3, "char", "data", 1, 0),
+        (1, "bool", "m_IsActive", 1, 0x4000),
+        (1, "Vector3f", "m_LocalScale", 12, 1 << 21),
+        (2, "float", "x", 4, 0),
+        (2, "float", "y", 4, 0),
+        (2, "float", "z", 4, 0),
+        (1, "vector", "m_Data", -1, 0),
+        (2, "Array", "Array", -1, 0x4000),
+        (3, "int", "size", 4, 0),
+        (3, "UInt8", "data", 1, 0),
+        (1, "map", "m_Tags", -1, 0),
+        (2, "Array", "Array", -1, 0x4000),
+        (3, "int", "size", 4, 0),
+        (3, "pair", "data", -1, 0),
+        (4, "string", "first", -1, 0),
+        (5, "Array", "Array", -1, 0x4000),
+        (6, "int", "size", 4, 0),
+        (6, "char", "data", 1, 0),
+        (4, "int", "second", 4, 0),
+    ])
+}
+
+/// Object of the first document of text, read by build_game_object_class_args.
+pub fn read_game_object_yaml(text: &str) -> TypeTreeObject {
+    parse_unity_yaml(text).unwrap()[0]
+        .to_type_tree_object(&build_game_object_class_args(), Endian::Little, &[], 0)
+        .unwrap()
+}
+
+/// Bundle with one uncompressed block holding the nodes, like the fixtures.
+pub fn build_unity_fs(nodes: &[(&str, &[u8])]) -> Vec<u8> {
+    build_unity_fs_with_block_size(nodes, u32::MAX as usize)
+}
+
+/// Bundle with the node data split to uncompressed blocks of block_size.
+pub fn build_unity_fs_with_block_size(nodes: &[(&str, &[u8])], block_size: usize) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut blocks_info = vec![0u8; 16];
+    let mut node_infos = Vec::new();
+    for (path, node_data) in nodes {
+        node_infos.extend((data.len() as i64).to_be_bytes());
+        node_infos.extend((node_data.len() as i64).to_be_bytes());
+        node_infos.extend(4u32.to_be_bytes());
+        node_infos.extend(path.as_bytes());
+        node_infos.push(0);
+        data.extend_from_slice(node_data);
+    }
+    let block_sizes: Vec<_> = data
+        .chunks(block_size)
+        .map(|block| block.len() as u32)
+        .collect();
+    blocks_info.extend((block_sizes.len() as u32).to_be_bytes());
+    for block_size in block_sizes {
+        blocks_info.extend(block_size.to_be_bytes());
+        blocks_info.extend(block_size.to_be_bytes());
+        blocks_info.extend(0x40u16.to_be_bytes());
+    }
+    blocks_info.extend((nodes.len() as u32).to_be_bytes());
+    blocks_info.extend(node_infos);
+
+    let mut bundle = b"UnityFS\0".to_vec();
+    bundle.extend(6u32.to_be_bytes());
+    bundle.extend(b"5.x.x\0");
+    bundle.extend(b"2018.4.36f1\0");
+    let size = bundle.len() + 20 + blocks_info.len() + data.len();
+    bundle.extend((size as i64).to_be_bytes());
+    for value in [blocks_info.len() as u32, blocks_info.len() as u32, 0x40] {
+        bundle.extend(value.to_be_bytes());
+    }
+    bundle.extend(blocks_info);
+    bundle.extend(data);
+    bundle
+}
+
+#[test]
+fn test_build_unity_fs() {
+    assert_eq!(
+        build_unity_fs(&[(PREFABS_CAB, &read_prefabs_cab())]),
+        PREFABS_BUNDLE
+    );
+}
+
+#[test]
+fn test_read_prefabs_object() {
+    for (path_id, class_id) in [(ASSET_BUNDLE, 142), (PLAYER, 1), (PLAYER_TRANSFORM, 4)] {
+        let object = read_prefabs_object(path_id);
+        assert_eq!(object.get_path_id(), path_id);
+        assert_eq!(object.get_class_id(), class_id);
+    }
+}
@@ -190,6 +190,24 @@ impl TryCastFrom<&TypeTreeObjectRef, &[String]> for HashMap<String, TypeTreeObje
     }
 }
 
+/// Pairs of a map field in order, keys may repeat.
+impl TryCastFrom<&TypeTreeObjectRef, &[String]> for Vec<(String, TypeTreeObjectRef)> {
+    type Error = Error;
+
+    fn try_cast_from(value: &TypeTreeObjectRef, path: &[String]) -> Result<Self, Self::Error> {
+        let entites = <Vec<TypeTreeObjectRef>>::try_cast_from(value, path)?;
+        let mut pairs = Vec::new();
+        for entry in entites {
+            let key = String::try_cast_from(&entry, "/Base/first");
+            let value = TypeTreeObjectRef::try_cast_from(&entry, "/Base/second");
+            if let (Ok(key), Ok(value)) = (key, value) {
+                pairs.push((key, value));
+            }
+        }
+        Ok(pairs)
+    }
+}
+
 #[inline]
 fn gen_reader<'a>(
     object_data_buff: &'a [u8],
@@ -0,0 +1,589 @@
+use std::fmt::Display;
+
+use serde::de::{
+    self, value::StrDeserializer, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess,
+    SeqAccess, Visitor,
+};
+
+use super::{
+    convert::{FieldCastArgs, TryCast},
+    ArrayField, Field, FieldValue, TypeTreeObject, TypeTreeObjectRef,
+};
+use crate::error::{Error, ReadResult};
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Other(msg.to_string())
+    }
+}
+
+/// Deserializer of one field, structs are read as maps of their fields in declaration order,
+/// vector as sequences and map as sequences of pairs or as maps.
+#[derive(Clone)]
+pub struct FieldDeserializer<'a> {
+    field: &'a Field,
+    object_data_buff: &'a [u8],
+    field_cast_args: FieldCastArgs,
+}
+
+impl TypeTreeObject {
+    pub fn get_field_deserializer(&self, path: &[String]) -> ReadResult<FieldDeserializer<'_>> {
+        let (field, offset) = self
+            .get_field_by_path_list(path)
+            .ok_or_else(|| Error::FieldNotFound(path.to_vec()))?;
+        let mut field_cast_args = self.get_field_cast_args();
+        field_cast_args.field_offset = offset;
+        Ok(FieldDeserializer {
+            field,
+            object_data_buff: &self.data_buff,
+            field_cast_args,
+        })
+    }
+}
+
+impl<'a> FieldDeserializer<'a> {
+    fn with_field(&self, field: &'a Field) -> Self {
+        FieldDeserializer {
+            field,
+            object_data_buff: self.object_data_buff,
+            field_cast_args: self.field_cast_args.clone(),
+        }
+    }
+
+    fn get_type(&self) -> &str {
+        self.field.get_type()
+    }
+
+    fn cast<T>(&self) -> ReadResult<T>
+    where
+        Field: TryCast<T, Error = Error>,
+    {
+        self.field
+            .try_cast_to(self.object_data_buff, &self.field_cast_args)
+    }
+
+    /// Children in declaration order.
+    fn get_fields(&self) -> Vec<&'a Field> {
+        match &self.field.data {
+            FieldValue::Fields(fields) => fields.values().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn get_child(&self, name: &str) -> Option<Self> {
+        match &self.field.data {
+            FieldValue::Fields(fields) => fields.get(name).map(|field| self.with_field(field)),
+            _ => None,
+        }
+    }
+
+    /// The Array of vector, map and staticvector.
+    fn get_wrapped_array(&self) -> Option<Self> {
+        match &self.field.data {
+            FieldValue::Fields(fields) if fields.len() == 1 && self.get_type() != "string" => {
+                let array = fields.get("Array")?;
+                matches!(array.data, FieldValue::Array(_)).then(|| self.with_field(array))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_byte_array(&self) -> bool {
+        matches!(
+            self.field.try_get_buff_type_and_type_size(),
+            Some((item_type, 1)) if ["UInt8", "SInt8", "char"].contains(&item_type.as_str())
+        )
+    }
+
+    fn get_array_access(&self) -> ReadResult<ArrayAccess<'a>> {
+        let FieldValue::Array(array) = &self.field.data else {
+            return Err(Error::ArrayFieldNotFound(vec![self
+                .field
+                .get_name()
+                .to_owned()]));
+        };
+        let size = array.get_size(self.object_data_buff, &self.field_cast_args)?;
+        Ok(ArrayAccess {
+            deserializer: self.clone(),
+            array,
+            index: 0,
+            size,
+        })
+    }
+
+    fn deserialize_primitive<'de, V: Visitor<'de>>(self, visitor: V) -> ReadResult<V::Value> {
+        match self.get_type() {
+            "bool" => visitor.visit_bool(self.cast()?),
+            "SInt8" => visitor.visit_i8(self.cast()?),
+            "SInt16" | "short" => visitor.visit_i16(self.cast()?),
+            "SInt32" | "int" => visitor.visit_i32(self.cast()?),
+            "SInt64" | "long long" => visitor.visit_i64(self.cast()?),
+            "UInt8" | "char" => visitor.visit_u8(self.cast()?),
+            "UInt16" | "unsigned short" => visitor.visit_u16(self.cast()?),
+            "UInt32" | "unsigned int" => visitor.visit_u32(self.cast()?),
+            "UInt64" | "unsigned long long" => visitor.visit_u64(self.cast()?),
+            "FileSize" => visitor.visit_u64(self.cast::<usize>()? as u64),
+            "float" => visitor.visit_f32(self.cast()?),
+            "double" => visitor.visit_f64(self.cast()?),
+            _ => visitor.visit_byte_buf(
+                self.field
+                    .try_as_slice(self.object_data_buff, &self.field_cast_args)?
+                    .to_vec(),
+            ),
+        }
+    }
+}
+
+impl<'de, 'a> Deserializer<'de> for FieldDeserializer<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> ReadResult<V::Value> {
+        match &self.field.data {
+            FieldValue::DataOffset(_) => self.deserialize_primitive(visitor),
+            FieldValue::Fields(_) => {
+                if self.get_type() == "string" {
+                    return visitor.visit_string(self.cast()?);
+                }
+                if let Some(array) = self.get_wrapped_array() {
+                    return array.deserialize_any(visitor);
+                }
+                visitor.visit_map(FieldsAccess {
+                    deserializer: self.clone(),
+                    fields: self.get_fields(),
+                    index: 0,
+                })
+            }
+            FieldValue::Array(_) => visitor.visit_seq(self.get_array_access()?),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> ReadResult<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> ReadResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> ReadResult<V::Value> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> ReadResult<V::Value> {
+        if let Some(array) = self.get_wrapped_array() {
+            return array.deserialize_byte_buf(visitor);
+        }
+        if self.is_byte_array() {
+            visitor.visit_byte_buf(self.cast()?)
+        } else if self.get_type() == "string" {
+            visitor.visit_byte_buf(self.cast::<String>()?.into_bytes())
+        } else {
+            self.deserialize_any(visitor)
+        }
+    }
+
+    /// Structs like pair and Vector3f can also be read as tuples of their fields.
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> ReadResult<V::Value> {
+        match &self.field.data {
+            FieldValue::Fields(_)
+                if self.get_type() != "string" && self.get_wrapped_array().is_none() =>
+            {
+                visitor.visit_seq(FieldsAccess {
+                    deserializer: self.clone(),
+                    fields: self.get_fields(),
+                    index: 0,
+                })
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> ReadResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> ReadResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    /// map is read with first as key and second as value.
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> ReadResult<V::Value> {
+        match self.get_wrapped_array() {
+            Some(array) if self.get_type() == "map" => visitor.visit_map(PairsAccess {
+                array: array.get_array_access()?,
+                value: None,
+            }),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> ReadResult<V::Value> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct struct enum identifier
+    }
+}
+
+struct FieldsAccess<'a> {
+    deserializer: FieldDeserializer<'a>,
+    fields: Vec<&'a Field>,
+    index: usize,
+}
+
+impl<'de, 'a> MapAccess<'de> for FieldsAccess<'a> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> ReadResult<Option<K::Value>> {
+        match self.fields.get(self.index) {
+            Some(field) => {
+                let key: StrDeserializer<Error> = field.get_name().as_str().into_deserializer();
+                seed.deserialize(key).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> ReadResult<V::Value> {
+        let field = self.fields[self.index];
+        self.index += 1;
+        seed.deserialize(self.deserializer.with_field(field))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.fields.len() - self.index)
+    }
+}
+
+impl<'de, 'a> SeqAccess<'de> for FieldsAccess<'a> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> ReadResult<Option<T::Value>> {
+        match self.fields.get(self.index) {
+            Some(field) => {
+                self.index += 1;
+                seed.deserialize(self.deserializer.with_field(field))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.fields.len() - self.index)
+    }
+}
+
+struct ArrayAccess<'a> {
+    deserializer: FieldDeserializer<'a>,
+    array: &'a ArrayField,
+    index: usize,
+    size: usize,
+}
+
+impl<'a> ArrayAccess<'a> {
+    fn next_item(&mut self) -> ReadResult<Option<FieldDeserializer<'a>>> {
+        if self.index >= self.size {
+            return Ok(None);
+        }
+        let index = self.index;
+        self.index += 1;
+        let (item_field, item_cast_args) = self.array.get_item(
+            index,
+            self.deserializer.object_data_buff,
+            &self.deserializer.field_cast_args,
+        )?;
+        let mut item = self.deserializer.with_field(item_field);
+        item.field_cast_args = item_cast_args;
+        Ok(Some(item))
+    }
+}
+
+impl<'de, 'a> SeqAccess<'de> for ArrayAccess<'a> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> ReadResult<Option<T::Value>> {
+        match self.next_item()? {
+            Some(item) => seed.deserialize(item).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.size - self.index)
+    }
+}
+
+struct PairsAccess<'a> {
+    array: ArrayAccess<'a>,
+    value: Option<FieldDeserializer<'a>>,
+}
+
+impl<'de, 'a> MapAccess<'de> for PairsAccess<'a> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> ReadResult<Option<K::Value>> {
+        let Some(pair) = self.array.next_item()? else {
+            return Ok(None);
+        };
+        let (Some(first), Some(second)) = (pair.get_child("first"), pair.get_child("second"))
+        else {
+            return Err(Error::FieldNotFound(vec![
+                pair.field.get_name().to_owned(),
+                "first".to_owned(),
+            ]));
+        };
+        self.value = Some(second);
+        seed.deserialize(first).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> ReadResult<V::Value> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error::Other("map value read before its key".to_owned()))?;
+        seed.deserialize(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.array.size_hint()
+    }
+}
+
+impl<'a> ArrayAccess<'a> {
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.size - self.index)
+    }
+}
+
+macro_rules! forward_to_field_deserializer {
+    ($($method:ident($($arg:ident: $arg_type:ty),*))*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $arg_type,)* visitor: V) -> ReadResult<V::Value> {
+                let type_tree_obj = self
+                    .inner
+                    .read()
+                    .map_err(|e| Error::Other(e.to_string()))?;
+                type_tree_obj
+                    .get_field_deserializer(&self.path)?
+                    .$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+/// Decode the field the ref points to, like `Texture2DData::deserialize(&texture)`.
+impl<'de> Deserializer<'de> for &TypeTreeObjectRef {
+    type Error = Error;
+
+    forward_to_field_deserializer! {
+        deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16() deserialize_i32()
+        deserialize_i64() deserialize_i128() deserialize_u8() deserialize_u16() deserialize_u32()
+        deserialize_u64() deserialize_u128() deserialize_f32() deserialize_f64() deserialize_char()
+        deserialize_str() deserialize_string() deserialize_bytes() deserialize_byte_buf()
+        deserialize_option() deserialize_unit() deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str) deserialize_seq()
+        deserialize_tuple(len: usize) deserialize_tuple_struct(name: &'static str, len: usize)
+        deserialize_map() deserialize_struct(name: &'static str, fields: &'static [&'static str])
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+        deserialize_identifier() deserialize_ignored_any()
+    }
+}
+
+impl<'de> Deserializer<'de> for TypeTreeObjectRef {
+    type Error = Error;
+
+    forward_to_field_deserializer! {
+        deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16() deserialize_i32()
+        deserialize_i64() deserialize_i128() deserialize_u8() deserialize_u16() deserialize_u32()
+        deserialize_u64() deserialize_u128() deserialize_f32() deserialize_f64() deserialize_char()
+        deserialize_str() deserialize_string() deserialize_bytes() deserialize_byte_buf()
+        deserialize_option() deserialize_unit() deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str) deserialize_seq()
+        deserialize_tuple(len: usize) deserialize_tuple_struct(name: &'static str, len: usize)
+        deserialize_map() deserialize_struct(name: &'static str, fields: &'static [&'static str])
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+        deserialize_identifier() deserialize_ignored_any()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::test_data::{
+        read_game_object_yaml, read_prefabs_object, ASSET_BUNDLE, GAME_OBJECT_YAML, PICKUP, PLAYER,
+        PLAYER_TRANSFORM,
+    };
+
+    #[allow(non_snake_case)]
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct PPtr {
+        m_FileID: i32,
+        m_PathID: i64,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct ComponentPair {
+        component: PPtr,
+    }
+
+    #[allow(non_snake_case)]
+    #[derive(Debug, Deserialize)]
+    struct GameObject {
+        m_Component: Vec<ComponentPair>,
+        m_Layer: u32,
+        m_Name: String,
+        m_IsActive: bool,
+        m_LocalScale: (f32, f32, f32),
+        m_Data: Vec<u8>,
+        m_Tags: HashMap<String, i32>,
+        m_Missing: Option<i32>,
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let object = TypeTreeObjectRef::from(read_game_object_yaml(GAME_OBJECT_YAML));
+        let game_object = GameObject::deserialize(&object).unwrap();
+        assert_eq!(game_object.m_Component[1].component.m_PathID, 5);
+        assert_eq!(game_object.m_Layer, 5);
+        assert_eq!(game_object.m_Name, "Hero");
+        assert!(game_object.m_IsActive);
+        assert_eq!(game_object.m_LocalScale, (1.0, 2.0, 3.0));
+        assert_eq!(game_object.m_Data, [0x01, 0x02]);
+        assert_eq!(
+            game_object.m_Tags,
+            HashMap::from([("Body".to_owned(), 1), ("Head".to_owned(), 2)])
+        );
+        assert_eq!(game_object.m_Missing, None);
+
+        let tags = TypeTreeObjectRef {
+            inner: object.inner.clone(),
+            path: vec!["m_Tags".to_owned()],
+        };
+        assert_eq!(
+            <Vec<(String, i32)>>::deserialize(tags).unwrap(),
+            [("Body".to_owned(), 1), ("Head".to_owned(), 2)]
+        );
+    }
+
+    #[allow(non_snake_case)]
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Vector3 {
+        x: f32,
+        y: f32,
+        z: f32,
+    }
+
+    #[allow(non_snake_case)]
+    #[derive(Debug, Deserialize)]
+    struct Transform {
+        m_GameObject: PPtr,
+        m_LocalPosition: Vector3,
+        m_Children: Vec<PPtr>,
+        m_Father: PPtr,
+    }
+
+    #[allow(non_snake_case)]
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct AssetInfo {
+        preloadIndex: i32,
+        preloadSize: i32,
+        asset: PPtr,
+    }
+
+    #[test]
+    fn test_deserialize_prefabs_bundle() {
+        #[allow(non_snake_case)]
+        #[derive(Debug, Deserialize)]
+        struct GameObject {
+            m_Component: Vec<ComponentPair>,
+            m_Layer: u32,
+            m_Name: String,
+            m_Tag: u16,
+            m_IsActive: bool,
+        }
+
+        let player = GameObject::deserialize(&read_prefabs_object(PLAYER)).unwrap();
+        assert_eq!(player.m_Name, "Player");
+        assert_eq!(player.m_Layer, 8);
+        assert_eq!(player.m_Tag, 0);
+        assert!(player.m_IsActive);
+        assert_eq!(
+            player.m_Component,
+            [ComponentPair {
+                component: PPtr {
+                    m_FileID: 0,
+                    m_PathID: PLAYER_TRANSFORM,
+                },
+            }]
+        );
+
+        let transform = Transform::deserialize(&read_prefabs_object(PLAYER_TRANSFORM)).unwrap();
+        assert_eq!(transform.m_GameObject.m_PathID, PLAYER);
+        assert_eq!(
+            transform.m_LocalPosition,
+            Vector3 {
+                x: 1.0,
+                y: 2.0,
+                z: 3.0
+            }
+        );
+        assert_eq!(transform.m_Children.len(), 1);
+        assert_eq!(transform.m_Father.m_PathID, 0);
+
+        let asset_bundle = read_prefabs_object(ASSET_BUNDLE);
+        let container = TypeTreeObjectRef {
+            inner: asset_bundle.inner.clone(),
+            path: vec!["m_Container".to_owned()],
+        };
+        let container = <Vec<(String, AssetInfo)>>::deserialize(container).unwrap();
+        assert_eq!(
+            container,
+            [
+                (
+                    "assets/prefabs/pickup.prefab".to_owned(),
+                    AssetInfo {
+                        preloadIndex: 0,
+                        preloadSize: 2,
+                        asset: PPtr {
+                            m_FileID: 0,
+                            m_PathID: PICKUP,
+                        },
+                    }
+                ),
+                (
+                    "assets/prefabs/player.prefab".to_owned(),
+                    AssetInfo {
+                        preloadIndex: 2,
+                        preloadSize: 4,
+                        asset: PPtr {
+                            m_FileID: 0,
+                            m_PathID: PLAYER,
+                        },
+                    }
+                ),
+            ]
+        );
+    }
+}
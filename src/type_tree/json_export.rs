@@ -0,0 +1,229 @@
+use serde_json::{Map, Value};
+
+use super::{
+    convert::{FieldCastArgs, TryCast},
+    yaml_export::to_hex,
+    Field, FieldValue, TypeTreeObject, TypeTreeObjectRef,
+};
+use crate::error::{Error, ReadResult};
+
+impl TypeTreeObject {
+    /// Every field of the object in declaration order, values are read by their type tree type.
+    /// Byte arrays are written as hex strings.
+    pub fn to_json(&self) -> ReadResult<Value> {
+        self.data_layout
+            .to_json(&self.data_buff, &self.get_field_cast_args())
+    }
+}
+
+impl TypeTreeObjectRef {
+    /// Json of the field this ref points to.
+    pub fn to_json(&self) -> ReadResult<Value> {
+        let type_tree_obj = self.inner.read().map_err(|e| Error::Other(e.to_string()))?;
+        let (field, offset) = type_tree_obj
+            .get_field_by_path_list(&self.path)
+            .ok_or_else(|| Error::FieldNotFound(self.path.clone()))?;
+        let mut field_cast_args = type_tree_obj.get_field_cast_args();
+        field_cast_args.field_offset = offset;
+        field.to_json(&type_tree_obj.data_buff, &field_cast_args)
+    }
+}
+
+impl Field {
+    fn to_json(
+        &self,
+        object_data_buff: &[u8],
+        field_cast_args: &FieldCastArgs,
+    ) -> ReadResult<Value> {
+        match &self.data {
+            FieldValue::DataOffset(_) => self.primitive_to_json(object_data_buff, field_cast_args),
+            FieldValue::Fields(fields) => {
+                if self.get_type() == "string" {
+                    let value: String = self.try_cast_to(object_data_buff, field_cast_args)?;
+                    return Ok(Value::String(value));
+                }
+                // vector, map and staticvector only wrap their Array.
+                if let (1, Some(array)) = (fields.len(), fields.get("Array")) {
+                    if let FieldValue::Array(_) = array.data {
+                        return array.to_json(object_data_buff, field_cast_args);
+                    }
+                }
+                let mut map = Map::new();
+                for field in fields.values() {
+                    map.insert(
+                        field.get_name().to_owned(),
+                        field.to_json(object_data_buff, field_cast_args)?,
+                    );
+                }
+                Ok(Value::Object(map))
+            }
+            FieldValue::Array(array) => {
+                if let Some((item_type, 1)) = self.try_get_buff_type_and_type_size() {
+                    if ["UInt8", "SInt8", "char"].contains(&item_type.as_str()) {
+                        let data: Vec<u8> = self.try_cast_to(object_data_buff, field_cast_args)?;
+                        return Ok(Value::String(to_hex(&data)));
+                    }
+                }
+                array
+                    .iter_items(object_data_buff, field_cast_args)?
+                    .map(|item| {
+                        let (item, item_cast_args) = item?;
+                        item.to_json(object_data_buff, &item_cast_args)
+                    })
+                    .collect::<ReadResult<_>>()
+                    .map(Value::Array)
+            }
+        }
+    }
+
+    fn primitive_to_json(
+        &self,
+        object_data_buff: &[u8],
+        field_cast_args: &FieldCastArgs,
+    ) -> ReadResult<Value> {
+        Ok(match self.get_type().as_str() {
+            "bool" => cast_to_json::<bool>(self, object_data_buff, field_cast_args)?,
+            "SInt8" => cast_to_json::<i8>(self, object_data_buff, field_cast_args)?,
+            "SInt16" | "short" => cast_to_json::<i16>(self, object_data_buff, field_cast_args)?,
+            "SInt32" | "int" => cast_to_json::<i32>(self, object_data_buff, field_cast_args)?,
+            "SInt64" | "long long" => cast_to_json::<i64>(self, object_data_buff, field_cast_args)?,
+            "UInt8" | "char" => cast_to_json::<u8>(self, object_data_buff, field_cast_args)?,
+            "UInt16" | "unsigned short" => {
+                cast_to_json::<u16>(self, object_data_buff, field_cast_args)?
+            }
+            "UInt32" | "unsigned int" => {
+                cast_to_json::<u32>(self, object_data_buff, field_cast_args)?
+            }
+            "UInt64" | "unsigned long long" => {
+                cast_to_json::<u64>(self, object_data_buff, field_cast_args)?
+            }
+            "FileSize" => cast_to_json::<usize>(self, object_data_buff, field_cast_args)?,
+            "float" => {
+                let value: f32 = self.try_cast_to(object_data_buff, field_cast_args)?;
+                // go through the shortest f32 text, so 0.1f is not written as 0.10000000149011612.
+                Value::from(value.to_string().parse::<f64>().unwrap_or(value as f64))
+            }
+            "double" => cast_to_json::<f64>(self, object_data_buff, field_cast_args)?,
+            _ => Value::String(to_hex(
+                self.try_as_slice(object_data_buff, field_cast_args)?,
+            )),
+        })
+    }
+}
+
+fn cast_to_json<T: Into<Value>>(
+    field: &Field,
+    object_data_buff: &[u8],
+    field_cast_args: &FieldCastArgs,
+) -> ReadResult<Value>
+where
+    Field: TryCast<T, Error = Error>,
+{
+    let value: T = field.try_cast_to(object_data_buff, field_cast_args)?;
+    Ok(value.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_data::{
+        read_game_object_yaml, read_prefabs_object, ASSET_BUNDLE, GAME_OBJECT_YAML, PICKUP,
+        PICKUP_TRANSFORM, PLAYER, PLAYER_TRANSFORM, WEAPON, WEAPON_TRANSFORM,
+    };
+
+    /// Maps keep the declaration order, but their equality does not check it.
+    fn assert_json_eq(value: Value, expected: Value) {
+        assert_eq!(
+            serde_json::to_string_pretty(&value).unwrap(),
+            serde_json::to_string_pretty(&expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let pptr = TypeTreeObject::new_pptr(0, 2, -1234567890123);
+        assert_eq!(
+            serde_json::to_string(&pptr.to_json().unwrap()).unwrap(),
+            r#"{"m_FileID":2,"m_PathID":-1234567890123}"#
+        );
+        let pptr = TypeTreeObjectRef::from(pptr);
+        let path_id = TypeTreeObjectRef {
+            inner: pptr.inner.clone(),
+            path: vec!["m_PathID".to_owned()],
+        };
+        assert_eq!(path_id.to_json().unwrap(), Value::from(-1234567890123i64));
+    }
+
+    #[test]
+    fn test_to_json_prefabs_bundle() {
+        let pptr = |path_id: i64| json!({"m_FileID": 0, "m_PathID": path_id});
+        let asset_info = |preload_index: i32, preload_size: i32, path_id: i64| json!({"preloadIndex": preload_index, "preloadSize": preload_size, "asset": pptr(path_id)});
+        let asset_bundle = read_prefabs_object(ASSET_BUNDLE);
+        assert_json_eq(
+            asset_bundle.to_json().unwrap(),
+            json!({
+                "m_Name": "prefabs",
+                "m_PreloadTable": [
+                    pptr(PICKUP),
+                    pptr(PICKUP_TRANSFORM),
+                    pptr(PLAYER),
+                    pptr(PLAYER_TRANSFORM),
+                    pptr(WEAPON),
+                    pptr(WEAPON_TRANSFORM),
+                ],
+                "m_Container": [
+                    {"first": "assets/prefabs/pickup.prefab", "second": asset_info(0, 2, PICKUP)},
+                    {"first": "assets/prefabs/player.prefab", "second": asset_info(2, 4, PLAYER)},
+                ],
+                "m_MainAsset": asset_info(0, 0, 0),
+                "m_RuntimeCompatibility": 1,
+                "m_AssetBundleName": "prefabs",
+                "m_Dependencies": [],
+                "m_IsStreamedSceneAssetBundle": false,
+                "m_ExplicitDataLayout": 0,
+                "m_PathFlags": 7,
+                "m_SceneHashes": [],
+            }),
+        );
+
+        assert_json_eq(
+            read_prefabs_object(PLAYER).to_json().unwrap(),
+            json!({
+                "m_Component": [{"component": pptr(PLAYER_TRANSFORM)}],
+                "m_Layer": 8,
+                "m_Name": "Player",
+                "m_Tag": 0,
+                "m_IsActive": true,
+            }),
+        );
+
+        let weapon_transform = read_prefabs_object(WEAPON_TRANSFORM).to_json().unwrap();
+        assert_eq!(
+            serde_json::to_string(&weapon_transform["m_LocalPosition"]).unwrap(),
+            r#"{"x":0.5,"y":1.25,"z":0.0}"#
+        );
+
+        let mut game_object = read_game_object_yaml(GAME_OBJECT_YAML);
+        game_object.set("/Base/m_LocalScale/y", 0.1f32).unwrap();
+        assert_json_eq(
+            game_object.to_json().unwrap(),
+            json!({
+                "m_Component": [
+                    {"component": {"m_FileID": 0, "m_PathID": 4}},
+                    {"component": {"m_FileID": 0, "m_PathID": 5}},
+                ],
+                "m_Layer": 5,
+                "m_Name": "Hero",
+                "m_IsActive": true,
+                "m_LocalScale": {"x": 1.0, "y": 0.1, "z": 3.0},
+                "m_Data": "0102",
+                "m_Tags": [
+                    {"first": "Body", "second": 1},
+                    {"first": "Head", "second": 2},
+                ],
+            }),
+        );
+    }
+}
@@ -0,0 +1,883 @@
+//! Read type definitions from .NET assembly (ECMA-335) metadata.
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use binrw::{binread, BinRead, BinReaderExt};
+
+use crate::error::{Error, ReadResult};
+
+use super::{get_full_name, ManagedAssembly, ManagedField, ManagedType, ManagedTypeDefinition};
+
+#[binread]
+#[derive(Debug)]
+#[br(little)]
+struct SectionHeader {
+    _name: [u8; 8],
+    virtual_size: u32,
+    virtual_address: u32,
+    size_of_raw_data: u32,
+    pointer_to_raw_data: u32,
+    #[br(temp)]
+    _other: [u8; 16],
+}
+
+#[binread]
+#[derive(Debug)]
+#[br(little, magic = b"BSJB")]
+struct MetadataRoot {
+    _major_version: u16,
+    _minor_version: u16,
+    _reserved: u32,
+    #[br(temp, assert(version_length <= 256, "metadata version of {} bytes", version_length))]
+    version_length: u32,
+    #[br(count = version_length)]
+    _version: Vec<u8>,
+    _flags: u16,
+    #[br(temp)]
+    stream_count: u16,
+    #[br(args { count: stream_count.into(), inner: () })]
+    stream_headers: Vec<StreamHeader>,
+}
+
+#[binread]
+#[derive(Debug)]
+#[br(little)]
+struct StreamHeader {
+    offset: u32,
+    size: u32,
+    #[br(parse_with = parse_stream_name)]
+    name: String,
+}
+
+#[binrw::parser(reader)]
+fn parse_stream_name() -> binrw::BinResult<String> {
+    let mut name = Vec::new();
+    loop {
+        let mut chunk = [0u8; 4];
+        reader.read_exact(&mut chunk)?;
+        if let Some(end) = chunk.iter().position(|b| *b == 0) {
+            name.extend_from_slice(&chunk[..end]);
+            break;
+        }
+        name.extend_from_slice(&chunk);
+    }
+    Ok(String::from_utf8_lossy(&name).to_string())
+}
+
+const TABLE_MODULE: usize = 0x00;
+const TABLE_TYPE_REF: usize = 0x01;
+const TABLE_TYPE_DEF: usize = 0x02;
+const TABLE_FIELD: usize = 0x04;
+const TABLE_METHOD_DEF: usize = 0x06;
+const TABLE_PARAM: usize = 0x08;
+const TABLE_INTERFACE_IMPL: usize = 0x09;
+const TABLE_MEMBER_REF: usize = 0x0A;
+const TABLE_CUSTOM_ATTRIBUTE: usize = 0x0C;
+const TABLE_DECL_SECURITY: usize = 0x0E;
+const TABLE_STAND_ALONE_SIG: usize = 0x11;
+const TABLE_EVENT: usize = 0x14;
+const TABLE_PROPERTY: usize = 0x17;
+const TABLE_MODULE_REF: usize = 0x1A;
+const TABLE_TYPE_SPEC: usize = 0x1B;
+const TABLE_ASSEMBLY: usize = 0x20;
+const TABLE_ASSEMBLY_REF: usize = 0x23;
+const TABLE_FILE: usize = 0x26;
+const TABLE_EXPORTED_TYPE: usize = 0x27;
+const TABLE_MANIFEST_RESOURCE: usize = 0x28;
+const TABLE_NESTED_CLASS: usize = 0x29;
+const TABLE_GENERIC_PARAM: usize = 0x2A;
+const TABLE_METHOD_SPEC: usize = 0x2B;
+const TABLE_GENERIC_PARAM_CONSTRAINT: usize = 0x2C;
+const TABLE_COUNT: usize = 0x2D;
+
+/// Tables that a coded index can point to, None for unused tags.
+#[derive(Debug, Clone, Copy)]
+enum CodedIndex {
+    TypeDefOrRef,
+    HasConstant,
+    HasCustomAttribute,
+    HasFieldMarshal,
+    HasDeclSecurity,
+    MemberRefParent,
+    HasSemantics,
+    MethodDefOrRef,
+    MemberForwarded,
+    Implementation,
+    CustomAttributeType,
+    ResolutionScope,
+    TypeOrMethodDef,
+}
+
+impl CodedIndex {
+    fn get_tables(&self) -> &'static [Option<usize>] {
+        match self {
+            CodedIndex::TypeDefOrRef => &[
+                Some(TABLE_TYPE_DEF),
+                Some(TABLE_TYPE_REF),
+                Some(TABLE_TYPE_SPEC),
+            ],
+            CodedIndex::HasConstant => {
+                &[Some(TABLE_FIELD), Some(TABLE_PARAM), Some(TABLE_PROPERTY)]
+            }
+            CodedIndex::HasCustomAttribute => &[
+                Some(TABLE_METHOD_DEF),
+                Some(TABLE_FIELD),
+                Some(TABLE_TYPE_REF),
+                Some(TABLE_TYPE_DEF),
+                Some(TABLE_PARAM),
+                Some(TABLE_INTERFACE_IMPL),
+                Some(TABLE_MEMBER_REF),
+                Some(TABLE_MODULE),
+                Some(TABLE_DECL_SECURITY),
+                Some(TABLE_PROPERTY),
+                Some(TABLE_EVENT),
+                Some(TABLE_STAND_ALONE_SIG),
+                Some(TABLE_MODULE_REF),
+                Some(TABLE_TYPE_SPEC),
+                Some(TABLE_ASSEMBLY),
+                Some(TABLE_ASSEMBLY_REF),
+                Some(TABLE_FILE),
+                Some(TABLE_EXPORTED_TYPE),
+                Some(TABLE_MANIFEST_RESOURCE),
+                Some(TABLE_GENERIC_PARAM),
+                Some(TABLE_GENERIC_PARAM_CONSTRAINT),
+                Some(TABLE_METHOD_SPEC),
+            ],
+            CodedIndex::HasFieldMarshal => &[Some(TABLE_FIELD), Some(TABLE_PARAM)],
+            CodedIndex::HasDeclSecurity => &[
+                Some(TABLE_TYPE_DEF),
+                Some(TABLE_METHOD_DEF),
+                Some(TABLE_ASSEMBLY),
+            ],
+            CodedIndex::MemberRefParent => &[
+                Some(TABLE_TYPE_DEF),
+                Some(TABLE_TYPE_REF),
+                Some(TABLE_MODULE_REF),
+                Some(TABLE_METHOD_DEF),
+                Some(TABLE_TYPE_SPEC),
+            ],
+            CodedIndex::HasSemantics => &[Some(TABLE_EVENT), Some(TABLE_PROPERTY)],
+            CodedIndex::MethodDefOrRef => &[Some(TABLE_METHOD_DEF), Some(TABLE_MEMBER_REF)],
+            CodedIndex::MemberForwarded => &[Some(TABLE_FIELD), Some(TABLE_METHOD_DEF)],
+            CodedIndex::Implementation => &[
+                Some(TABLE_FILE),
+                Some(TABLE_ASSEMBLY_REF),
+                Some(TABLE_EXPORTED_TYPE),
+            ],
+            CodedIndex::CustomAttributeType => &[
+                None,
+                None,
+                Some(TABLE_METHOD_DEF),
+                Some(TABLE_MEMBER_REF),
+                None,
+            ],
+            CodedIndex::ResolutionScope => &[
+                Some(TABLE_MODULE),
+                Some(TABLE_MODULE_REF),
+                Some(TABLE_ASSEMBLY_REF),
+                Some(TABLE_TYPE_REF),
+            ],
+            CodedIndex::TypeOrMethodDef => &[Some(TABLE_TYPE_DEF), Some(TABLE_METHOD_DEF)],
+        }
+    }
+
+    fn get_tag_bits(&self) -> u32 {
+        let count = self.get_tables().len() as u32;
+        u32::BITS - (count - 1).leading_zeros()
+    }
+
+    /// (table, 1 based row)
+    fn decode(&self, value: u32) -> Option<(usize, usize)> {
+        let tag_bits = self.get_tag_bits();
+        let tag = value & ((1 << tag_bits) - 1);
+        let table = (*self.get_tables().get(tag as usize)?)?;
+        Some((table, (value >> tag_bits) as usize))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Column {
+    U16,
+    U32,
+    String,
+    Guid,
+    Blob,
+    Table(usize),
+    Coded(CodedIndex),
+}
+
+fn get_table_columns(table: usize) -> &'static [Column] {
+    use CodedIndex::*;
+    use Column::*;
+    match table {
+        0x00 => &[U16, String, Guid, Guid, Guid],
+        0x01 => &[Coded(ResolutionScope), String, String],
+        0x02 => &[
+            U32,
+            String,
+            String,
+            Coded(TypeDefOrRef),
+            Table(TABLE_FIELD),
+            Table(TABLE_METHOD_DEF),
+        ],
+        0x03 => &[Table(TABLE_FIELD)],
+        0x04 => &[U16, String, Blob],
+        0x05 => &[Table(TABLE_METHOD_DEF)],
+        0x06 => &[U32, U16, U16, String, Blob, Table(TABLE_PARAM)],
+        0x07 => &[Table(TABLE_PARAM)],
+        0x08 => &[U16, U16, String],
+        0x09 => &[Table(TABLE_TYPE_DEF), Coded(TypeDefOrRef)],
+        0x0A => &[Coded(MemberRefParent), String, Blob],
+        0x0B => &[U16, Coded(HasConstant), Blob],
+        0x0C => &[Coded(HasCustomAttribute), Coded(CustomAttributeType), Blob],
+        0x0D => &[Coded(HasFieldMarshal), Blob],
+        0x0E => &[U16, Coded(HasDeclSecurity), Blob],
+        0x0F => &[U16, U32, Table(TABLE_TYPE_DEF)],
+        0x10 => &[U32, Table(TABLE_FIELD)],
+        0x11 => &[Blob],
+        0x12 => &[Table(TABLE_TYPE_DEF), Table(TABLE_EVENT)],
+        0x13 => &[Table(TABLE_EVENT)],
+        0x14 => &[U16, String, Coded(TypeDefOrRef)],
+        0x15 => &[Table(TABLE_TYPE_DEF), Table(TABLE_PROPERTY)],
+        0x16 => &[Table(TABLE_PROPERTY)],
+        0x17 => &[U16, String, Blob],
+        0x18 => &[U16, Table(TABLE_METHOD_DEF), Coded(HasSemantics)],
+        0x19 => &[
+            Table(TABLE_TYPE_DEF),
+            Coded(MethodDefOrRef),
+            Coded(MethodDefOrRef),
+        ],
+        0x1A => &[String],
+        0x1B => &[Blob],
+        0x1C => &[U16, Coded(MemberForwarded), String, Table(TABLE_MODULE_REF)],
+        0x1D => &[U32, Table(TABLE_FIELD)],
+        0x1E => &[U32, U32],
+        0x1F => &[U32],
+        0x20 => &[U32, U16, U16, U16, U16, U32, Blob, String, String],
+        0x21 => &[U32],
+        0x22 => &[U32, U32, U32],
+        0x23 => &[U16, U16, U16, U16, U32, Blob, String, String, Blob],
+        0x24 => &[U32, Table(TABLE_ASSEMBLY_REF)],
+        0x25 => &[U32, U32, U32, Table(TABLE_ASSEMBLY_REF)],
+        0x26 => &[U32, String, Blob],
+        0x27 => &[U32, U32, String, String, Coded(Implementation)],
+        0x28 => &[U32, U32, String, Coded(Implementation)],
+        0x29 => &[Table(TABLE_TYPE_DEF), Table(TABLE_TYPE_DEF)],
+        0x2A => &[U16, U16, Coded(TypeOrMethodDef), String],
+        0x2B => &[Coded(MethodDefOrRef), Blob],
+        0x2C => &[Table(TABLE_GENERIC_PARAM), Coded(TypeDefOrRef)],
+        _ => &[],
+    }
+}
+
+/// Metadata tables with every cell widened to u32.
+struct Tables {
+    rows: Vec<Vec<Vec<u32>>>,
+}
+
+impl Tables {
+    fn read(stream: &[u8]) -> ReadResult<Self> {
+        let mut reader = Cursor::new(stream);
+        reader.seek(SeekFrom::Start(6))?;
+        let heap_sizes: u8 = reader.read_le()?;
+        let _reserved: u8 = reader.read_le()?;
+        let valid: u64 = reader.read_le()?;
+        let _sorted: u64 = reader.read_le()?;
+
+        let mut row_counts = [0u32; 64];
+        for (table, row_count) in row_counts.iter_mut().enumerate() {
+            if valid & (1 << table) > 0 {
+                *row_count = reader.read_le()?;
+            }
+        }
+        if valid >> TABLE_COUNT > 0 {
+            return Err(Error::Other(format!(
+                "unsupported metadata table present. valid tables : {valid:#x}"
+            )));
+        }
+        if heap_sizes & 0x40 > 0 {
+            let _extra_data: u32 = reader.read_le()?;
+        }
+
+        let index_size = |large: bool| if large { 4 } else { 2 };
+        let get_column_size = |column: &Column| match column {
+            Column::U16 => 2,
+            Column::U32 => 4,
+            Column::String => index_size(heap_sizes & 0x1 > 0),
+            Column::Guid => index_size(heap_sizes & 0x2 > 0),
+            Column::Blob => index_size(heap_sizes & 0x4 > 0),
+            Column::Table(table) => index_size(row_counts[*table] > 0xFFFF),
+            Column::Coded(coded_index) => {
+                let max_rows = coded_index
+                    .get_tables()
+                    .iter()
+                    .flatten()
+                    .map(|table| row_counts[*table])
+                    .max()
+                    .unwrap_or(0);
+                index_size(max_rows >= 1 << (16 - coded_index.get_tag_bits()))
+            }
+        };
+
+        let mut rows = Vec::with_capacity(TABLE_COUNT);
+        for (table, row_count) in row_counts.iter().enumerate().take(TABLE_COUNT) {
+            let columns = get_table_columns(table);
+            let row_size: u64 = columns.iter().map(get_column_size).sum();
+            let bytes_left = stream.len() as u64 - reader.position().min(stream.len() as u64);
+            if *row_count as u64 * row_size > bytes_left {
+                return Err(Error::Other(format!(
+                    "metadata table {table:#x} of {row_count} rows is larger than the table stream"
+                )));
+            }
+            let mut table_rows = Vec::with_capacity(*row_count as usize);
+            for _ in 0..*row_count {
+                let mut row = Vec::with_capacity(columns.len());
+                for column in columns {
+                    let value = if get_column_size(column) == 2 {
+                        u16::read_le(&mut reader)? as u32
+                    } else {
+                        u32::read_le(&mut reader)?
+                    };
+                    row.push(value);
+                }
+                table_rows.push(row);
+            }
+            rows.push(table_rows);
+        }
+        Ok(Tables { rows })
+    }
+
+    fn row_count(&self, table: usize) -> usize {
+        self.rows[table].len()
+    }
+
+    /// Cell of 1 based row.
+    fn get(&self, table: usize, row: usize, column: usize) -> Option<u32> {
+        self.rows[table]
+            .get(row.checked_sub(1)?)
+            .and_then(|r| r.get(column))
+            .copied()
+    }
+}
+
+struct Metadata<'a> {
+    tables: Tables,
+    strings: &'a [u8],
+    blobs: &'a [u8],
+}
+
+impl<'a> Metadata<'a> {
+    fn get_string(&self, offset: u32) -> String {
+        let data = self.strings.get(offset as usize..).unwrap_or_default();
+        let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+        String::from_utf8_lossy(&data[..end]).to_string()
+    }
+
+    fn get_blob(&self, offset: u32) -> Option<&'a [u8]> {
+        let mut data = self.blobs.get(offset as usize..)?;
+        let length = read_compressed_u32(&mut data)? as usize;
+        data.get(..length)
+    }
+
+    fn get_type_ref_full_name(&self, row: usize, depth: usize) -> Option<String> {
+        let name = self.get_string(self.tables.get(TABLE_TYPE_REF, row, 1)?);
+        let namespace = self.get_string(self.tables.get(TABLE_TYPE_REF, row, 2)?);
+        let scope = self.tables.get(TABLE_TYPE_REF, row, 0)?;
+        if let Some((TABLE_TYPE_REF, outer_row)) = CodedIndex::ResolutionScope.decode(scope) {
+            if depth < 16 {
+                let outer_name = self.get_type_ref_full_name(outer_row, depth + 1)?;
+                return Some(format!("{outer_name}/{name}"));
+            }
+        }
+        Some(get_full_name(&namespace, &name))
+    }
+
+    fn get_type_def_or_ref(
+        &self,
+        (table, row): (usize, usize),
+        type_def_names: &[String],
+    ) -> Option<ManagedType> {
+        match table {
+            TABLE_TYPE_DEF => Some(ManagedType::Named(
+                type_def_names.get(row.checked_sub(1)?)?.clone(),
+            )),
+            TABLE_TYPE_REF => Some(ManagedType::Named(self.get_type_ref_full_name(row, 0)?)),
+            TABLE_TYPE_SPEC => {
+                let mut signature = self.get_blob(self.tables.get(TABLE_TYPE_SPEC, row, 0)?)?;
+                self.read_type_signature(&mut signature, type_def_names, 0)
+            }
+            _ => None,
+        }
+    }
+
+    fn read_type_signature(
+        &self,
+        signature: &mut &[u8],
+        type_def_names: &[String],
+        depth: usize,
+    ) -> Option<ManagedType> {
+        if depth > 32 {
+            return None;
+        }
+        let element_type = read_u8(signature)?;
+        Some(match element_type {
+            0x02 => ManagedType::Boolean,
+            0x03 => ManagedType::Char,
+            0x04 => ManagedType::SByte,
+            0x05 => ManagedType::Byte,
+            0x06 => ManagedType::Int16,
+            0x07 => ManagedType::UInt16,
+            0x08 => ManagedType::Int32,
+            0x09 => ManagedType::UInt32,
+            0x0A => ManagedType::Int64,
+            0x0B => ManagedType::UInt64,
+            0x0C => ManagedType::Single,
+            0x0D => ManagedType::Double,
+            0x0E => ManagedType::String,
+            0x1C => ManagedType::Object,
+            // valuetype, class
+            0x11 | 0x12 => {
+                let index = read_compressed_u32(signature)?;
+                self.get_type_def_or_ref(decode_type_def_or_ref_encoded(index), type_def_names)?
+            }
+            // var
+            0x13 => ManagedType::GenericParameter(read_compressed_u32(signature)?),
+            // genericinst
+            0x15 => {
+                let _class_or_value_type = read_u8(signature)?;
+                let index = read_compressed_u32(signature)?;
+                let generic_type = self
+                    .get_type_def_or_ref(decode_type_def_or_ref_encoded(index), type_def_names)?;
+                let count = read_compressed_u32(signature)?;
+                let mut arguments = Vec::new();
+                for _ in 0..count {
+                    arguments.push(self.read_type_signature(
+                        signature,
+                        type_def_names,
+                        depth + 1,
+                    )?);
+                }
+                match generic_type {
+                    ManagedType::Named(name) => ManagedType::GenericInstance(name, arguments),
+                    _ => ManagedType::Unsupported,
+                }
+            }
+            // szarray
+            0x1D => ManagedType::SzArray(Box::new(self.read_type_signature(
+                signature,
+                type_def_names,
+                depth + 1,
+            )?)),
+            // custom modifiers
+            0x1F | 0x20 => {
+                read_compressed_u32(signature)?;
+                self.read_type_signature(signature, type_def_names, depth + 1)?
+            }
+            // ptr, byref, array, fnptr, mvar, native int ... are never serialized.
+            _ => ManagedType::Unsupported,
+        })
+    }
+
+    /// Full name of the type declaring a custom attribute constructor.
+    fn get_attribute_type_name(
+        &self,
+        constructor: u32,
+        type_def_names: &[String],
+        method_owners: &[usize],
+    ) -> Option<String> {
+        match CodedIndex::CustomAttributeType.decode(constructor)? {
+            (TABLE_METHOD_DEF, row) => type_def_names
+                .get(*method_owners.get(row.checked_sub(1)?)?)
+                .cloned(),
+            (TABLE_MEMBER_REF, row) => {
+                let parent = self.tables.get(TABLE_MEMBER_REF, row, 0)?;
+                match self.get_type_def_or_ref(
+                    CodedIndex::MemberRefParent.decode(parent)?,
+                    type_def_names,
+                )? {
+                    ManagedType::Named(name) => Some(name),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+fn read_u8(data: &mut &[u8]) -> Option<u8> {
+    let (first, rest) = data.split_first()?;
+    *data = rest;
+    Some(*first)
+}
+
+/// ECMA-335 II.23.2 compressed unsigned integer.
+fn read_compressed_u32(data: &mut &[u8]) -> Option<u32> {
+    let first = read_u8(data)? as u32;
+    if first & 0x80 == 0 {
+        Some(first)
+    } else if first & 0xC0 == 0x80 {
+        Some((first & 0x3F) << 8 | read_u8(data)? as u32)
+    } else if first & 0xE0 == 0xC0 {
+        let mut value = first & 0x1F;
+        for _ in 0..3 {
+            value = value << 8 | read_u8(data)? as u32;
+        }
+        Some(value)
+    } else {
+        None
+    }
+}
+
+fn decode_type_def_or_ref_encoded(value: u32) -> (usize, usize) {
+    let table = match value & 0x3 {
+        0 => TABLE_TYPE_DEF,
+        1 => TABLE_TYPE_REF,
+        _ => TABLE_TYPE_SPEC,
+    };
+    (table, (value >> 2) as usize)
+}
+
+fn rva_to_offset(sections: &[SectionHeader], rva: u32) -> ReadResult<u64> {
+    sections
+        .iter()
+        .find_map(|section| {
+            let offset = rva.checked_sub(section.virtual_address)?;
+            (offset < section.virtual_size.max(section.size_of_raw_data))
+                .then(|| section.pointer_to_raw_data as u64 + offset as u64)
+        })
+        .ok_or_else(|| Error::Other(format!("rva {rva:#x} not in any section")))
+}
+
+impl ManagedAssembly {
+    /// Read a managed assembly (.dll) built for mono scripting backend.
+    pub fn read<R: Read + Seek>(reader: &mut R) -> ReadResult<Self> {
+        reader.seek(SeekFrom::Start(0x3C))?;
+        let pe_offset: u32 = reader.read_le()?;
+        reader.seek(SeekFrom::Start(pe_offset as u64))?;
+        let signature: u32 = reader.read_le()?;
+        if signature != 0x4550 {
+            return Err(Error::Other("not a PE file".to_owned()));
+        }
+        reader.seek(SeekFrom::Current(2))?;
+        let section_count: u16 = reader.read_le()?;
+        reader.seek(SeekFrom::Current(12))?;
+        let optional_header_size: u16 = reader.read_le()?;
+        reader.seek(SeekFrom::Current(2))?;
+
+        let optional_header_offset = reader.stream_position()?;
+        let magic: u16 = reader.read_le()?;
+        let data_directories_offset = match magic {
+            0x10B => 96,
+            0x20B => 112,
+            _ => {
+                return Err(Error::Other(format!(
+                    "unknown PE optional header {magic:#x}"
+                )))
+            }
+        };
+        reader.seek(SeekFrom::Start(
+            optional_header_offset + data_directories_offset + 14 * 8,
+        ))?;
+        let cli_header_rva: u32 = reader.read_le()?;
+        if cli_header_rva == 0 {
+            return Err(Error::Other("PE file is not a managed assembly".to_owned()));
+        }
+
+        reader.seek(SeekFrom::Start(
+            optional_header_offset + optional_header_size as u64,
+        ))?;
+        let mut sections = Vec::new();
+        for _ in 0..section_count {
+            sections.push(SectionHeader::read(reader)?);
+        }
+
+        reader.seek(SeekFrom::Start(
+            rva_to_offset(&sections, cli_header_rva)? + 8,
+        ))?;
+        let metadata_rva: u32 = reader.read_le()?;
+        let metadata_size: u32 = reader.read_le()?;
+        reader.seek(SeekFrom::Start(rva_to_offset(&sections, metadata_rva)?))?;
+        let mut metadata_buff = Vec::new();
+        reader
+            .take(metadata_size as u64)
+            .read_to_end(&mut metadata_buff)?;
+        if metadata_buff.len() != metadata_size as usize {
+            return Err(Error::Other(format!(
+                "metadata of {metadata_size} bytes is out of the file"
+            )));
+        }
+
+        Self::from_metadata(&metadata_buff)
+    }
+
+    fn from_metadata(metadata_buff: &[u8]) -> ReadResult<Self> {
+        let metadata_root = MetadataRoot::read(&mut Cursor::new(metadata_buff))?;
+        let get_stream = |names: &[&str]| -> ReadResult<&[u8]> {
+            metadata_root
+                .stream_headers
+                .iter()
+                .find(|header| names.contains(&header.name.as_str()))
+                .and_then(|header| {
+                    let end = header.offset.checked_add(header.size)?;
+                    metadata_buff.get(header.offset as usize..end as usize)
+                })
+                .ok_or_else(|| Error::Other(format!("metadata stream {names:?} not found")))
+        };
+        let metadata = Metadata {
+            tables: Tables::read(get_stream(&["#~", "#-"])?)?,
+            strings: get_stream(&["#Strings"])?,
+            blobs: get_stream(&["#Blob"]).unwrap_or_default(),
+        };
+        let tables = &metadata.tables;
+
+        let name = if tables.row_count(TABLE_ASSEMBLY) > 0 {
+            metadata.get_string(tables.get(TABLE_ASSEMBLY, 1, 7).unwrap_or(0))
+        } else {
+            metadata.get_string(tables.get(TABLE_MODULE, 1, 1).unwrap_or(0))
+        };
+
+        let type_def_count = tables.row_count(TABLE_TYPE_DEF);
+        let mut enclosing_classes = HashMap::new();
+        for row in 1..=tables.row_count(TABLE_NESTED_CLASS) {
+            if let (Some(nested), Some(enclosing)) = (
+                tables.get(TABLE_NESTED_CLASS, row, 0),
+                tables.get(TABLE_NESTED_CLASS, row, 1),
+            ) {
+                enclosing_classes.insert(nested as usize, enclosing as usize);
+            }
+        }
+        let mut type_def_names = Vec::with_capacity(type_def_count);
+        for row in 1..=type_def_count {
+            let mut full_name = String::new();
+            let mut current = row;
+            for _ in 0..16 {
+                let name = metadata.get_string(tables.get(TABLE_TYPE_DEF, current, 1).unwrap_or(0));
+                let namespace =
+                    metadata.get_string(tables.get(TABLE_TYPE_DEF, current, 2).unwrap_or(0));
+                full_name = if full_name.is_empty() {
+                    get_full_name(&namespace, &name)
+                } else {
+                    format!("{}/{full_name}", get_full_name(&namespace, &name))
+                };
+                match enclosing_classes.get(&current) {
+                    Some(enclosing) => current = *enclosing,
+                    None => break,
+                }
+            }
+            type_def_names.push(full_name);
+        }
+
+        // member lists run until the start of the next type's list.
+        let get_member_range = |row: usize, column: usize, member_count: usize| {
+            let start = tables.get(TABLE_TYPE_DEF, row, column).unwrap_or(1) as usize;
+            let end = if row < type_def_count {
+                tables.get(TABLE_TYPE_DEF, row + 1, column).unwrap_or(1) as usize
+            } else {
+                member_count + 1
+            };
+            let start = start.clamp(1, member_count + 1);
+            start..end.clamp(start, member_count + 1)
+        };
+        let mut field_owners = vec![usize::MAX; tables.row_count(TABLE_FIELD)];
+        let mut method_owners = vec![usize::MAX; tables.row_count(TABLE_METHOD_DEF)];
+        for (type_def_index, row) in (1..=type_def_count).enumerate() {
+            for field in get_member_range(row, 4, field_owners.len()) {
+                field_owners[field - 1] = type_def_index;
+            }
+            for method in get_member_range(row, 5, method_owners.len()) {
+                method_owners[method - 1] = type_def_index;
+            }
+        }
+
+        let mut serialize_fields = HashMap::new();
+        for row in 1..=tables.row_count(TABLE_CUSTOM_ATTRIBUTE) {
+            let (Some(parent), Some(constructor)) = (
+                tables.get(TABLE_CUSTOM_ATTRIBUTE, row, 0),
+                tables.get(TABLE_CUSTOM_ATTRIBUTE, row, 1),
+            ) else {
+                continue;
+            };
+            let Some((TABLE_FIELD, field)) = CodedIndex::HasCustomAttribute.decode(parent) else {
+                continue;
+            };
+            match metadata
+                .get_attribute_type_name(constructor, &type_def_names, &method_owners)
+                .as_deref()
+            {
+                Some("UnityEngine.SerializeField") => {
+                    serialize_fields.entry(field).or_insert((false, false)).0 = true
+                }
+                Some("UnityEngine.SerializeReference") => {
+                    serialize_fields.entry(field).or_insert((false, false)).1 = true
+                }
+                _ => (),
+            }
+        }
+
+        let mut types = HashMap::new();
+        for (row, full_name) in (1..=type_def_count).zip(type_def_names.iter().cloned()) {
+            let base_type = tables
+                .get(TABLE_TYPE_DEF, row, 3)
+                .and_then(|extends| CodedIndex::TypeDefOrRef.decode(extends))
+                .filter(|(_, row)| *row > 0)
+                .and_then(|index| metadata.get_type_def_or_ref(index, &type_def_names));
+
+            let mut fields = Vec::new();
+            for field in get_member_range(row, 4, field_owners.len()) {
+                let (Some(attributes), Some(name), Some(signature)) = (
+                    tables.get(TABLE_FIELD, field, 0),
+                    tables.get(TABLE_FIELD, field, 1),
+                    tables.get(TABLE_FIELD, field, 2),
+                ) else {
+                    continue;
+                };
+                let field_type = metadata
+                    .get_blob(signature)
+                    .and_then(|mut signature| {
+                        // FIELD calling convention.
+                        if read_u8(&mut signature)? != 0x06 {
+                            return None;
+                        }
+                        metadata.read_type_signature(&mut signature, &type_def_names, 0)
+                    })
+                    .unwrap_or(ManagedType::Unsupported);
+                let (has_serialize_field, has_serialize_reference) =
+                    serialize_fields.get(&field).copied().unwrap_or_default();
+                fields.push(ManagedField {
+                    name: metadata.get_string(name),
+                    attributes: attributes as u16,
+                    field_type,
+                    has_serialize_field,
+                    has_serialize_reference,
+                });
+            }
+
+            let name = metadata.get_string(tables.get(TABLE_TYPE_DEF, row, 1).unwrap_or(0));
+            let namespace = metadata.get_string(tables.get(TABLE_TYPE_DEF, row, 2).unwrap_or(0));
+            types.insert(
+                full_name.clone(),
+                ManagedTypeDefinition {
+                    namespace,
+                    name,
+                    full_name,
+                    attributes: tables.get(TABLE_TYPE_DEF, row, 0).unwrap_or(0),
+                    base_type,
+                    fields,
+                },
+            );
+        }
+
+        Ok(ManagedAssembly { name, types })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_data::GAME_DLL,
+        type_tree::managed::{MonoBehaviourTypeTreeGenerator, TYPE_ATTRIBUTES_SERIALIZABLE},
+    };
+
+    #[test]
+    fn test_read_assembly() {
+        let assembly = ManagedAssembly::read(&mut Cursor::new(GAME_DLL)).unwrap();
+        assert_eq!(assembly.name, "Game");
+        let mut type_names: Vec<_> = assembly.types.keys().collect();
+        type_names.sort();
+        assert_eq!(type_names, ["<Module>", "Game.Hero", "Game.Hero/Item"]);
+
+        let hero = &assembly.types["Game.Hero"];
+        assert_eq!(hero.get_base_type_name(), Some("UnityEngine.MonoBehaviour"));
+        let fields: Vec<_> = hero
+            .fields
+            .iter()
+            .map(|field| {
+                (
+                    field.name.as_str(),
+                    &field.field_type,
+                    field.is_serialized(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("level", &ManagedType::Int32, true),
+                ("title", &ManagedType::String, true),
+                ("speed", &ManagedType::Single, true),
+                ("hidden", &ManagedType::Int32, false),
+                (
+                    "scores",
+                    &ManagedType::GenericInstance(
+                        "System.Collections.Generic.List`1".to_owned(),
+                        vec![ManagedType::Int32]
+                    ),
+                    true
+                ),
+                (
+                    "items",
+                    &ManagedType::SzArray(Box::new(ManagedType::Named(
+                        "Game.Hero/Item".to_owned()
+                    ))),
+                    true
+                ),
+            ]
+        );
+        assert!(hero.fields[2].has_serialize_field);
+
+        let item = &assembly.types["Game.Hero/Item"];
+        assert_eq!(item.name, "Item");
+        assert!(item.attributes & TYPE_ATTRIBUTES_SERIALIZABLE > 0);
+        assert_eq!(item.fields[0].name, "id");
+
+        let type_fields = MonoBehaviourTypeTreeGenerator::new(&[assembly], "2018.4.36f1", 16)
+            .unwrap()
+            .generate("Game.dll", "Game", "Hero")
+            .unwrap();
+        let nodes: Vec<_> = type_fields
+            .iter()
+            .map(|f| (f.get_level(), f.get_type().as_str(), f.get_name().as_str()))
+            .collect();
+        assert_eq!(
+            nodes,
+            [
+                (1, "int", "level"),
+                (1, "string", "title"),
+                (2, "Array", "Array"),
+                (3, "int", "size"),
+                (3, "char", "data"),
+                (1, "float", "speed"),
+                (1, "vector", "scores"),
+                (2, "Array", "Array"),
+                (3, "int", "size"),
+                (3, "int", "data"),
+                (1, "vector", "items"),
+                (2, "Array", "Array"),
+                (3, "int", "size"),
+                (3, "Item", "data"),
+                (4, "int", "id"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_broken_assembly() {
+        // the metadata start after the section headers and the CLI header.
+        for len in 0..0x200 + 72 + 16 {
+            assert!(ManagedAssembly::read(&mut Cursor::new(&GAME_DLL[..len])).is_err());
+        }
+        // any byte broken or cut gives an error or some assembly, never a panic or a huge
+        // allocation.
+        for len in 0..GAME_DLL.len() {
+            let _ = ManagedAssembly::read(&mut Cursor::new(&GAME_DLL[..len]));
+        }
+        for index in 0..GAME_DLL.len() {
+            for value in [0x00, 0x7f, 0xff] {
+                let mut data = GAME_DLL.to_vec();
+                data[index] = value;
+                let _ = ManagedAssembly::read(&mut Cursor::new(data));
+            }
+        }
+    }
+}
@@ -0,0 +1,1171 @@
+//! Read type definitions from IL2CPP global-metadata.dat (version 24 - 29)
+//! and the type table of the native binary (GameAssembly.dll, libil2cpp.so).
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::error::{Error, ReadResult};
+
+use super::{get_full_name, ManagedAssembly, ManagedField, ManagedType, ManagedTypeDefinition};
+
+const METADATA_SANITY: u32 = 0xFAB11BAF;
+/// Size of Il2CppMethodDefinition since 24.2.
+const METHOD_DEFINITION_SIZE: usize = 32;
+
+fn read_u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_u64_at(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        data.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+fn read_all<R: Read + Seek>(reader: &mut R) -> ReadResult<Vec<u8>> {
+    let mut data = Vec::new();
+    reader.seek(SeekFrom::Start(0))?;
+    reader.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Names of the (offset, size) pairs in metadata header.
+fn get_header_section_names(version: i32, has_rgctx: bool) -> Vec<&'static str> {
+    let mut names = vec![
+        "stringLiteral",
+        "stringLiteralData",
+        "string",
+        "events",
+        "properties",
+        "methods",
+        "parameterDefaultValues",
+        "fieldDefaultValues",
+        "fieldAndParameterDefaultValueData",
+        "fieldMarshaledSizes",
+        "parameters",
+        "fields",
+        "genericParameters",
+        "genericParameterConstraints",
+        "genericContainers",
+        "nestedTypes",
+        "interfaces",
+        "vtableMethods",
+        "interfaceOffsets",
+        "typeDefinitions",
+    ];
+    if has_rgctx {
+        names.push("rgctxEntries");
+    }
+    names.extend(["images", "assemblies"]);
+    if version < 27 {
+        names.extend(["metadataUsageLists", "metadataUsagePairs"]);
+    }
+    names.extend(["fieldRefs", "referencedAssemblies"]);
+    if version < 29 {
+        names.extend(["attributesInfo", "attributeTypes"]);
+    } else {
+        names.extend(["attributeData", "attributeDataRange"]);
+    }
+    names.extend([
+        "unresolvedVirtualCallParameterTypes",
+        "unresolvedVirtualCallParameterRanges",
+        "windowsRuntimeTypeNames",
+    ]);
+    if version >= 27 {
+        names.push("windowsRuntimeStrings");
+    }
+    names.push("exportedTypeDefinitions");
+    names
+}
+
+/// Row layout of a metadata table, column name to byte offset.
+struct TableLayout {
+    columns: HashMap<&'static str, usize>,
+    size: usize,
+}
+
+impl TableLayout {
+    fn new(
+        u32_columns: &[&'static str],
+        u16_columns: &[&'static str],
+        tail: &[&'static str],
+    ) -> Self {
+        let mut columns = HashMap::new();
+        let mut size = 0;
+        for name in u32_columns {
+            columns.insert(*name, size);
+            size += 4;
+        }
+        for name in u16_columns {
+            columns.insert(*name, size);
+            size += 2;
+        }
+        for name in tail {
+            columns.insert(*name, size);
+            size += 4;
+        }
+        TableLayout { columns, size }
+    }
+
+    fn get(&self, data: &[u8], row: usize, column: &str) -> Option<u32> {
+        read_u32_at(data, row * self.size + self.columns.get(column)?)
+    }
+
+    fn get_u16(&self, data: &[u8], row: usize, column: &str) -> Option<u16> {
+        read_u16_at(data, row * self.size + self.columns.get(column)?)
+    }
+}
+
+/// Layout variants of version 24.0, 24.1, 24.2 - 24.5 and 27+.
+#[derive(Debug, Clone, Copy)]
+struct LayoutVariant {
+    has_custom_attribute_index: bool,
+    has_byref_type_index: bool,
+    has_rgctx: bool,
+}
+
+fn get_type_definition_layout(variant: LayoutVariant) -> TableLayout {
+    let mut u32_columns = vec!["nameIndex", "namespaceIndex"];
+    if variant.has_custom_attribute_index {
+        u32_columns.push("customAttributeIndex");
+    }
+    u32_columns.push("byvalTypeIndex");
+    if variant.has_byref_type_index {
+        u32_columns.push("byrefTypeIndex");
+    }
+    u32_columns.extend(["declaringTypeIndex", "parentIndex", "elementTypeIndex"]);
+    if variant.has_rgctx {
+        u32_columns.extend(["rgctxStartIndex", "rgctxCount"]);
+    }
+    u32_columns.extend([
+        "genericContainerIndex",
+        "flags",
+        "fieldStart",
+        "methodStart",
+        "eventStart",
+        "propertyStart",
+        "nestedTypesStart",
+        "interfacesStart",
+        "vtableStart",
+        "interfaceOffsetsStart",
+    ]);
+    TableLayout::new(
+        &u32_columns,
+        &[
+            "method_count",
+            "property_count",
+            "field_count",
+            "event_count",
+            "nested_type_count",
+            "vtable_count",
+            "interfaces_count",
+            "interface_offsets_count",
+        ],
+        &["bitfield", "token"],
+    )
+}
+
+fn get_field_definition_layout(variant: LayoutVariant) -> TableLayout {
+    if variant.has_custom_attribute_index {
+        TableLayout::new(
+            &["nameIndex", "typeIndex", "customAttributeIndex", "token"],
+            &[],
+            &[],
+        )
+    } else {
+        TableLayout::new(&["nameIndex", "typeIndex", "token"], &[], &[])
+    }
+}
+
+fn get_image_definition_layout(variant: LayoutVariant) -> TableLayout {
+    let mut u32_columns = vec![
+        "nameIndex",
+        "assemblyIndex",
+        "typeStart",
+        "typeCount",
+        "exportedTypeStart",
+        "exportedTypeCount",
+        "entryPointIndex",
+        "token",
+    ];
+    if !variant.has_custom_attribute_index {
+        u32_columns.extend(["customAttributeStart", "customAttributeCount"]);
+    }
+    TableLayout::new(&u32_columns, &[], &[])
+}
+
+struct Il2CppMetadata {
+    version: i32,
+    data: Vec<u8>,
+    sections: HashMap<&'static str, (usize, usize)>,
+    variant: LayoutVariant,
+    type_definition_layout: TableLayout,
+    field_definition_layout: TableLayout,
+    image_definition_layout: TableLayout,
+}
+
+impl Il2CppMetadata {
+    fn read(mut data: Vec<u8>) -> ReadResult<Self> {
+        if read_u32_at(&data, 0) != Some(METADATA_SANITY) {
+            return Err(Error::Other("not a il2cpp global-metadata file".to_owned()));
+        }
+        let version = read_u32_at(&data, 4).unwrap_or_default() as i32;
+        if !(24..=29).contains(&version) {
+            return Err(Error::Other(format!(
+                "unsupported il2cpp metadata version {version}"
+            )));
+        }
+        // string literal section follow the header directly.
+        let header_size = read_u32_at(&data, 8).unwrap_or_default() as usize;
+        let names = [true, false]
+            .into_iter()
+            .filter(|has_rgctx| version == 24 || !has_rgctx)
+            .map(|has_rgctx| (has_rgctx, get_header_section_names(version, has_rgctx)))
+            .find(|(_, names)| 8 + names.len() * 8 == header_size);
+        let Some((has_rgctx, names)) = names else {
+            return Err(Error::Other(format!(
+                "unknown il2cpp metadata header size {header_size} for version {version}"
+            )));
+        };
+        let mut sections: HashMap<_, _> = names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let offset = read_u32_at(&data, 8 + i * 8).unwrap_or_default() as usize;
+                let size = read_u32_at(&data, 12 + i * 8).unwrap_or_default() as usize;
+                (name, (offset, size))
+            })
+            .collect();
+
+        let variants = if has_rgctx {
+            vec![
+                LayoutVariant {
+                    has_custom_attribute_index: true,
+                    has_byref_type_index: true,
+                    has_rgctx: true,
+                },
+                LayoutVariant {
+                    has_custom_attribute_index: false,
+                    has_byref_type_index: true,
+                    has_rgctx: true,
+                },
+            ]
+        } else {
+            // byrefTypeIndex is removed after 24.5.
+            let mut variants = vec![
+                LayoutVariant {
+                    has_custom_attribute_index: false,
+                    has_byref_type_index: true,
+                    has_rgctx: false,
+                },
+                LayoutVariant {
+                    has_custom_attribute_index: false,
+                    has_byref_type_index: false,
+                    has_rgctx: false,
+                },
+            ];
+            if version > 24 {
+                variants.reverse();
+            }
+            variants
+        };
+        for variant in variants {
+            let mut metadata = Il2CppMetadata {
+                version,
+                data,
+                sections,
+                variant,
+                type_definition_layout: get_type_definition_layout(variant),
+                field_definition_layout: get_field_definition_layout(variant),
+                image_definition_layout: get_image_definition_layout(variant),
+            };
+            if metadata.is_layout_valid() {
+                return Ok(metadata);
+            }
+            data = std::mem::take(&mut metadata.data);
+            sections = metadata.sections;
+        }
+        Err(Error::Other(format!(
+            "can not determine il2cpp metadata layout for version {version}"
+        )))
+    }
+
+    fn get_section(&self, name: &str) -> &[u8] {
+        self.sections
+            .get(name)
+            .and_then(|(offset, size)| self.data.get(*offset..offset + size))
+            .unwrap_or_default()
+    }
+
+    fn get_row_count(&self, name: &str, layout: &TableLayout) -> usize {
+        self.get_section(name).len() / layout.size
+    }
+
+    fn get_type_definition(&self, row: usize, column: &str) -> Option<u32> {
+        self.type_definition_layout
+            .get(self.get_section("typeDefinitions"), row, column)
+    }
+
+    fn get_field_definition(&self, row: usize, column: &str) -> Option<u32> {
+        self.field_definition_layout
+            .get(self.get_section("fields"), row, column)
+    }
+
+    fn get_image_definition(&self, row: usize, column: &str) -> Option<u32> {
+        self.image_definition_layout
+            .get(self.get_section("images"), row, column)
+    }
+
+    fn is_layout_valid(&self) -> bool {
+        let type_definitions = self.get_section("typeDefinitions");
+        let fields = self.get_section("fields");
+        if !type_definitions
+            .len()
+            .is_multiple_of(self.type_definition_layout.size)
+            || !fields
+                .len()
+                .is_multiple_of(self.field_definition_layout.size)
+        {
+            return false;
+        }
+        let string_size = self.get_section("string").len() as u32;
+        let field_count = self.get_row_count("fields", &self.field_definition_layout) as u32;
+        (0..self.get_row_count("typeDefinitions", &self.type_definition_layout)).all(|row| {
+            let field_start = self.get_type_definition(row, "fieldStart").unwrap_or(0) as i32;
+            let field_end = field_start
+                + self
+                    .type_definition_layout
+                    .get_u16(type_definitions, row, "field_count")
+                    .unwrap_or(0) as i32;
+            self.get_type_definition(row, "nameIndex")
+                .is_some_and(|index| index < string_size)
+                && (field_start == -1 || (field_start >= 0 && field_end as u32 <= field_count))
+        })
+    }
+
+    fn get_string(&self, index: u32) -> String {
+        let data = self
+            .get_section("string")
+            .get(index as usize..)
+            .unwrap_or_default();
+        let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+        String::from_utf8_lossy(&data[..end]).to_string()
+    }
+
+    fn get_generic_parameter_num(&self, index: u32) -> Option<u32> {
+        read_u16_at(
+            self.get_section("genericParameters"),
+            index as usize * 16 + 12,
+        )
+        .map(|num| num as u32)
+    }
+
+    /// Declaring type definition of a method (version 29 custom attribute constructor).
+    fn get_method_declaring_type(&self, index: u32) -> Option<u32> {
+        read_u32_at(
+            self.get_section("methods"),
+            index as usize * METHOD_DEFINITION_SIZE + 4,
+        )
+    }
+}
+
+struct Segment {
+    address: u64,
+    offset: u64,
+    size: u64,
+}
+
+/// Native binary with loaded segments mapping and relative relocations applied.
+struct Il2CppBinary {
+    data: Vec<u8>,
+    is_64bit: bool,
+    segments: Vec<Segment>,
+    relocations: HashMap<u64, u64>,
+}
+
+impl Il2CppBinary {
+    fn read(data: Vec<u8>) -> ReadResult<Self> {
+        if data.starts_with(b"\x7fELF") {
+            Self::read_elf(data)
+        } else if data.starts_with(b"MZ") {
+            Self::read_pe(data)
+        } else {
+            Err(Error::Other(
+                "unsupported il2cpp binary, only ELF and PE are supported".to_owned(),
+            ))
+        }
+    }
+
+    fn read_elf(data: Vec<u8>) -> ReadResult<Self> {
+        let invalid = || Error::Other("invalid ELF file".to_owned());
+        let is_64bit = data.get(4) == Some(&2);
+        let (ph_offset, ph_entry_size, ph_count) = if is_64bit {
+            (
+                read_u64_at(&data, 0x20).ok_or_else(invalid)?,
+                read_u16_at(&data, 0x36).ok_or_else(invalid)?,
+                read_u16_at(&data, 0x38).ok_or_else(invalid)?,
+            )
+        } else {
+            (
+                read_u32_at(&data, 0x1C).ok_or_else(invalid)? as u64,
+                read_u16_at(&data, 0x2A).ok_or_else(invalid)?,
+                read_u16_at(&data, 0x2C).ok_or_else(invalid)?,
+            )
+        };
+
+        let mut binary = Il2CppBinary {
+            data,
+            is_64bit,
+            segments: Vec::new(),
+            relocations: HashMap::new(),
+        };
+        let mut dynamic = None;
+        for i in 0..ph_count as u64 {
+            let header = (ph_offset + i * ph_entry_size as u64) as usize;
+            let read_field = |offset_64: usize, offset_32: usize| {
+                if is_64bit {
+                    read_u64_at(&binary.data, header + offset_64)
+                } else {
+                    read_u32_at(&binary.data, header + offset_32).map(|v| v as u64)
+                }
+            };
+            let p_type = read_u32_at(&binary.data, header).ok_or_else(invalid)?;
+            let segment = Segment {
+                offset: read_field(8, 4).ok_or_else(invalid)?,
+                address: read_field(16, 8).ok_or_else(invalid)?,
+                size: read_field(32, 16).ok_or_else(invalid)?,
+            };
+            match p_type {
+                // PT_LOAD
+                1 => binary.segments.push(segment),
+                // PT_DYNAMIC
+                2 => dynamic = Some(segment),
+                _ => (),
+            }
+        }
+
+        if let Some(dynamic) = dynamic {
+            binary.read_elf_relocations(&dynamic);
+        }
+        Ok(binary)
+    }
+
+    fn read_elf_relocations(&mut self, dynamic: &Segment) {
+        let pointer_size = self.get_pointer_size();
+        let mut tags = HashMap::new();
+        for i in 0..dynamic.size / (pointer_size * 2) {
+            let entry = dynamic.offset + i * pointer_size * 2;
+            let (Some(tag), Some(value)) = (
+                self.read_file_pointer(entry),
+                self.read_file_pointer(entry + pointer_size),
+            ) else {
+                break;
+            };
+            if tag == 0 {
+                break;
+            }
+            tags.insert(tag, value);
+        }
+
+        // (DT_RELA, DT_RELASZ) and (DT_REL, DT_RELSZ)
+        for (table_tag, size_tag, has_addend) in [(7, 8, true), (17, 18, false)] {
+            let (Some(address), Some(size)) = (tags.get(&table_tag), tags.get(&size_tag)) else {
+                continue;
+            };
+            let Some(table_offset) = self.address_to_offset(*address) else {
+                continue;
+            };
+            let entry_size = pointer_size * if has_addend { 3 } else { 2 };
+            for i in 0..size / entry_size {
+                let entry = table_offset + i * entry_size;
+                let (Some(address), Some(info)) = (
+                    self.read_file_pointer(entry),
+                    self.read_file_pointer(entry + pointer_size),
+                ) else {
+                    break;
+                };
+                let relocation_type = if self.is_64bit {
+                    info & 0xFFFF_FFFF
+                } else {
+                    info & 0xFF
+                };
+                // R_X86_64_RELATIVE, R_386_RELATIVE, R_ARM_RELATIVE, R_AARCH64_RELATIVE
+                if !matches!(relocation_type, 8 | 23 | 1027) {
+                    continue;
+                }
+                let value = if has_addend {
+                    self.read_file_pointer(entry + pointer_size * 2)
+                } else {
+                    self.address_to_offset(address)
+                        .and_then(|offset| self.read_file_pointer(offset))
+                };
+                if let Some(value) = value {
+                    self.relocations.insert(address, value);
+                }
+            }
+        }
+    }
+
+    fn read_pe(data: Vec<u8>) -> ReadResult<Self> {
+        let invalid = || Error::Other("invalid PE file".to_owned());
+        let pe_offset = read_u32_at(&data, 0x3C).ok_or_else(invalid)? as usize;
+        if read_u32_at(&data, pe_offset) != Some(0x4550) {
+            return Err(invalid());
+        }
+        let section_count = read_u16_at(&data, pe_offset + 6).ok_or_else(invalid)?;
+        let optional_header_size = read_u16_at(&data, pe_offset + 20).ok_or_else(invalid)?;
+        let optional_header = pe_offset + 24;
+        let (is_64bit, image_base) = match read_u16_at(&data, optional_header) {
+            Some(0x10B) => (
+                false,
+                read_u32_at(&data, optional_header + 28).ok_or_else(invalid)? as u64,
+            ),
+            Some(0x20B) => (
+                true,
+                read_u64_at(&data, optional_header + 24).ok_or_else(invalid)?,
+            ),
+            _ => return Err(invalid()),
+        };
+        let mut segments = Vec::new();
+        for i in 0..section_count as usize {
+            let header = optional_header + optional_header_size as usize + i * 40;
+            segments.push(Segment {
+                address: image_base + read_u32_at(&data, header + 12).ok_or_else(invalid)? as u64,
+                size: read_u32_at(&data, header + 16).ok_or_else(invalid)? as u64,
+                offset: read_u32_at(&data, header + 20).ok_or_else(invalid)? as u64,
+            });
+        }
+        Ok(Il2CppBinary {
+            data,
+            is_64bit,
+            segments,
+            relocations: HashMap::new(),
+        })
+    }
+
+    fn get_pointer_size(&self) -> u64 {
+        if self.is_64bit {
+            8
+        } else {
+            4
+        }
+    }
+
+    fn address_to_offset(&self, address: u64) -> Option<u64> {
+        self.segments
+            .iter()
+            .find(|segment| address >= segment.address && address < segment.address + segment.size)
+            .map(|segment| address - segment.address + segment.offset)
+    }
+
+    fn read_file_pointer(&self, offset: u64) -> Option<u64> {
+        if self.is_64bit {
+            read_u64_at(&self.data, offset as usize)
+        } else {
+            read_u32_at(&self.data, offset as usize).map(|v| v as u64)
+        }
+    }
+
+    fn read_pointer(&self, address: u64) -> Option<u64> {
+        if let Some(value) = self.relocations.get(&address) {
+            return Some(*value);
+        }
+        self.read_file_pointer(self.address_to_offset(address)?)
+    }
+
+    fn is_valid_pointer(&self, address: u64) -> bool {
+        address != 0 && self.address_to_offset(address).is_some()
+    }
+
+    /// Find Il2CppMetadataRegistration by fieldOffsetsCount and typeDefinitionsSizesCount
+    /// which both equal to type definition count, return the address of Il2CppType.
+    fn find_types(&self, type_definition_count: u64) -> Option<Vec<u64>> {
+        let pointer_size = self.get_pointer_size();
+        for segment in &self.segments {
+            let slot_count = segment.size / pointer_size;
+            for slot in 10..slot_count.saturating_sub(4) {
+                let address = segment.address + slot * pointer_size;
+                let read_slot = |index: u64| self.read_pointer(address + index * pointer_size);
+                if read_slot(0) != Some(type_definition_count)
+                    || read_slot(2) != Some(type_definition_count)
+                    || !read_slot(1).is_some_and(|p| self.is_valid_pointer(p))
+                    || !read_slot(3).is_some_and(|p| self.is_valid_pointer(p))
+                {
+                    continue;
+                }
+                let registration = address - 10 * pointer_size;
+                let types_count = self.read_pointer(registration + 6 * pointer_size)?;
+                let types = self.read_pointer(registration + 7 * pointer_size)?;
+                if types_count == 0 || types_count > 0x100_0000 || !self.is_valid_pointer(types) {
+                    continue;
+                }
+                return (0..types_count)
+                    .map(|i| self.read_pointer(types + i * pointer_size))
+                    .collect();
+            }
+        }
+        None
+    }
+
+    /// Il2CppType (data, bits).
+    fn read_type(&self, address: u64) -> Option<(u64, u32)> {
+        let data = self.read_pointer(address)?;
+        let bits_offset = self.address_to_offset(address + self.get_pointer_size())?;
+        Some((data, read_u32_at(&self.data, bits_offset as usize)?))
+    }
+}
+
+struct Il2CppReader {
+    metadata: Il2CppMetadata,
+    binary: Il2CppBinary,
+    types: Vec<u64>,
+    type_definition_names: Vec<String>,
+}
+
+impl Il2CppReader {
+    fn get_type_definition_index(&self, type_index: u32) -> Option<u32> {
+        let (data, bits) = self
+            .binary
+            .read_type(*self.types.get(type_index as usize)?)?;
+        // IL2CPP_TYPE_VALUETYPE, IL2CPP_TYPE_CLASS
+        matches!((bits >> 16) & 0xFF, 0x11 | 0x12).then_some(data as u32)
+    }
+
+    fn get_type_definition_full_name(&self, index: usize, depth: usize) -> Option<String> {
+        let name = self
+            .metadata
+            .get_string(self.metadata.get_type_definition(index, "nameIndex")?);
+        let namespace = self
+            .metadata
+            .get_string(self.metadata.get_type_definition(index, "namespaceIndex")?);
+        let declaring_type_index = self
+            .metadata
+            .get_type_definition(index, "declaringTypeIndex")?;
+        if declaring_type_index != u32::MAX && depth < 16 {
+            let declaring_type = self.get_type_definition_index(declaring_type_index)?;
+            let declaring_name =
+                self.get_type_definition_full_name(declaring_type as usize, depth + 1)?;
+            return Some(format!("{declaring_name}/{name}"));
+        }
+        Some(get_full_name(&namespace, &name))
+    }
+
+    fn get_managed_type(&self, address: u64, depth: usize) -> Option<ManagedType> {
+        if depth > 32 {
+            return None;
+        }
+        let (data, bits) = self.binary.read_type(address)?;
+        let pointer_size = self.binary.get_pointer_size();
+        Some(match (bits >> 16) & 0xFF {
+            0x02 => ManagedType::Boolean,
+            0x03 => ManagedType::Char,
+            0x04 => ManagedType::SByte,
+            0x05 => ManagedType::Byte,
+            0x06 => ManagedType::Int16,
+            0x07 => ManagedType::UInt16,
+            0x08 => ManagedType::Int32,
+            0x09 => ManagedType::UInt32,
+            0x0A => ManagedType::Int64,
+            0x0B => ManagedType::UInt64,
+            0x0C => ManagedType::Single,
+            0x0D => ManagedType::Double,
+            0x0E => ManagedType::String,
+            0x1C => ManagedType::Object,
+            0x11 | 0x12 => ManagedType::Named(
+                self.type_definition_names
+                    .get(data as u32 as usize)?
+                    .clone(),
+            ),
+            0x13 => {
+                ManagedType::GenericParameter(self.metadata.get_generic_parameter_num(data as u32)?)
+            }
+            0x1D => ManagedType::SzArray(Box::new(self.get_managed_type(data, depth + 1)?)),
+            0x15 => {
+                // Il2CppGenericClass
+                let generic_type_definition = if self.metadata.version < 27 {
+                    self.binary.read_pointer(data)? as u32
+                } else {
+                    let (type_data, _) = self.binary.read_type(self.binary.read_pointer(data)?)?;
+                    type_data as u32
+                };
+                let name = self
+                    .type_definition_names
+                    .get(generic_type_definition as usize)?
+                    .clone();
+                // Il2CppGenericInst
+                let class_inst = self.binary.read_pointer(data + pointer_size)?;
+                let argument_count = self.binary.read_pointer(class_inst)? as u32 as u64;
+                let argument_types = self.binary.read_pointer(class_inst + pointer_size)?;
+                let mut arguments = Vec::new();
+                for i in 0..argument_count {
+                    let argument = self
+                        .binary
+                        .read_pointer(argument_types + i * pointer_size)?;
+                    arguments.push(self.get_managed_type(argument, depth + 1)?);
+                }
+                ManagedType::GenericInstance(name, arguments)
+            }
+            _ => ManagedType::Unsupported,
+        })
+    }
+
+    /// Custom attribute type names by token of an image.
+    fn get_custom_attributes(&self, image: usize) -> HashMap<u32, Vec<String>> {
+        let mut attributes = HashMap::new();
+        let metadata = &self.metadata;
+        let start = metadata
+            .get_image_definition(image, "customAttributeStart")
+            .unwrap_or(0) as usize;
+        let count = metadata
+            .get_image_definition(image, "customAttributeCount")
+            .unwrap_or(0) as usize;
+        if metadata.version >= 29 {
+            let ranges = metadata.get_section("attributeDataRange");
+            let attribute_data = metadata.get_section("attributeData");
+            for i in start..start + count {
+                let (Some(token), Some(data_start)) =
+                    (read_u32_at(ranges, i * 8), read_u32_at(ranges, i * 8 + 4))
+                else {
+                    break;
+                };
+                let mut data = attribute_data
+                    .get(data_start as usize..)
+                    .unwrap_or_default();
+                let Some(constructor_count) = read_il2cpp_compressed_u32(&mut data) else {
+                    continue;
+                };
+                let names = (0..constructor_count as usize)
+                    .filter_map(|j| read_u32_at(data, j * 4))
+                    .filter_map(|method| metadata.get_method_declaring_type(method))
+                    .filter_map(|type_definition| {
+                        self.type_definition_names.get(type_definition as usize)
+                    })
+                    .cloned()
+                    .collect();
+                attributes.insert(token, names);
+            }
+        } else if !metadata.variant.has_custom_attribute_index {
+            let ranges = metadata.get_section("attributesInfo");
+            for i in start..start + count {
+                let (Some(token), Some(type_start), Some(type_count)) = (
+                    read_u32_at(ranges, i * 12),
+                    read_u32_at(ranges, i * 12 + 4),
+                    read_u32_at(ranges, i * 12 + 8),
+                ) else {
+                    break;
+                };
+                attributes.insert(token, self.get_attribute_type_names(type_start, type_count));
+            }
+        }
+        attributes
+    }
+
+    fn get_attribute_type_names(&self, start: u32, count: u32) -> Vec<String> {
+        let attribute_types = self.metadata.get_section("attributeTypes");
+        (start..start + count)
+            .filter_map(|i| read_u32_at(attribute_types, i as usize * 4))
+            .filter_map(|type_index| self.get_type_definition_index(type_index))
+            .filter_map(|type_definition| self.type_definition_names.get(type_definition as usize))
+            .cloned()
+            .collect()
+    }
+
+    fn get_field_attribute_names(
+        &self,
+        field: usize,
+        image_attributes: &HashMap<u32, Vec<String>>,
+    ) -> Vec<String> {
+        if self.metadata.variant.has_custom_attribute_index {
+            let Some(index) = self
+                .metadata
+                .get_field_definition(field, "customAttributeIndex")
+            else {
+                return Vec::new();
+            };
+            let ranges = self.metadata.get_section("attributesInfo");
+            match (
+                read_u32_at(ranges, index as usize * 8),
+                read_u32_at(ranges, index as usize * 8 + 4),
+            ) {
+                (Some(start), Some(count)) => self.get_attribute_type_names(start, count),
+                _ => Vec::new(),
+            }
+        } else {
+            self.metadata
+                .get_field_definition(field, "token")
+                .and_then(|token| image_attributes.get(&token))
+                .cloned()
+                .unwrap_or_default()
+        }
+    }
+
+    fn read_assemblies(&self) -> Vec<ManagedAssembly> {
+        let metadata = &self.metadata;
+        let mut assemblies = Vec::new();
+        for image in 0..metadata.get_row_count("images", &metadata.image_definition_layout) {
+            let name = metadata.get_string(
+                metadata
+                    .get_image_definition(image, "nameIndex")
+                    .unwrap_or(0),
+            );
+            let type_start = metadata
+                .get_image_definition(image, "typeStart")
+                .unwrap_or(0) as usize;
+            let type_count = metadata
+                .get_image_definition(image, "typeCount")
+                .unwrap_or(0) as usize;
+            let image_attributes = self.get_custom_attributes(image);
+
+            let mut types = HashMap::new();
+            for type_definition in type_start..type_start + type_count {
+                let Some(full_name) = self.type_definition_names.get(type_definition).cloned()
+                else {
+                    continue;
+                };
+                let base_type = metadata
+                    .get_type_definition(type_definition, "parentIndex")
+                    .filter(|parent| *parent != u32::MAX)
+                    .and_then(|parent| self.types.get(parent as usize))
+                    .and_then(|address| self.get_managed_type(*address, 0));
+
+                let field_start = metadata
+                    .get_type_definition(type_definition, "fieldStart")
+                    .unwrap_or(0) as usize;
+                let field_count = metadata
+                    .type_definition_layout
+                    .get_u16(
+                        metadata.get_section("typeDefinitions"),
+                        type_definition,
+                        "field_count",
+                    )
+                    .unwrap_or(0) as usize;
+                let mut fields = Vec::new();
+                for field in field_start..field_start + field_count {
+                    let Some(field_type) = metadata
+                        .get_field_definition(field, "typeIndex")
+                        .and_then(|type_index| self.types.get(type_index as usize))
+                    else {
+                        continue;
+                    };
+                    let (_, bits) = self.binary.read_type(*field_type).unwrap_or_default();
+                    let attribute_names = self.get_field_attribute_names(field, &image_attributes);
+                    let has_attribute = |attribute_name: &str| {
+                        attribute_names.iter().any(|name| name == attribute_name)
+                    };
+                    fields.push(ManagedField {
+                        name: metadata.get_string(
+                            metadata
+                                .get_field_definition(field, "nameIndex")
+                                .unwrap_or(0),
+                        ),
+                        // field attributes are stored in the attrs of the field type.
+                        attributes: (bits & 0xFFFF) as u16,
+                        field_type: self
+                            .get_managed_type(*field_type, 0)
+                            .unwrap_or(ManagedType::Unsupported),
+                        has_serialize_field: has_attribute("UnityEngine.SerializeField"),
+                        has_serialize_reference: has_attribute("UnityEngine.SerializeReference"),
+                    });
+                }
+
+                let name = metadata.get_string(
+                    metadata
+                        .get_type_definition(type_definition, "nameIndex")
+                        .unwrap_or(0),
+                );
+                let namespace = metadata.get_string(
+                    metadata
+                        .get_type_definition(type_definition, "namespaceIndex")
+                        .unwrap_or(0),
+                );
+                types.insert(
+                    full_name.clone(),
+                    ManagedTypeDefinition {
+                        namespace,
+                        name,
+                        full_name,
+                        attributes: metadata
+                            .get_type_definition(type_definition, "flags")
+                            .unwrap_or(0),
+                        base_type,
+                        fields,
+                    },
+                );
+            }
+            assemblies.push(ManagedAssembly { name, types });
+        }
+        assemblies
+    }
+}
+
+/// Compressed unsigned integer of il2cpp custom attribute data.
+fn read_il2cpp_compressed_u32(data: &mut &[u8]) -> Option<u32> {
+    let (first, rest) = data.split_first()?;
+    let (value, length) = match *first {
+        first if first & 0x80 == 0 => (first as u32, 1),
+        first if first & 0xC0 == 0x80 => (((first as u32 & !0x80) << 8) | *data.get(1)? as u32, 2),
+        first if first & 0xE0 == 0xC0 => (
+            ((first as u32 & !0xC0) << 24)
+                | (*data.get(1)? as u32) << 16
+                | (*data.get(2)? as u32) << 8
+                | *data.get(3)? as u32,
+            4,
+        ),
+        0xF0 => (read_u32_at(rest, 0)?, 5),
+        0xFE => (u32::MAX - 1, 1),
+        0xFF => (u32::MAX, 1),
+        _ => return None,
+    };
+    *data = data.get(length..)?;
+    Some(value)
+}
+
+impl ManagedAssembly {
+    /// Read all assemblies of a IL2CPP build from global-metadata.dat
+    /// and the native binary (GameAssembly.dll or libil2cpp.so).
+    pub fn read_il2cpp<M: Read + Seek, B: Read + Seek>(
+        metadata_reader: &mut M,
+        binary_reader: &mut B,
+    ) -> ReadResult<Vec<Self>> {
+        let metadata = Il2CppMetadata::read(read_all(metadata_reader)?)?;
+        let binary = Il2CppBinary::read(read_all(binary_reader)?)?;
+        let type_definition_count =
+            metadata.get_row_count("typeDefinitions", &metadata.type_definition_layout);
+        let types = binary
+            .find_types(type_definition_count as u64)
+            .ok_or_else(|| Error::Other("can not find il2cpp metadata registration".to_owned()))?;
+
+        let mut reader = Il2CppReader {
+            metadata,
+            binary,
+            types,
+            type_definition_names: Vec::new(),
+        };
+        reader.type_definition_names = (0..type_definition_count)
+            .map(|index| {
+                reader
+                    .get_type_definition_full_name(index, 0)
+                    .unwrap_or_default()
+            })
+            .collect();
+        Ok(reader.read_assemblies())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn push_u32s(buff: &mut Vec<u8>, values: &[u32]) {
+        for value in values {
+            buff.extend(value.to_le_bytes());
+        }
+    }
+
+    fn build_metadata(version: i32) -> Vec<u8> {
+        let mut strings = Vec::new();
+        let mut add_string = |s: &str| {
+            let index = strings.len() as u32;
+            strings.extend(s.as_bytes());
+            strings.push(0);
+            index
+        };
+        let unity_engine = add_string("UnityEngine");
+        let mono_behaviour = add_string("MonoBehaviour");
+        let serialize_field = add_string("SerializeField");
+        let game = add_string("Game");
+        let player = add_string("Player");
+        let speed = add_string("speed");
+        let secret = add_string("secret");
+        let image_name = add_string("Assembly-CSharp.dll");
+
+        let mut type_definitions = Vec::new();
+        // name, namespace, byval, declaring, parent, element, generic container, flags,
+        // field start, 7 other starts, 8 u16 counts, bitfield, token.
+        for (name, namespace, parent, field_count) in [
+            (mono_behaviour, unity_engine, u32::MAX, 0u16),
+            (serialize_field, unity_engine, u32::MAX, 0),
+            (player, game, 0, 2),
+        ] {
+            push_u32s(
+                &mut type_definitions,
+                &[
+                    name,
+                    namespace,
+                    0,
+                    u32::MAX,
+                    parent,
+                    u32::MAX,
+                    u32::MAX,
+                    0x100001,
+                    0,
+                ],
+            );
+            push_u32s(&mut type_definitions, &[0; 7]);
+            for count in [0, 0, field_count, 0, 0, 0, 0, 0] {
+                type_definitions.extend(count.to_le_bytes());
+            }
+            push_u32s(&mut type_definitions, &[0, 0x02000000]);
+        }
+
+        let mut fields = Vec::new();
+        push_u32s(&mut fields, &[speed, 1, 0x04000001, secret, 2, 0x04000002]);
+        let mut images = Vec::new();
+        push_u32s(&mut images, &[image_name, 0, 0, 3, 0, 0, u32::MAX, 1, 0, 1]);
+        let mut sections_data = vec![
+            ("string", strings),
+            ("fields", fields),
+            ("typeDefinitions", type_definitions),
+            ("images", images),
+        ];
+        let attribute_sections = if version < 29 {
+            let mut attributes_info = Vec::new();
+            push_u32s(&mut attributes_info, &[0x04000002, 0, 1]);
+            let mut attribute_types = Vec::new();
+            push_u32s(&mut attribute_types, &[3]);
+            [
+                ("attributesInfo", attributes_info),
+                ("attributeTypes", attribute_types),
+            ]
+        } else {
+            // attribute constructors are methods, the constructor of SerializeField is the second.
+            let mut methods = Vec::new();
+            for (name, declaring_type, return_type) in
+                [(mono_behaviour, 0, 0), (serialize_field, 1, 0)]
+            {
+                push_u32s(
+                    &mut methods,
+                    &[name, declaring_type, return_type, 0, u32::MAX, 0x06000001],
+                );
+                push_u32s(&mut methods, &[0, 0]);
+            }
+            let mut attribute_data = vec![1];
+            push_u32s(&mut attribute_data, &[1]);
+            let mut attribute_data_range = Vec::new();
+            push_u32s(&mut attribute_data_range, &[0x04000002, 0]);
+            sections_data.push(("methods", methods));
+            [
+                ("attributeData", attribute_data),
+                ("attributeDataRange", attribute_data_range),
+            ]
+        };
+        sections_data.extend(attribute_sections);
+
+        let names = get_header_section_names(version, false);
+        let header_size = 8 + names.len() * 8;
+        let mut body = Vec::new();
+        let mut sections = HashMap::new();
+        for (name, data) in sections_data {
+            sections.insert(name, ((header_size + body.len()) as u32, data.len() as u32));
+            body.extend(data);
+        }
+        let mut metadata = Vec::new();
+        push_u32s(&mut metadata, &[METADATA_SANITY, version as u32]);
+        for name in names {
+            let (offset, size) = sections
+                .get(name)
+                .copied()
+                .unwrap_or((header_size as u32, 0));
+            push_u32s(&mut metadata, &[offset, size]);
+        }
+        metadata.extend(body);
+        metadata
+    }
+
+    fn build_binary() -> Vec<u8> {
+        const IMAGE_BASE: u64 = 0x1_4000_0000;
+        const SECTION_ADDRESS: u64 = 0x1000;
+        const SECTION_OFFSET: usize = 0x200;
+        let mut binary = vec![0u8; SECTION_OFFSET];
+        binary[..2].copy_from_slice(b"MZ");
+        binary[0x3C..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+        binary[0x40..0x44].copy_from_slice(b"PE\0\0");
+        binary[0x46..0x48].copy_from_slice(&1u16.to_le_bytes());
+        binary[0x54..0x56].copy_from_slice(&0xF0u16.to_le_bytes());
+        binary[0x58..0x5A].copy_from_slice(&0x20Bu16.to_le_bytes());
+        binary[0x70..0x78].copy_from_slice(&IMAGE_BASE.to_le_bytes());
+        let section_header = 0x58 + 0xF0;
+        for (offset, value) in [
+            (8, 0x200u32),
+            (12, SECTION_ADDRESS as u32),
+            (16, 0x200),
+            (20, 0x200),
+        ] {
+            binary[section_header + offset..section_header + offset + 4]
+                .copy_from_slice(&value.to_le_bytes());
+        }
+
+        let address = |offset: u64| IMAGE_BASE + SECTION_ADDRESS + offset;
+        let mut section = Vec::new();
+        // Il2CppType: class MonoBehaviour, public float, private int, class SerializeField.
+        for (data, bits) in [
+            (0u64, 0x12_0000u64),
+            (0, 0x0C_0006),
+            (0, 0x08_0001),
+            (1, 0x12_0000),
+        ] {
+            section.extend(data.to_le_bytes());
+            section.extend(bits.to_le_bytes());
+        }
+        for i in 0..4 {
+            section.extend(address(i * 16).to_le_bytes());
+        }
+        // Il2CppMetadataRegistration
+        let valid_pointer = address(0);
+        for slot in [
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            4,
+            address(0x40),
+            0,
+            0,
+            3,
+            valid_pointer,
+            3,
+            valid_pointer,
+        ] {
+            section.extend(slot.to_le_bytes());
+        }
+        section.resize(0x200, 0);
+        binary.extend(section);
+        binary
+    }
+
+    #[test]
+    fn test_read_il2cpp() {
+        for version in [27, 29] {
+            let assemblies = ManagedAssembly::read_il2cpp(
+                &mut Cursor::new(build_metadata(version)),
+                &mut Cursor::new(build_binary()),
+            )
+            .unwrap();
+            assert_eq!(assemblies.len(), 1);
+            assert_eq!(assemblies[0].name, "Assembly-CSharp.dll");
+            let player = &assemblies[0].types["Game.Player"];
+            assert_eq!(
+                player.get_base_type_name(),
+                Some("UnityEngine.MonoBehaviour")
+            );
+            assert_eq!(player.fields.len(), 2);
+            assert_eq!(player.fields[0].name, "speed");
+            assert_eq!(player.fields[0].field_type, ManagedType::Single);
+            assert!(player.fields[0].is_serialized());
+            assert_eq!(player.fields[1].field_type, ManagedType::Int32);
+            assert!(player.fields[1].has_serialize_field, "version {version}");
+            assert!(player.fields[1].is_serialized());
+        }
+    }
+}
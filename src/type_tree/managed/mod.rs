@@ -0,0 +1,978 @@
+pub mod assembly;
+pub mod il2cpp;
+
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use walkdir::WalkDir;
+
+use crate::error::ReadResult;
+use crate::until::UnityVersion;
+
+use super::provider::TypeTreeProvider;
+use super::reader::TypeTreeObjectBinReadClassArgs;
+use super::TypeField;
+
+pub const TYPE_ATTRIBUTES_INTERFACE: u32 = 0x20;
+pub const TYPE_ATTRIBUTES_ABSTRACT: u32 = 0x80;
+pub const TYPE_ATTRIBUTES_SERIALIZABLE: u32 = 0x2000;
+
+pub const FIELD_ATTRIBUTES_FIELD_ACCESS_MASK: u16 = 0x7;
+pub const FIELD_ATTRIBUTES_PUBLIC: u16 = 0x6;
+pub const FIELD_ATTRIBUTES_STATIC: u16 = 0x10;
+pub const FIELD_ATTRIBUTES_INIT_ONLY: u16 = 0x20;
+pub const FIELD_ATTRIBUTES_LITERAL: u16 = 0x40;
+pub const FIELD_ATTRIBUTES_NOT_SERIALIZED: u16 = 0x80;
+
+/// Field or generic argument type of managed code.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ManagedType {
+    Boolean,
+    Char,
+    SByte,
+    Byte,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Int64,
+    UInt64,
+    Single,
+    Double,
+    String,
+    Object,
+    /// Class or struct by full name, nested type use "Outer/Inner".
+    Named(String),
+    SzArray(Box<ManagedType>),
+    GenericInstance(String, Vec<ManagedType>),
+    GenericParameter(u32),
+    Unsupported,
+}
+
+#[derive(Debug, Clone)]
+pub struct ManagedField {
+    pub name: String,
+    pub attributes: u16,
+    pub field_type: ManagedType,
+    pub has_serialize_field: bool,
+    pub has_serialize_reference: bool,
+}
+
+impl ManagedField {
+    pub fn is_static(&self) -> bool {
+        self.attributes & FIELD_ATTRIBUTES_STATIC > 0
+    }
+
+    /// Unity serialize public or [SerializeField] instance fields
+    /// which are not const, readonly or [NonSerialized].
+    pub fn is_serialized(&self) -> bool {
+        if self.attributes
+            & (FIELD_ATTRIBUTES_STATIC
+                | FIELD_ATTRIBUTES_INIT_ONLY
+                | FIELD_ATTRIBUTES_LITERAL
+                | FIELD_ATTRIBUTES_NOT_SERIALIZED)
+            > 0
+        {
+            return false;
+        }
+        self.attributes & FIELD_ATTRIBUTES_FIELD_ACCESS_MASK == FIELD_ATTRIBUTES_PUBLIC
+            || self.has_serialize_field
+            || self.has_serialize_reference
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ManagedTypeDefinition {
+    pub namespace: String,
+    pub name: String,
+    pub full_name: String,
+    pub attributes: u32,
+    pub base_type: Option<ManagedType>,
+    pub fields: Vec<ManagedField>,
+}
+
+impl ManagedTypeDefinition {
+    pub fn get_base_type_name(&self) -> Option<&str> {
+        match &self.base_type {
+            Some(ManagedType::Named(name)) => Some(name),
+            Some(ManagedType::GenericInstance(name, _)) => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn is_enum(&self) -> bool {
+        self.get_base_type_name() == Some("System.Enum")
+    }
+
+    pub fn is_value_type(&self) -> bool {
+        self.get_base_type_name() == Some("System.ValueType") || self.is_enum()
+    }
+
+    pub fn is_serializable(&self) -> bool {
+        self.attributes & TYPE_ATTRIBUTES_SERIALIZABLE > 0
+            && self.attributes & (TYPE_ATTRIBUTES_ABSTRACT | TYPE_ATTRIBUTES_INTERFACE) == 0
+    }
+
+    pub fn get_generic_parameter_count(&self) -> u32 {
+        self.name
+            .split_once('`')
+            .and_then(|(_, count)| count.parse().ok())
+            .unwrap_or(0)
+    }
+
+    pub fn get_enum_underlying_type(&self) -> Option<&ManagedType> {
+        self.fields
+            .iter()
+            .find(|field| !field.is_static() && field.name == "value__")
+            .map(|field| &field.field_type)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ManagedAssembly {
+    pub name: String,
+    pub types: HashMap<String, ManagedTypeDefinition>,
+}
+
+pub fn get_full_name(namespace: &str, name: &str) -> String {
+    if namespace.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{namespace}.{name}")
+    }
+}
+
+fn get_assembly_name(name: &str) -> &str {
+    name.strip_suffix(".dll").unwrap_or(name)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeTreeNode {
+    version: u16,
+    level: u8,
+    type_flags: u8,
+    type_name: String,
+    name: String,
+    byte_size: i32,
+    index: i32,
+    meta_flag: i32,
+}
+
+impl TypeField for TypeTreeNode {
+    fn get_version(&self) -> u16 {
+        self.version
+    }
+
+    fn get_level(&self) -> u8 {
+        self.level
+    }
+
+    fn is_array(&self) -> bool {
+        self.type_flags & 1 > 0
+    }
+
+    fn get_byte_size(&self) -> i32 {
+        self.byte_size
+    }
+
+    fn get_index(&self) -> i32 {
+        self.index
+    }
+
+    fn get_meta_flag(&self) -> i32 {
+        self.meta_flag
+    }
+
+    fn is_align(&self) -> bool {
+        self.meta_flag & 0x4000 > 0
+    }
+
+    fn get_ref_type_hash(&self) -> Option<u64> {
+        None
+    }
+
+    fn get_type(&self) -> &String {
+        &self.type_name
+    }
+
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+}
+
+const META_FLAG_ALIGN: i32 = 0x4000;
+const META_FLAG_ANY_CHILD_ALIGN: i32 = 0x8000;
+/// Unity stop serialize nested class deeper than this.
+const MAX_SERIALIZATION_DEPTH: usize = 10;
+
+/// Generate MonoBehaviour script type tree by unity serialization rules.
+pub struct MonoBehaviourTypeTreeGenerator<'a> {
+    assemblies: &'a [ManagedAssembly],
+    unity_version: UnityVersion,
+    first_index: i32,
+    nodes: Vec<TypeTreeNode>,
+}
+
+impl<'a> MonoBehaviourTypeTreeGenerator<'a> {
+    /// Generated nodes are indexed from first_index, to follow the base class nodes.
+    pub fn new(
+        assemblies: &'a [ManagedAssembly],
+        unity_version: &str,
+        first_index: i32,
+    ) -> Option<Self> {
+        if !unity_version.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        Some(MonoBehaviourTypeTreeGenerator {
+            assemblies,
+            unity_version: UnityVersion::from_str(unity_version).ok()?,
+            first_index,
+            nodes: Vec::new(),
+        })
+    }
+
+    /// Script fields of the class, start at level 1 (the children of Base).
+    /// None if the layout cannot be determined.
+    pub fn generate(
+        mut self,
+        assembly_name: &str,
+        namespace: &str,
+        class_name: &str,
+    ) -> Option<Vec<Arc<Box<dyn TypeField + Send + Sync>>>> {
+        let full_name = get_full_name(namespace, class_name);
+        let type_definition = self
+            .assemblies
+            .iter()
+            .find(|assembly| get_assembly_name(&assembly.name) == get_assembly_name(assembly_name))
+            .and_then(|assembly| assembly.types.get(&full_name))
+            .or_else(|| self.resolve(&full_name))?;
+        self.add_class_fields(type_definition, &[], 1, 0)?;
+        Some(
+            self.nodes
+                .into_iter()
+                .map(|node| Arc::new(Box::new(node) as Box<dyn TypeField + Send + Sync>))
+                .collect(),
+        )
+    }
+
+    fn is_version_at_least(&self, version: &[u32]) -> bool {
+        self.unity_version >= UnityVersion::new(version.to_vec(), None)
+    }
+
+    fn resolve(&self, full_name: &str) -> Option<&'a ManagedTypeDefinition> {
+        self.assemblies
+            .iter()
+            .find_map(|assembly| assembly.types.get(full_name))
+    }
+
+    fn push(&mut self, level: u8, type_name: &str, name: &str, byte_size: i32, meta_flag: i32) {
+        self.push_node(level, type_name, name, byte_size, meta_flag, 0)
+    }
+
+    fn push_node(
+        &mut self,
+        level: u8,
+        type_name: &str,
+        name: &str,
+        byte_size: i32,
+        meta_flag: i32,
+        type_flags: u8,
+    ) {
+        let index = self.first_index + self.nodes.len() as i32;
+        self.nodes.push(TypeTreeNode {
+            version: 1,
+            level,
+            type_flags,
+            type_name: type_name.to_owned(),
+            name: name.to_owned(),
+            byte_size,
+            index,
+            meta_flag,
+        });
+    }
+
+    fn push_fields(&mut self, level: u8, fields: &[(&str, &str, i32)]) {
+        for (type_name, name, byte_size) in fields {
+            self.push(level, type_name, name, *byte_size, 0);
+        }
+    }
+
+    fn push_array_header(&mut self, level: u8, type_name: &str, name: &str) {
+        self.push(level, type_name, name, -1, META_FLAG_ANY_CHILD_ALIGN);
+        self.push_node(level + 1, "Array", "Array", -1, META_FLAG_ALIGN, 1);
+        self.push(level + 2, "int", "size", 4, 0);
+    }
+
+    fn push_string(&mut self, level: u8, name: &str) {
+        self.push_array_header(level, "string", name);
+        self.push(level + 2, "char", "data", 1, 0);
+    }
+
+    fn push_pptr(&mut self, level: u8, type_name: &str, name: &str) {
+        self.push(level, &format!("PPtr<{type_name}>"), name, 12, 0);
+        if self.is_version_at_least(&[5]) {
+            self.push_fields(
+                level + 1,
+                &[("int", "m_FileID", 4), ("SInt64", "m_PathID", 8)],
+            );
+        } else {
+            self.push_fields(level + 1, &[("int", "m_FileID", 4), ("int", "m_PathID", 4)]);
+        }
+    }
+
+    fn add_class_fields(
+        &mut self,
+        type_definition: &'a ManagedTypeDefinition,
+        generic_arguments: &[ManagedType],
+        level: u8,
+        depth: usize,
+    ) -> Option<()> {
+        if let Some(base_type) = &type_definition.base_type {
+            let base_type = substitute(base_type, generic_arguments);
+            let (base_type_name, base_generic_arguments) = match &base_type {
+                ManagedType::Named(name) => (name.as_str(), vec![]),
+                ManagedType::GenericInstance(name, arguments) => (name.as_str(), arguments.clone()),
+                _ => return None,
+            };
+            if !is_serialization_root(base_type_name) {
+                let base_type_definition = self.resolve(base_type_name)?;
+                self.add_class_fields(base_type_definition, &base_generic_arguments, level, depth)?;
+            }
+        }
+
+        for field in &type_definition.fields {
+            if !field.is_serialized() {
+                continue;
+            }
+            if field.has_serialize_reference {
+                // managed reference registry layout is not supported.
+                return None;
+            }
+            let field_type = substitute(&field.field_type, generic_arguments);
+            self.add_field(&field.name, &field_type, level, depth, false)?;
+        }
+        Some(())
+    }
+
+    /// Push nodes of a field, push nothing if unity not serialize the field type.
+    /// Return None if the layout cannot be determined.
+    fn add_field(
+        &mut self,
+        name: &str,
+        field_type: &ManagedType,
+        level: u8,
+        depth: usize,
+        is_array_item: bool,
+    ) -> Option<()> {
+        let align = if is_array_item { 0 } else { META_FLAG_ALIGN };
+        match field_type {
+            ManagedType::Boolean => self.push(level, "bool", name, 1, align),
+            ManagedType::Char => self.push(level, "UInt16", name, 2, align),
+            ManagedType::SByte => self.push(level, "SInt8", name, 1, align),
+            ManagedType::Byte => self.push(level, "UInt8", name, 1, align),
+            ManagedType::Int16 => self.push(level, "SInt16", name, 2, align),
+            ManagedType::UInt16 => self.push(level, "UInt16", name, 2, align),
+            ManagedType::Int32 => self.push(level, "int", name, 4, 0),
+            ManagedType::UInt32 => self.push(level, "unsigned int", name, 4, 0),
+            ManagedType::Int64 => self.push(level, "SInt64", name, 8, 0),
+            ManagedType::UInt64 => self.push(level, "UInt64", name, 8, 0),
+            ManagedType::Single => self.push(level, "float", name, 4, 0),
+            ManagedType::Double => self.push(level, "double", name, 8, 0),
+            ManagedType::String => self.push_string(level, name),
+            ManagedType::SzArray(item_type) => {
+                if !is_array_item {
+                    self.add_vector(name, item_type, level, depth)?;
+                }
+            }
+            ManagedType::GenericInstance(type_name, arguments) => {
+                if type_name == "System.Collections.Generic.List`1" {
+                    if !is_array_item {
+                        self.add_vector(name, arguments.first()?, level, depth)?;
+                    }
+                } else if self.is_version_at_least(&[2020, 1]) {
+                    let type_definition = self.resolve(type_name)?;
+                    self.add_named_type(name, type_definition, arguments, level, depth)?;
+                }
+            }
+            ManagedType::Named(type_name) => {
+                if self.add_engine_struct(name, type_name, level) {
+                    return Some(());
+                }
+                let Some(type_definition) = self.resolve(type_name) else {
+                    // unresolved system types like Dictionary are not serialized.
+                    return if type_name.starts_with("System.") {
+                        Some(())
+                    } else {
+                        None
+                    };
+                };
+                self.add_named_type(name, type_definition, &[], level, depth)?;
+            }
+            ManagedType::Object | ManagedType::GenericParameter(_) | ManagedType::Unsupported => (),
+        }
+        Some(())
+    }
+
+    fn add_vector(
+        &mut self,
+        name: &str,
+        item_type: &ManagedType,
+        level: u8,
+        depth: usize,
+    ) -> Option<()> {
+        let nodes_len = self.nodes.len();
+        self.push_array_header(level, "vector", name);
+        self.add_field("data", item_type, level + 2, depth, true)?;
+        if self.nodes.len() == nodes_len + 3 {
+            // item type is not serialized.
+            self.nodes.truncate(nodes_len);
+        }
+        Some(())
+    }
+
+    fn add_named_type(
+        &mut self,
+        name: &str,
+        type_definition: &'a ManagedTypeDefinition,
+        generic_arguments: &[ManagedType],
+        level: u8,
+        depth: usize,
+    ) -> Option<()> {
+        if let Some(unity_object_name) = self.get_unity_object_name(type_definition)? {
+            self.push_pptr(level, &unity_object_name, name);
+        } else if type_definition.is_enum() {
+            let underlying_type = type_definition.get_enum_underlying_type()?.clone();
+            self.add_field(name, &underlying_type, level, depth, false)?;
+        } else if type_definition.is_serializable()
+            && type_definition.get_generic_parameter_count() == generic_arguments.len() as u32
+            && depth < MAX_SERIALIZATION_DEPTH
+        {
+            let type_name = type_definition
+                .name
+                .split_once('`')
+                .map(|(n, _)| n)
+                .unwrap_or(&type_definition.name);
+            self.push(level, type_name, name, -1, 0);
+            self.add_class_fields(type_definition, generic_arguments, level + 1, depth + 1)?;
+        }
+        Some(())
+    }
+
+    /// Some(Some(name)) for UnityEngine.Object, managed script class name start with '$'.
+    fn get_unity_object_name(
+        &self,
+        type_definition: &ManagedTypeDefinition,
+    ) -> Option<Option<String>> {
+        let mut current = type_definition;
+        let mut is_script = true;
+        loop {
+            if current.full_name == "UnityEngine.Object" {
+                return Some(Some(if is_script {
+                    format!("${}", type_definition.name)
+                } else {
+                    type_definition.name.clone()
+                }));
+            }
+            if current.full_name == "UnityEngine.MonoBehaviour"
+                || current.full_name == "UnityEngine.ScriptableObject"
+            {
+                is_script = type_definition.full_name != current.full_name;
+                if !is_script {
+                    return Some(Some(type_definition.name.clone()));
+                }
+                return Some(Some(format!("${}", type_definition.name)));
+            }
+            if type_definition.full_name == current.full_name
+                && current.namespace.starts_with("UnityEngine")
+            {
+                is_script = false;
+            }
+            let Some(base_type_name) = current.get_base_type_name() else {
+                return Some(None);
+            };
+            if base_type_name == "System.Object"
+                || base_type_name == "System.ValueType"
+                || base_type_name == "System.Enum"
+            {
+                return Some(None);
+            }
+            current = self.resolve(base_type_name)?;
+        }
+    }
+
+    /// Unity builtin structs which are not marked [Serializable].
+    fn add_engine_struct(&mut self, name: &str, type_name: &str, level: u8) -> bool {
+        let child = level + 1;
+        match type_name {
+            "UnityEngine.Vector2" => {
+                self.push(level, "Vector2f", name, 8, 0);
+                self.push_fields(child, &[("float", "x", 4), ("float", "y", 4)]);
+            }
+            "UnityEngine.Vector3" => {
+                self.push(level, "Vector3f", name, 12, 0);
+                self.push_fields(
+                    child,
+                    &[("float", "x", 4), ("float", "y", 4), ("float", "z", 4)],
+                );
+            }
+            "UnityEngine.Vector4" | "UnityEngine.Quaternion" => {
+                let type_name = if type_name == "UnityEngine.Vector4" {
+                    "Vector4f"
+                } else {
+                    "Quaternionf"
+                };
+                self.push(level, type_name, name, 16, 0);
+                self.push_fields(
+                    child,
+                    &[
+                        ("float", "x", 4),
+                        ("float", "y", 4),
+                        ("float", "z", 4),
+                        ("float", "w", 4),
+                    ],
+                );
+            }
+            "UnityEngine.Color" => {
+                self.push(level, "ColorRGBA", name, 16, 0);
+                self.push_fields(
+                    child,
+                    &[
+                        ("float", "r", 4),
+                        ("float", "g", 4),
+                        ("float", "b", 4),
+                        ("float", "a", 4),
+                    ],
+                );
+            }
+            "UnityEngine.Color32" => {
+                self.push(level, "ColorRGBA", name, 4, 0);
+                self.push_fields(child, &[("unsigned int", "rgba", 4)]);
+            }
+            "UnityEngine.Rect" => {
+                self.push(level, "Rectf", name, 16, 0);
+                self.push_fields(
+                    child,
+                    &[
+                        ("float", "x", 4),
+                        ("float", "y", 4),
+                        ("float", "width", 4),
+                        ("float", "height", 4),
+                    ],
+                );
+            }
+            "UnityEngine.Matrix4x4" => {
+                self.push(level, "Matrix4x4f", name, 64, 0);
+                for row in 0..4 {
+                    for column in 0..4 {
+                        self.push(child, "float", &format!("e{row}{column}"), 4, 0);
+                    }
+                }
+            }
+            "UnityEngine.Bounds" => {
+                self.push(level, "AABB", name, 24, 0);
+                for vector_name in ["m_Center", "m_Extent"] {
+                    self.push(child, "Vector3f", vector_name, 12, 0);
+                    self.push_fields(
+                        child + 1,
+                        &[("float", "x", 4), ("float", "y", 4), ("float", "z", 4)],
+                    );
+                }
+            }
+            "UnityEngine.LayerMask" => {
+                self.push(level, "BitField", name, 4, 0);
+                self.push_fields(child, &[("unsigned int", "m_Bits", 4)]);
+            }
+            "UnityEngine.Vector2Int" => {
+                self.push(level, "int2_storage", name, 8, 0);
+                self.push_fields(child, &[("int", "m_X", 4), ("int", "m_Y", 4)]);
+            }
+            "UnityEngine.Vector3Int" => {
+                self.push(level, "int3_storage", name, 12, 0);
+                self.push_fields(
+                    child,
+                    &[("int", "m_X", 4), ("int", "m_Y", 4), ("int", "m_Z", 4)],
+                );
+            }
+            "UnityEngine.RectInt" => {
+                self.push(level, "RectInt", name, 16, 0);
+                self.push_fields(
+                    child,
+                    &[
+                        ("int", "m_XMin", 4),
+                        ("int", "m_YMin", 4),
+                        ("int", "m_Width", 4),
+                        ("int", "m_Height", 4),
+                    ],
+                );
+            }
+            "UnityEngine.BoundsInt" => {
+                self.push(level, "BoundsInt", name, 24, 0);
+                for vector_name in ["m_Position", "m_Size"] {
+                    self.push(child, "int3_storage", vector_name, 12, 0);
+                    self.push_fields(
+                        child + 1,
+                        &[("int", "m_X", 4), ("int", "m_Y", 4), ("int", "m_Z", 4)],
+                    );
+                }
+            }
+            "UnityEngine.RectOffset" => {
+                self.push(level, "RectOffset", name, 16, 0);
+                self.push_fields(
+                    child,
+                    &[
+                        ("int", "m_Left", 4),
+                        ("int", "m_Right", 4),
+                        ("int", "m_Top", 4),
+                        ("int", "m_Bottom", 4),
+                    ],
+                );
+            }
+            "UnityEngine.AnimationCurve" => {
+                self.push(level, "AnimationCurve", name, -1, 0);
+                self.push_array_header(child, "vector", "m_Curve");
+                let keyframe_level = child + 2;
+                if self.is_version_at_least(&[2018]) {
+                    self.push(keyframe_level, "Keyframe", "data", 28, 0);
+                    self.push_fields(
+                        keyframe_level + 1,
+                        &[
+                            ("float", "time", 4),
+                            ("float", "value", 4),
+                            ("float", "inSlope", 4),
+                            ("float", "outSlope", 4),
+                            ("int", "weightedMode", 4),
+                            ("float", "inWeight", 4),
+                            ("float", "outWeight", 4),
+                        ],
+                    );
+                } else {
+                    self.push(keyframe_level, "Keyframe", "data", 16, 0);
+                    self.push_fields(
+                        keyframe_level + 1,
+                        &[
+                            ("float", "time", 4),
+                            ("float", "value", 4),
+                            ("float", "inSlope", 4),
+                            ("float", "outSlope", 4),
+                        ],
+                    );
+                }
+                self.push_fields(
+                    child,
+                    &[("int", "m_PreInfinity", 4), ("int", "m_PostInfinity", 4)],
+                );
+                if self.is_version_at_least(&[5, 3]) {
+                    self.push_fields(child, &[("int", "m_RotationOrder", 4)]);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+type ScriptTypeFieldsCacheKey = (String, usize, String, String);
+type ScriptTypeFields = Option<Vec<Arc<Box<dyn TypeField + Send + Sync>>>>;
+
+/// Provide MonoBehaviour type tree generated from managed assemblies (Managed/*.dll).
+pub struct ManagedAssemblyTypeTreeProvider {
+    assemblies: Vec<ManagedAssembly>,
+    script_type_fields_cache_map: Mutex<HashMap<ScriptTypeFieldsCacheKey, ScriptTypeFields>>,
+}
+
+impl ManagedAssemblyTypeTreeProvider {
+    pub fn new() -> Self {
+        ManagedAssemblyTypeTreeProvider {
+            assemblies: Vec::new(),
+            script_type_fields_cache_map: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn add_assembly(&mut self, assembly: ManagedAssembly) {
+        if let Ok(mut cache_map) = self.script_type_fields_cache_map.lock() {
+            cache_map.clear();
+        }
+        self.assemblies.push(assembly);
+    }
+
+    /// Read all managed assemblies in dir, files which are not managed assembly are skipped.
+    pub fn read_managed_dir<P: AsRef<Path>>(&mut self, dir_path: P) -> ReadResult<()> {
+        for entry in WalkDir::new(dir_path).into_iter().flatten() {
+            if entry.file_type().is_file()
+                && entry.path().extension().is_some_and(|ext| ext == "dll")
+            {
+                let file = OpenOptions::new().read(true).open(entry.path())?;
+                if let Ok(assembly) = ManagedAssembly::read(&mut BufReader::new(file)) {
+                    self.add_assembly(assembly);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Read assemblies of IL2CPP build from global-metadata.dat and
+    /// the native binary (GameAssembly.dll or libil2cpp.so).
+    pub fn read_il2cpp<M: Read + Seek, B: Read + Seek>(
+        &mut self,
+        metadata_reader: &mut M,
+        binary_reader: &mut B,
+    ) -> ReadResult<()> {
+        for assembly in ManagedAssembly::read_il2cpp(metadata_reader, binary_reader)? {
+            self.add_assembly(assembly);
+        }
+        Ok(())
+    }
+
+    pub fn get_assemblies(&self) -> &Vec<ManagedAssembly> {
+        &self.assemblies
+    }
+}
+
+impl Default for ManagedAssemblyTypeTreeProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeTreeProvider for ManagedAssemblyTypeTreeProvider {
+    fn get_type_object_args_by_version_class_id(
+        &self,
+        _version: &str,
+        _class_id: i32,
+    ) -> Option<TypeTreeObjectBinReadClassArgs> {
+        None
+    }
+
+    fn get_mono_behaviour_type_object_args(
+        &self,
+        version: &str,
+        base_class_args: &TypeTreeObjectBinReadClassArgs,
+        assembly_name: &str,
+        namespace: &str,
+        class_name: &str,
+    ) -> Option<TypeTreeObjectBinReadClassArgs> {
+        let base_type_fields = base_class_args.get_type_fields();
+        let key = (
+            version.to_owned(),
+            base_type_fields.len(),
+            get_assembly_name(assembly_name).to_owned(),
+            get_full_name(namespace, class_name),
+        );
+        let mut cache_map = self.script_type_fields_cache_map.lock().ok()?;
+        let script_type_fields = cache_map
+            .entry(key)
+            .or_insert_with(|| {
+                MonoBehaviourTypeTreeGenerator::new(
+                    &self.assemblies,
+                    version,
+                    base_type_fields.len() as i32,
+                )?
+                .generate(assembly_name, namespace, class_name)
+            })
+            .clone()?;
+        let mut type_fields = base_type_fields.clone();
+        type_fields.extend(script_type_fields);
+        Some(TypeTreeObjectBinReadClassArgs::new(
+            base_class_args.get_class_id(),
+            type_fields,
+        ))
+    }
+}
+
+/// Classes whose fields are serialized by engine instead of script.
+fn is_serialization_root(full_name: &str) -> bool {
+    matches!(
+        full_name,
+        "System.Object"
+            | "System.ValueType"
+            | "UnityEngine.Object"
+            | "UnityEngine.Component"
+            | "UnityEngine.Behaviour"
+            | "UnityEngine.MonoBehaviour"
+            | "UnityEngine.ScriptableObject"
+    )
+}
+
+fn substitute(managed_type: &ManagedType, generic_arguments: &[ManagedType]) -> ManagedType {
+    match managed_type {
+        ManagedType::GenericParameter(index) => generic_arguments
+            .get(*index as usize)
+            .cloned()
+            .unwrap_or(ManagedType::Unsupported),
+        ManagedType::SzArray(item_type) => {
+            ManagedType::SzArray(Box::new(substitute(item_type, generic_arguments)))
+        }
+        ManagedType::GenericInstance(name, arguments) => ManagedType::GenericInstance(
+            name.clone(),
+            arguments
+                .iter()
+                .map(|argument| substitute(argument, generic_arguments))
+                .collect(),
+        ),
+        _ => managed_type.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, attributes: u16, field_type: ManagedType) -> ManagedField {
+        ManagedField {
+            name: name.to_owned(),
+            attributes,
+            field_type,
+            has_serialize_field: false,
+            has_serialize_reference: false,
+        }
+    }
+
+    fn type_definition(
+        namespace: &str,
+        name: &str,
+        attributes: u32,
+        base_type: &str,
+        fields: Vec<ManagedField>,
+    ) -> ManagedTypeDefinition {
+        ManagedTypeDefinition {
+            namespace: namespace.to_owned(),
+            name: name.to_owned(),
+            full_name: get_full_name(namespace, name),
+            attributes,
+            base_type: Some(ManagedType::Named(base_type.to_owned())),
+            fields,
+        }
+    }
+
+    #[test]
+    fn test_generate_mono_behaviour_type_tree() {
+        let mut engine = ManagedAssembly {
+            name: "UnityEngine.CoreModule".to_owned(),
+            ..Default::default()
+        };
+        for (name, base_type) in [
+            ("Object", "System.Object"),
+            ("Component", "UnityEngine.Object"),
+            ("Behaviour", "UnityEngine.Component"),
+            ("MonoBehaviour", "UnityEngine.Behaviour"),
+            ("Texture2D", "UnityEngine.Object"),
+        ] {
+            let definition = type_definition("UnityEngine", name, 0, base_type, vec![]);
+            engine
+                .types
+                .insert(definition.full_name.clone(), definition);
+        }
+
+        let mut game = ManagedAssembly {
+            name: "Assembly-CSharp.dll".to_owned(),
+            ..Default::default()
+        };
+        for definition in [
+            type_definition(
+                "Game",
+                "Item",
+                TYPE_ATTRIBUTES_SERIALIZABLE,
+                "System.Object",
+                vec![field("id", FIELD_ATTRIBUTES_PUBLIC, ManagedType::Int32)],
+            ),
+            type_definition(
+                "Game",
+                "Kind",
+                0,
+                "System.Enum",
+                vec![
+                    field("value__", FIELD_ATTRIBUTES_PUBLIC, ManagedType::Byte),
+                    field(
+                        "None",
+                        FIELD_ATTRIBUTES_PUBLIC
+                            | FIELD_ATTRIBUTES_STATIC
+                            | FIELD_ATTRIBUTES_LITERAL,
+                        ManagedType::Named("Game.Kind".to_owned()),
+                    ),
+                ],
+            ),
+            type_definition(
+                "Game",
+                "Player",
+                0,
+                "UnityEngine.MonoBehaviour",
+                vec![
+                    field("speed", FIELD_ATTRIBUTES_PUBLIC, ManagedType::Single),
+                    field("hidden", 0x1, ManagedType::Int32),
+                    ManagedField {
+                        has_serialize_field: true,
+                        ..field("title", 0x1, ManagedType::String)
+                    },
+                    field(
+                        "ignored",
+                        FIELD_ATTRIBUTES_PUBLIC | FIELD_ATTRIBUTES_NOT_SERIALIZED,
+                        ManagedType::Int32,
+                    ),
+                    field(
+                        "kind",
+                        FIELD_ATTRIBUTES_PUBLIC,
+                        ManagedType::Named("Game.Kind".to_owned()),
+                    ),
+                    field(
+                        "items",
+                        FIELD_ATTRIBUTES_PUBLIC,
+                        ManagedType::GenericInstance(
+                            "System.Collections.Generic.List`1".to_owned(),
+                            vec![ManagedType::Named("Game.Item".to_owned())],
+                        ),
+                    ),
+                    field(
+                        "icon",
+                        FIELD_ATTRIBUTES_PUBLIC,
+                        ManagedType::Named("UnityEngine.Texture2D".to_owned()),
+                    ),
+                    field(
+                        "position",
+                        FIELD_ATTRIBUTES_PUBLIC,
+                        ManagedType::Named("UnityEngine.Vector3".to_owned()),
+                    ),
+                ],
+            ),
+        ] {
+            game.types.insert(definition.full_name.clone(), definition);
+        }
+
+        let assemblies = [engine, game];
+        let type_fields = MonoBehaviourTypeTreeGenerator::new(&assemblies, "2019.4.1f1", 10)
+            .unwrap()
+            .generate("Assembly-CSharp", "Game", "Player")
+            .unwrap();
+        let nodes: Vec<(u8, &str, &str)> = type_fields
+            .iter()
+            .map(|f| (f.get_level(), f.get_type().as_str(), f.get_name().as_str()))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![
+                (1, "float", "speed"),
+                (1, "string", "title"),
+                (2, "Array", "Array"),
+                (3, "int", "size"),
+                (3, "char", "data"),
+                (1, "UInt8", "kind"),
+                (1, "vector", "items"),
+                (2, "Array", "Array"),
+                (3, "int", "size"),
+                (3, "Item", "data"),
+                (4, "int", "id"),
+                (1, "PPtr<Texture2D>", "icon"),
+                (2, "int", "m_FileID"),
+                (2, "SInt64", "m_PathID"),
+                (1, "Vector3f", "position"),
+                (2, "float", "x"),
+                (2, "float", "y"),
+                (2, "float", "z"),
+            ]
+        );
+        assert_eq!(type_fields[0].get_index(), 10);
+        assert!(type_fields[5].is_align());
+    }
+}
@@ -1,14 +1,29 @@
 pub mod convert;
+#[cfg(feature = "serde")]
+pub mod deserializer;
+#[cfg(feature = "json")]
+pub mod json_export;
+pub mod managed;
+pub mod provider;
+pub mod query;
 pub mod reader;
 #[cfg(feature = "type-tree-json")]
 pub mod type_tree_json;
+#[cfg(feature = "type-tree-tpk")]
+pub mod type_tree_tpk;
+pub mod visitor;
+pub mod writer;
+pub mod yaml_export;
+pub mod yaml_import;
 
 use std::{
-    collections::HashMap,
     fmt::Debug,
-    sync::{Arc, RwLock},
+    io::Cursor,
+    sync::{Arc, OnceLock, RwLock},
 };
 
+use indexmap::IndexMap;
+
 use crate::{
     error::{Error, ReadResult},
     type_tree::convert::TryCast,
@@ -32,7 +47,8 @@ pub trait TypeField: Debug {
 #[derive(Debug, Clone)]
 pub enum FieldValue {
     DataOffset(DataOffset),
-    Fields(HashMap<String, Field>),
+    /// Children in declaration order.
+    Fields(IndexMap<String, Field>),
     Array(Box<ArrayField>),
 }
 
@@ -45,10 +61,140 @@ pub struct ArrayField {
     data: ArrayFieldValue,
 }
 
+impl ArrayField {
+    /// Count of items, the size field is read for items of a fixed size.
+    pub fn get_size(
+        &self,
+        object_data_buff: &[u8],
+        field_cast_args: &FieldCastArgs,
+    ) -> ReadResult<usize> {
+        match &self.data {
+            ArrayFieldValue::ArrayItems(items) => Ok(items.len()),
+            ArrayFieldValue::DataOffset(_) => {
+                let size: i32 = self
+                    .array_size
+                    .try_cast_to(object_data_buff, field_cast_args)?;
+                Ok(size.max(0) as usize)
+            }
+        }
+    }
+
+    /// Item at index with the cast args to read it. Items of a fixed size share one field
+    /// which is read at the offset of the item, other items are read on first use.
+    pub fn get_item(
+        &self,
+        index: usize,
+        object_data_buff: &[u8],
+        field_cast_args: &FieldCastArgs,
+    ) -> ReadResult<(&Field, FieldCastArgs)> {
+        match &self.data {
+            ArrayFieldValue::ArrayItems(items) => {
+                Ok((items.get(index, object_data_buff)?, field_cast_args.clone()))
+            }
+            ArrayFieldValue::DataOffset(DataOffset::AbsDataOffset(offset)) => {
+                let (Some(item_field), Some(item_size)) = (&self.item_field, self.item_field_size)
+                else {
+                    return Err(Error::AsSliceError(
+                        "Fix item size array cannot get item field.",
+                    ));
+                };
+                let mut item_cast_args = field_cast_args.clone();
+                item_cast_args.field_offset = Some((offset + item_size * index as u64) as i64);
+                Ok((item_field, item_cast_args))
+            }
+            ArrayFieldValue::DataOffset(DataOffset::ArrayItemOffset(_)) => {
+                Err(Error::ArrayItemOffsetError)
+            }
+        }
+    }
+
+    /// Every item with the cast args to read it, see get_item.
+    pub fn iter_items<'a>(
+        &'a self,
+        object_data_buff: &'a [u8],
+        field_cast_args: &'a FieldCastArgs,
+    ) -> ReadResult<impl Iterator<Item = ReadResult<(&'a Field, FieldCastArgs)>> + 'a> {
+        let size = self.get_size(object_data_buff, field_cast_args)?;
+        Ok((0..size).map(move |index| self.get_item(index, object_data_buff, field_cast_args)))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ArrayFieldValue {
     DataOffset(DataOffset),
-    ArrayItems(Vec<Field>),
+    ArrayItems(ArrayItems),
+}
+
+/// Items of an array whose item size is not fixed, like strings or classes holding arrays.
+///
+/// Only the start of each item is kept when the object is read, the layout of an item is read on
+/// first use. So reading an object with huge arrays cost the bytes which are used.
+#[derive(Debug, Clone)]
+pub struct ArrayItems {
+    endian: binrw::Endian,
+    item_type_fields: Vec<Arc<Box<dyn TypeField + Send + Sync>>>,
+    offsets: Vec<u64>,
+    align_base: u64,
+    items: Vec<OnceLock<Box<Field>>>,
+}
+
+impl ArrayItems {
+    fn new(
+        endian: binrw::Endian,
+        item_type_fields: Vec<Arc<Box<dyn TypeField + Send + Sync>>>,
+        offsets: Vec<u64>,
+        align_base: u64,
+    ) -> Self {
+        let items = offsets.iter().map(|_| OnceLock::new()).collect();
+        Self {
+            endian,
+            item_type_fields,
+            offsets,
+            align_base,
+            items,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Layout of the item at index, read from the data of the object on first use.
+    pub fn get(&self, index: usize, object_data_buff: &[u8]) -> ReadResult<&Field> {
+        let (Some(item), Some(offset)) = (self.items.get(index), self.offsets.get(index)) else {
+            return Err(Error::Other(format!(
+                "array item {index} out of range {}",
+                self.len()
+            )));
+        };
+        if let Some(field) = item.get() {
+            return Ok(field);
+        }
+        let mut read_offset = *offset;
+        let mut data_reader = Cursor::new(object_data_buff);
+        data_reader.set_position(read_offset);
+        let field = reader::read_field(
+            &mut data_reader,
+            self.endian,
+            &self.item_type_fields,
+            &mut 0,
+            &mut read_offset,
+            false,
+            self.align_base,
+        )?;
+        Ok(item.get_or_init(|| Box::new(field)))
+    }
+
+    pub fn iter<'a>(
+        &'a self,
+        object_data_buff: &'a [u8],
+    ) -> impl Iterator<Item = ReadResult<&'a Field>> + 'a {
+        (0..self.len()).map(move |index| self.get(index, object_data_buff))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -77,6 +223,16 @@ impl Field {
         object_data_buff: &'a [u8],
         field_cast_args: &FieldCastArgs,
     ) -> ReadResult<&'a [u8]> {
+        let (pos, size) = self.get_data_range(object_data_buff, field_cast_args)?;
+        Ok(&object_data_buff[pos as usize..(pos + size) as usize])
+    }
+
+    /// Position and size of the data of a primitive field or a fixed item size array.
+    fn get_data_range(
+        &self,
+        object_data_buff: &[u8],
+        field_cast_args: &FieldCastArgs,
+    ) -> ReadResult<(u64, u64)> {
         let offset = field_cast_args.field_offset;
         let (pos, size) = match &self.data {
             FieldValue::DataOffset(data_offset) => {
@@ -117,7 +273,7 @@ impl Field {
             },
             FieldValue::Fields(_) => return Err(Error::AsSliceError("Cannot get fields data.")),
         };
-        Ok(&object_data_buff[pos as usize..(pos + size) as usize])
+        Ok((pos, size))
     }
 
     pub fn try_get_buff_type_and_type_size(&self) -> Option<(&String, i32)> {
@@ -134,6 +290,77 @@ impl Field {
         None
     }
 
+    fn collect_pptr_field_paths(
+        &self,
+        path: &mut Vec<String>,
+        paths: &mut Vec<Vec<String>>,
+        type_tree_object: &TypeTreeObject,
+    ) {
+        if self.get_type().starts_with("PPtr<") {
+            paths.push(path.clone());
+            return;
+        }
+        match &self.data {
+            FieldValue::DataOffset(_) => (),
+            FieldValue::Fields(fields) => {
+                for (name, field) in fields {
+                    path.push(name.clone());
+                    field.collect_pptr_field_paths(path, paths, type_tree_object);
+                    path.pop();
+                }
+            }
+            FieldValue::Array(array) => {
+                // skip arrays like image data which can not hold any PPtr.
+                if !array
+                    .item_type_fields
+                    .iter()
+                    .any(|item_type| item_type.get_type().starts_with("PPtr<"))
+                {
+                    return;
+                }
+                match &array.data {
+                    ArrayFieldValue::ArrayItems(items) => {
+                        let items = items.iter(&type_tree_object.data_buff);
+                        for (index, item) in items.enumerate() {
+                            let Ok(item) = item else {
+                                continue;
+                            };
+                            path.push(index.to_string());
+                            item.collect_pptr_field_paths(path, paths, type_tree_object);
+                            path.pop();
+                        }
+                    }
+                    ArrayFieldValue::DataOffset(_) => {
+                        // fixed size items share one layout, every item has the same PPtr paths.
+                        if let Some(item_field) = &array.item_field {
+                            let mut item_paths = Vec::new();
+                            item_field.collect_pptr_field_paths(
+                                &mut Vec::new(),
+                                &mut item_paths,
+                                type_tree_object,
+                            );
+                            let size: i32 = array
+                                .array_size
+                                .try_cast_to(
+                                    &type_tree_object.data_buff,
+                                    &type_tree_object.get_field_cast_args(),
+                                )
+                                .unwrap_or_default();
+                            for index in 0..size {
+                                for item_path in &item_paths {
+                                    let mut item_full_path = path.clone();
+                                    item_full_path.push(index.to_string());
+                                    item_full_path.extend_from_slice(item_path);
+                                    paths.push(item_full_path);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     fn display_field(
         &self,
         p: &String,
@@ -180,7 +407,7 @@ impl Field {
                     .display_field(&np, object_data_buff, field_cast_args);
                 match &ar.data {
                     ArrayFieldValue::ArrayItems(ai) => {
-                        if let Some(aii) = ai.get(0) {
+                        if let Ok(aii) = ai.get(0, object_data_buff) {
                             aii.display_field(&np, object_data_buff, field_cast_args);
                         } else {
                             for item in &ar.item_type_fields {
@@ -250,7 +477,9 @@ impl Field {
                                     );
                                 }
                                 ArrayFieldValue::ArrayItems(items) => {
-                                    if let Some(field) = items.get(index as usize) {
+                                    if let Ok(field) =
+                                        items.get(index as usize, &type_tree_object.data_buff)
+                                    {
                                         return field.get_field(
                                             path,
                                             field_offset,
@@ -279,6 +508,8 @@ pub struct TypeTreeObject {
     data_layout: Field,
     data_buff: Vec<u8>,
     pub external_data: Option<Vec<u8>>,
+    /// Unity version of the serialized file the object is read from.
+    pub unity_version: Option<String>,
 }
 
 impl TypeTreeObject {
@@ -302,6 +533,14 @@ impl TypeTreeObject {
         feild.try_as_slice(&self.data_buff, &field_cast_args)
     }
 
+    /// Paths of every PPtr field, PPtr in arrays are listed for each item.
+    pub fn get_pptr_field_paths(&self) -> Vec<Vec<String>> {
+        let mut paths = Vec::new();
+        self.data_layout
+            .collect_pptr_field_paths(&mut Vec::new(), &mut paths, self);
+        paths
+    }
+
     pub(super) fn get_field_by_path(&self, path: &str) -> ReadResult<(&Field, Option<i64>)> {
         let path: Vec<String> = path
             .split('/')
@@ -323,6 +562,28 @@ impl TypeTreeObject {
         self.data_layout.get_field(path, None, self)
     }
 
+    /// Field names in declaration order, or item indexes of an Array.
+    pub(super) fn get_child_names(&self, path: &[String]) -> Vec<String> {
+        let Some((field, _)) = self.get_field_by_path_list(path) else {
+            return Vec::new();
+        };
+        match &field.data {
+            FieldValue::DataOffset(_) => Vec::new(),
+            FieldValue::Fields(fields) => fields.keys().cloned().collect(),
+            FieldValue::Array(array) => {
+                let size = match &array.data {
+                    ArrayFieldValue::ArrayItems(items) => items.len(),
+                    ArrayFieldValue::DataOffset(_) => array
+                        .array_size
+                        .try_cast_to(&self.data_buff, &self.get_field_cast_args())
+                        .map(|size: i32| size.max(0) as usize)
+                        .unwrap_or_default(),
+                };
+                (0..size).map(|index| index.to_string()).collect()
+            }
+        }
+    }
+
     pub(super) fn get_field_cast_args(&self) -> FieldCastArgs {
         FieldCastArgs {
             endian: self.endian,
@@ -331,6 +592,110 @@ impl TypeTreeObject {
     }
 }
 
+/// Type field built in code, for objects which are not read from a file.
+#[derive(Debug)]
+struct BuiltinTypeField {
+    level: u8,
+    index: i32,
+    type_name: String,
+    name: String,
+    byte_size: i32,
+}
+
+impl TypeField for BuiltinTypeField {
+    fn get_version(&self) -> u16 {
+        1
+    }
+
+    fn get_level(&self) -> u8 {
+        self.level
+    }
+
+    fn is_array(&self) -> bool {
+        false
+    }
+
+    fn get_byte_size(&self) -> i32 {
+        self.byte_size
+    }
+
+    fn get_index(&self) -> i32 {
+        self.index
+    }
+
+    fn get_meta_flag(&self) -> i32 {
+        0
+    }
+
+    fn is_align(&self) -> bool {
+        false
+    }
+
+    fn get_ref_type_hash(&self) -> Option<u64> {
+        None
+    }
+
+    fn get_type(&self) -> &String {
+        &self.type_name
+    }
+
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+}
+
+impl TypeTreeObject {
+    /// PPtr holding only m_FileID and m_PathID, for a reference known without reading its file.
+    pub fn new_pptr(serialized_file_id: i64, file_id: i32, path_id: i64) -> TypeTreeObject {
+        let field = |level, index, type_name: &str, name: &str, byte_size, data| Field {
+            field_type: Arc::new(Box::new(BuiltinTypeField {
+                level,
+                index,
+                type_name: type_name.to_owned(),
+                name: name.to_owned(),
+                byte_size,
+            })),
+            data,
+        };
+        let fields = IndexMap::from([
+            (
+                "m_FileID".to_owned(),
+                field(
+                    1,
+                    1,
+                    "int",
+                    "m_FileID",
+                    4,
+                    FieldValue::DataOffset(DataOffset::AbsDataOffset(0)),
+                ),
+            ),
+            (
+                "m_PathID".to_owned(),
+                field(
+                    1,
+                    2,
+                    "SInt64",
+                    "m_PathID",
+                    8,
+                    FieldValue::DataOffset(DataOffset::AbsDataOffset(4)),
+                ),
+            ),
+        ]);
+        let mut data_buff = file_id.to_le_bytes().to_vec();
+        data_buff.extend(path_id.to_le_bytes());
+        TypeTreeObject {
+            endian: binrw::Endian::Little,
+            class_id: 0,
+            serialized_file_id,
+            path_id: 0,
+            data_layout: field(0, 0, "PPtr<Object>", "Base", 12, FieldValue::Fields(fields)),
+            data_buff,
+            external_data: None,
+            unity_version: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TypeTreeObjectRef {
     inner: Arc<RwLock<Box<TypeTreeObject>>>,
@@ -404,4 +769,86 @@ impl TypeTreeObjectRef {
     pub fn get_class_id(&self) -> i32 {
         self.inner.read().unwrap().class_id
     }
+
+    pub fn get_unity_version(&self) -> Option<String> {
+        self.inner.read().unwrap().unity_version.clone()
+    }
+
+    /// Names of the fields in declaration order, or item indexes if this ref is an Array.
+    pub fn get_child_names(&self) -> Vec<String> {
+        self.inner.read().unwrap().get_child_names(&self.path)
+    }
+
+    /// Refs of the fields in declaration order, or of the items if this ref is an Array.
+    pub fn get_children(&self) -> Vec<TypeTreeObjectRef> {
+        self.get_child_names()
+            .into_iter()
+            .map(|name| {
+                let mut path = self.path.clone();
+                path.push(name);
+                TypeTreeObjectRef {
+                    inner: self.inner.clone(),
+                    path,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{read_prefabs_object, ASSET_BUNDLE, PICKUP, PLAYER};
+
+    #[test]
+    fn test_iter_array_items() {
+        let asset_bundle = read_prefabs_object(ASSET_BUNDLE);
+        let object = asset_bundle.inner().read().unwrap();
+        let field_cast_args = object.get_field_cast_args();
+        let read_items = |path: &str, item_name: &str| -> Vec<(Field, FieldCastArgs)> {
+            let path: Vec<_> = path.split('/').map(str::to_owned).collect();
+            let (field, _) = object.get_field_by_path_list(&path).unwrap();
+            let FieldValue::Array(array) = &field.data else {
+                panic!("{path:?} is not an array");
+            };
+            array
+                .iter_items(&object.data_buff, &field_cast_args)
+                .unwrap()
+                .map(|item| {
+                    let (item, item_cast_args) = item.unwrap();
+                    let FieldValue::Fields(fields) = &item.data else {
+                        panic!("{path:?} items have no fields");
+                    };
+                    (fields[item_name].clone(), item_cast_args)
+                })
+                .collect()
+        };
+
+        // PPtr items have a fixed size, items holding strings are read lazily.
+        let path_ids: Vec<i64> = read_items("m_PreloadTable/Array", "m_PathID")
+            .iter()
+            .map(|(field, item_cast_args)| {
+                field
+                    .try_cast_to(&object.data_buff, item_cast_args)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(path_ids.len(), 6);
+        assert_eq!((path_ids[0], path_ids[2]), (PICKUP, PLAYER));
+        let names: Vec<String> = read_items("m_Container/Array", "first")
+            .iter()
+            .map(|(field, item_cast_args)| {
+                field
+                    .try_cast_to(&object.data_buff, item_cast_args)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "assets/prefabs/pickup.prefab",
+                "assets/prefabs/player.prefab"
+            ]
+        );
+    }
 }
@@ -0,0 +1,73 @@
+use super::reader::TypeTreeObjectBinReadClassArgs;
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+
+/// Source of type tree info for serialized files which are built without typetree.
+pub trait TypeTreeProvider {
+    fn get_type_object_args_by_version_class_id(
+        &self,
+        version: &str,
+        class_id: i32,
+    ) -> Option<TypeTreeObjectBinReadClassArgs>;
+
+    /// Extend the MonoBehaviour base type tree with the fields of a script class.
+    fn get_mono_behaviour_type_object_args(
+        &self,
+        _version: &str,
+        _base_class_args: &TypeTreeObjectBinReadClassArgs,
+        _assembly_name: &str,
+        _namespace: &str,
+        _class_name: &str,
+    ) -> Option<TypeTreeObjectBinReadClassArgs> {
+        None
+    }
+}
+
+static TYPE_TREE_PROVIDERS: Lazy<RwLock<Vec<Box<dyn TypeTreeProvider + Send + Sync>>>> =
+    Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Register a provider used when serialized file not contain typetree info.
+/// Providers are queried in the order they are added.
+pub fn add_type_tree_provider(provider: Box<dyn TypeTreeProvider + Send + Sync>) {
+    if let Ok(mut providers) = TYPE_TREE_PROVIDERS.write() {
+        providers.push(provider);
+    }
+}
+
+pub fn get_type_object_args_by_version_class_id(
+    version: &str,
+    class_id: i32,
+) -> Option<TypeTreeObjectBinReadClassArgs> {
+    if let Ok(providers) = TYPE_TREE_PROVIDERS.read() {
+        for provider in &*providers {
+            if let Some(args) = provider.get_type_object_args_by_version_class_id(version, class_id)
+            {
+                return Some(args);
+            }
+        }
+    }
+    None
+}
+
+pub fn get_mono_behaviour_type_object_args(
+    version: &str,
+    base_class_args: &TypeTreeObjectBinReadClassArgs,
+    assembly_name: &str,
+    namespace: &str,
+    class_name: &str,
+) -> Option<TypeTreeObjectBinReadClassArgs> {
+    if let Ok(providers) = TYPE_TREE_PROVIDERS.read() {
+        for provider in &*providers {
+            if let Some(args) = provider.get_mono_behaviour_type_object_args(
+                version,
+                base_class_args,
+                assembly_name,
+                namespace,
+                class_name,
+            ) {
+                return Some(args);
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,400 @@
+use std::collections::HashSet;
+
+use super::{convert::TryCastFrom, TypeTreeObject, TypeTreeObjectRef};
+use crate::error::{Error, ReadResult};
+
+/// Query of fields like `/Base/m_Component/Array/*/component`.
+///
+/// * `name` a field, or an item index of an Array.
+/// * `*` every field or every item.
+/// * `**` the node and every node under it.
+/// * `[path]`, `[path=="text"]`, `[path!=5]` after a step keep the nodes whose field at path
+///   exists, is or is not the value. Values are quoted strings, numbers, `true` or `false`.
+///
+/// The first step is the node the query start from, like `Base` of other paths.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Step {
+    name: StepName,
+    predicates: Vec<Predicate>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum StepName {
+    Name(String),
+    Any,
+    Recursive,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Predicate {
+    path: Vec<String>,
+    compare: Option<(bool, Literal)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    String(String),
+    Number(String),
+    Bool(bool),
+}
+
+impl Query {
+    pub fn parse(query: &str) -> ReadResult<Query> {
+        let error = |message: &str| Error::QueryError {
+            query: query.to_owned(),
+            message: message.to_owned(),
+        };
+        let mut steps = Vec::new();
+        for segment in split_unquoted(query, '/') {
+            if segment.is_empty() {
+                continue;
+            }
+            let (name, mut rest) = match segment.find('[') {
+                Some(index) => segment.split_at(index),
+                None => (segment, ""),
+            };
+            let name = match name.trim() {
+                "*" => StepName::Any,
+                "**" => StepName::Recursive,
+                "" => return Err(error("step without name")),
+                name => StepName::Name(name.to_owned()),
+            };
+            let mut predicates = Vec::new();
+            while !rest.is_empty() {
+                let end = find_unquoted(rest, ']')
+                    .filter(|_| rest.starts_with('['))
+                    .ok_or_else(|| error("predicate is not closed"))?;
+                predicates.push(parse_predicate(&rest[1..end]).map_err(error)?);
+                rest = rest[end + 1..].trim_start();
+            }
+            if name == StepName::Recursive && !predicates.is_empty() {
+                return Err(error("** can not have predicates"));
+            }
+            steps.push(Step { name, predicates });
+        }
+        if steps.is_empty() {
+            return Err(error("empty query"));
+        }
+        Ok(Query { steps })
+    }
+
+    /// Every node matched by the query, in declaration and item order.
+    pub fn find(&self, object: &TypeTreeObjectRef) -> ReadResult<Vec<TypeTreeObjectRef>> {
+        let type_tree_obj = object
+            .inner
+            .read()
+            .map_err(|e| Error::Other(e.to_string()))?;
+        let mut paths = Vec::new();
+        let (first, rest) = self.steps.split_first().unwrap();
+        let start = object.path.clone();
+        if type_tree_obj.get_field_by_path_list(&start).is_some() {
+            match first.name {
+                StepName::Recursive => find(&type_tree_obj, &self.steps, start, &mut paths),
+                _ => {
+                    if first.matches(&type_tree_obj, &start) {
+                        find(&type_tree_obj, rest, start, &mut paths)
+                    }
+                }
+            }
+        }
+        let mut found = HashSet::new();
+        Ok(paths
+            .into_iter()
+            .filter(|path| found.insert(path.clone()))
+            .map(|path| TypeTreeObjectRef {
+                inner: object.inner.clone(),
+                path,
+            })
+            .collect())
+    }
+}
+
+impl TypeTreeObjectRef {
+    /// Nodes under this ref matched by a query, see `Query`.
+    pub fn query(&self, query: &str) -> ReadResult<Vec<TypeTreeObjectRef>> {
+        Query::parse(query)?.find(self)
+    }
+}
+
+fn find(object: &TypeTreeObject, steps: &[Step], path: Vec<String>, paths: &mut Vec<Vec<String>>) {
+    let Some((step, rest)) = steps.split_first() else {
+        paths.push(path);
+        return;
+    };
+    match &step.name {
+        StepName::Name(name) => {
+            let mut child = path;
+            child.push(name.to_owned());
+            if object.get_field_by_path_list(&child).is_some() && step.matches(object, &child) {
+                find(object, rest, child, paths);
+            }
+        }
+        StepName::Any => {
+            for name in object.get_child_names(&path) {
+                let mut child = path.clone();
+                child.push(name);
+                if step.matches(object, &child) {
+                    find(object, rest, child, paths);
+                }
+            }
+        }
+        StepName::Recursive => {
+            find(object, rest, path.clone(), paths);
+            for name in object.get_child_names(&path) {
+                let mut child = path.clone();
+                child.push(name);
+                find(object, steps, child, paths);
+            }
+        }
+    }
+}
+
+impl Step {
+    fn matches(&self, object: &TypeTreeObject, path: &[String]) -> bool {
+        self.predicates
+            .iter()
+            .all(|predicate| predicate.matches(object, path))
+    }
+}
+
+impl Predicate {
+    fn matches(&self, object: &TypeTreeObject, path: &[String]) -> bool {
+        let mut path = path.to_vec();
+        path.extend_from_slice(&self.path);
+        if object.get_field_by_path_list(&path).is_none() {
+            return false;
+        }
+        let Some((equal, literal)) = &self.compare else {
+            return true;
+        };
+        let path = path.as_slice();
+        let same = match literal {
+            Literal::String(text) => String::try_cast_from(object, path).is_ok_and(|v| &v == text),
+            Literal::Bool(value) => bool::try_cast_from(object, path).is_ok_and(|v| &v == value),
+            Literal::Number(text) => number_equals(object, path, text),
+        };
+        same == *equal
+    }
+}
+
+/// Integer fields are compared as integers, so large path ids are exact.
+fn number_equals(object: &TypeTreeObject, path: &[String], text: &str) -> bool {
+    let float = text.parse::<f64>();
+    if let Ok(value) = i64::try_cast_from(object, path) {
+        return text
+            .parse::<i64>()
+            .map_or(float == Ok(value as f64), |n| n == value);
+    }
+    if let Ok(value) = u64::try_cast_from(object, path) {
+        return text
+            .parse::<u64>()
+            .map_or(float == Ok(value as f64), |n| n == value);
+    }
+    f64::try_cast_from(object, path)
+        .or_else(|_| f32::try_cast_from(object, path).map(|v| v as f64))
+        .is_ok_and(|value| float == Ok(value))
+}
+
+fn parse_predicate(text: &str) -> Result<Predicate, &'static str> {
+    let (path, compare) = match find_unquoted(text, '=') {
+        Some(index) if text[index..].starts_with("==") => {
+            (&text[..index], Some((true, &text[index + 2..])))
+        }
+        Some(index) if index > 0 && text[..index].ends_with('!') => {
+            (&text[..index - 1], Some((false, &text[index + 1..])))
+        }
+        Some(_) => return Err("predicate compare is not == or !="),
+        None => (text, None),
+    };
+    if path.trim().is_empty() {
+        return Err("predicate without path");
+    }
+    // `.` is the node itself.
+    let path = path
+        .trim()
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != ".")
+        .map(|s| s.to_owned())
+        .collect();
+    let compare = match compare {
+        Some((equal, value)) => Some((equal, parse_literal(value.trim())?)),
+        None => None,
+    };
+    Ok(Predicate { path, compare })
+}
+
+fn parse_literal(text: &str) -> Result<Literal, &'static str> {
+    let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'');
+    if let Some(quote) = quote {
+        let mut value = String::new();
+        let mut chars = text[1..].chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => value.push(chars.next().ok_or("unclosed string")?),
+                c if c == quote => {
+                    if !chars.as_str().is_empty() {
+                        return Err("text after string");
+                    }
+                    return Ok(Literal::String(value));
+                }
+                c => value.push(c),
+            }
+        }
+        return Err("unclosed string");
+    }
+    match text {
+        "true" => Ok(Literal::Bool(true)),
+        "false" => Ok(Literal::Bool(false)),
+        "" => Err("predicate without value"),
+        _ if text.parse::<f64>().is_ok() => Ok(Literal::Number(text.to_owned())),
+        _ => Err("value is not a string, number or bool"),
+    }
+}
+
+/// Index of the first `target` out of quotes and brackets.
+fn find_unquoted(text: &str, target: char) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut depth = 0;
+    for (index, c) in text.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ => (),
+        }
+        if c == target && depth == 0 {
+            return Some(index);
+        }
+    }
+    None
+}
+
+fn split_unquoted(text: &str, separator: char) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    while let Some(index) = find_unquoted(rest, separator) {
+        segments.push(&rest[..index]);
+        rest = &rest[index + separator.len_utf8()..];
+    }
+    segments.push(rest);
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{
+        read_game_object_yaml, read_prefabs_object, ASSET_BUNDLE, GAME_OBJECT_YAML, PICKUP, PLAYER,
+    };
+
+    #[test]
+    fn test_query() {
+        let object = TypeTreeObjectRef::from(read_game_object_yaml(GAME_OBJECT_YAML));
+        let paths = |query: &str| -> Vec<String> {
+            object
+                .query(query)
+                .unwrap()
+                .into_iter()
+                .map(|found| found.path.join("/"))
+                .collect()
+        };
+        assert_eq!(
+            object.get_child_names(),
+            [
+                "m_Component",
+                "m_Layer",
+                "m_Name",
+                "m_IsActive",
+                "m_LocalScale",
+                "m_Data",
+                "m_Tags"
+            ]
+        );
+        assert_eq!(
+            paths("/Base/m_Component/Array/*/component"),
+            [
+                "m_Component/Array/0/component",
+                "m_Component/Array/1/component"
+            ]
+        );
+        assert_eq!(
+            paths("/Base/m_Tags/Array/*[first==\"Body\"]/second"),
+            ["m_Tags/Array/0/second"]
+        );
+        assert_eq!(
+            paths("/**/m_PathID[.!=4]"),
+            ["m_Component/Array/1/component/m_PathID"]
+        );
+        assert_eq!(
+            paths("/**/component[m_PathID!=4]"),
+            ["m_Component/Array/1/component"]
+        );
+        assert_eq!(
+            paths("/Base[m_Name=='Hero']/m_Data/Array/1"),
+            ["m_Data/Array/1"]
+        );
+        assert_eq!(paths("/Base[m_Layer==6]"), Vec::<String>::new());
+        assert_eq!(paths("/Base/*[x==1]"), ["m_LocalScale"]);
+        assert_eq!(paths("/**/y").len(), 1);
+        assert!(Query::parse("/Base/*[m_Name==]").is_err());
+        assert!(Query::parse("/Base/*[m_Name==\"a\"").is_err());
+    }
+
+    #[test]
+    fn test_query_prefabs_bundle() {
+        let asset_bundle = read_prefabs_object(ASSET_BUNDLE);
+        let path_ids = |query: &str| -> Vec<i64> {
+            asset_bundle
+                .query(query)
+                .unwrap()
+                .iter()
+                .map(|found| i64::try_cast_from(found, &[] as &[String]).unwrap())
+                .collect()
+        };
+        assert_eq!(
+            path_ids("/Base/m_Container/Array/*/second/asset/m_PathID"),
+            [PICKUP, PLAYER]
+        );
+        assert_eq!(
+            path_ids(
+                "/Base/m_Container/Array/*[first==\"assets/prefabs/player.prefab\"]/second/asset/m_PathID"
+            ),
+            [PLAYER]
+        );
+        assert_eq!(
+            path_ids("/Base/m_Container/Array/*[second/preloadSize!=2]/second/asset/m_PathID"),
+            [PLAYER]
+        );
+        // the preload table and the container refer to the player, m_MainAsset do not.
+        assert_eq!(
+            asset_bundle
+                .query(&format!("/**/m_PathID[.=={PLAYER}]"))
+                .unwrap()
+                .into_iter()
+                .map(|found| found.path.join("/"))
+                .collect::<Vec<_>>(),
+            [
+                "m_PreloadTable/Array/2/m_PathID",
+                "m_Container/Array/1/second/asset/m_PathID"
+            ]
+        );
+        assert_eq!(path_ids("/Base/m_MainAsset/asset/m_PathID"), [0]);
+        assert!(path_ids("/Base/m_Container/Array/*[first=='missing']").is_empty());
+    }
+}
@@ -1,15 +1,15 @@
 use std::{
-    collections::HashMap,
     fmt::Debug,
     io::{ErrorKind, Read, Seek, SeekFrom},
     sync::Arc,
 };
 
 use binrw::{BinRead, BinResult, Endian, VecArgs};
+use indexmap::IndexMap;
 
 use crate::type_tree::{
     convert::{FieldCastArgs, TryRead},
-    ArrayField, ArrayFieldValue, DataOffset, FieldValue, TypeTreeObject,
+    ArrayField, ArrayFieldValue, ArrayItems, DataOffset, FieldValue, TypeTreeObject,
 };
 
 use super::{Field, TypeField};
@@ -48,6 +48,14 @@ impl TypeTreeObjectBinReadClassArgs {
             type_fields,
         }
     }
+
+    pub fn get_class_id(&self) -> i32 {
+        self.class_id
+    }
+
+    pub fn get_type_fields(&self) -> &Vec<Arc<Box<dyn TypeField + Send + Sync>>> {
+        &self.type_fields
+    }
 }
 
 impl BinRead for TypeTreeObject {
@@ -58,202 +66,17 @@ impl BinRead for TypeTreeObject {
         endian: Endian,
         args: Self::Args<'_>,
     ) -> BinResult<Self> {
-        fn read<R: Read + Seek>(
-            reader: &mut R,
-            endian: Endian,
-            type_fields: &Vec<Arc<Box<dyn TypeField + Send + Sync>>>,
-            field_index: &mut usize,
-            read_offset: &mut u64,
-            is_fix_size_array_item: bool,
-        ) -> BinResult<Field> {
-            let field = type_fields
-                .get(*field_index)
-                .ok_or(std::io::Error::from(ErrorKind::NotFound))?;
-            let field_level = field.get_level();
-            let field_value = if field.is_array() {
-                assert!(!is_fix_size_array_item);
-                *field_index += 1;
-                let size_start_pos = reader.stream_position()?;
-                let size_field =
-                    read(reader, endian, type_fields, field_index, read_offset, false)?;
-                reader.seek(SeekFrom::Start(size_start_pos))?;
-                let size: i32 = size_field
-                    .try_read_to(
-                        reader,
-                        &FieldCastArgs {
-                            endian,
-                            field_offset: None,
-                        },
-                    )
-                    .map_err(|_| std::io::Error::from(ErrorKind::NotFound))?;
-
-                *field_index += 1;
-                let item_field_index = *field_index;
-                let item_type_field = type_fields
-                    .get(item_field_index)
-                    .ok_or(std::io::Error::from(ErrorKind::NotFound))?;
-                let item_level = item_type_field.get_level();
-                let mut item_type_fields = Vec::new();
-                item_type_fields.push(item_type_field.clone());
-
-                while let Some(next_field) = type_fields.get(*field_index + 1) {
-                    if next_field.get_level() < item_level {
-                        break;
-                    }
-                    item_type_fields.push(next_field.clone());
-                    *field_index += 1;
-                }
-
-                let pos = reader.stream_position()?;
-                let is_pos_aligned = (pos % 4) == 0;
-                let fix_item_size = calc_no_array_field_size(&item_type_fields, &mut 0, &mut 0);
-                let mut buf_read_flag = false;
-                if let Some(byte_size) = fix_item_size {
-                    if (is_pos_aligned && ((byte_size % 4) == 0))
-                        || (item_type_fields.len() == 1
-                            && !item_type_fields.get(0).unwrap().is_align())
-                    {
-                        buf_read_flag = true;
-                    }
-                }
-
-                if let (Some(byte_size), true) = (fix_item_size, buf_read_flag) {
-                    let this_offset = *read_offset;
-                    let item_start_pos = reader.stream_position()?;
-                    let mut item_field_offset = 0;
-                    let item_field = read(
-                        reader,
-                        endian,
-                        &item_type_fields,
-                        &mut 0,
-                        &mut item_field_offset,
-                        true,
-                    )?;
-
-                    *read_offset += (byte_size * size as usize) as u64;
-                    reader.seek(SeekFrom::Start(
-                        item_start_pos + (byte_size * size as usize) as u64,
-                    ))?;
-
-                    Field {
-                        field_type: field.clone(),
-                        data: FieldValue::Array(
-                            ArrayField {
-                                array_size: size_field,
-                                item_type_fields,
-                                item_field: Some(item_field),
-                                item_field_size: Some(byte_size as u64),
-                                data: ArrayFieldValue::DataOffset(DataOffset::AbsDataOffset(
-                                    this_offset,
-                                )),
-                            }
-                            .into(),
-                        ),
-                    }
-                } else {
-                    let mut array = Vec::new();
-                    for _ in 0..size as usize {
-                        *field_index = item_field_index;
-                        array.push(read(
-                            reader,
-                            endian,
-                            type_fields,
-                            field_index,
-                            read_offset,
-                            false,
-                        )?);
-                    }
-
-                    Field {
-                        field_type: field.clone(),
-                        data: FieldValue::Array(
-                            ArrayField {
-                                array_size: size_field,
-                                item_type_fields,
-                                item_field: None,
-                                item_field_size: None,
-                                data: ArrayFieldValue::ArrayItems(array),
-                            }
-                            .into(),
-                        ),
-                    }
-                }
-            } else if let Some(next_field) = type_fields.get(*field_index + 1) {
-                if next_field.get_level() == field_level + 1 {
-                    let mut fields = HashMap::new();
-                    while let Some(next_field) = type_fields.get(*field_index + 1) {
-                        if next_field.get_level() == field_level + 1 {
-                            *field_index += 1;
-                            let field_data = read(
-                                reader,
-                                endian,
-                                type_fields,
-                                field_index,
-                                read_offset,
-                                is_fix_size_array_item,
-                            )?;
-                            fields.insert(field_data.get_name().clone(), field_data);
-                        } else if next_field.get_level() <= field_level {
-                            break;
-                        } else {
-                            panic!("{:#?} {:#?} ", next_field.get_level(), fields);
-                        }
-                    }
-
-                    Field {
-                        field_type: field.clone(),
-                        data: FieldValue::Fields(fields),
-                    }
-                } else {
-                    let this_offset = *read_offset;
-                    *read_offset += field.get_byte_size() as u64;
-                    reader.seek(SeekFrom::Current(field.get_byte_size() as i64))?;
-                    Field {
-                        field_type: field.clone(),
-                        data: if !is_fix_size_array_item {
-                            FieldValue::DataOffset(DataOffset::AbsDataOffset(this_offset))
-                        } else {
-                            FieldValue::DataOffset(DataOffset::ArrayItemOffset(this_offset))
-                        },
-                    }
-                }
-            } else {
-                let this_offset = *read_offset;
-                *read_offset += field.get_byte_size() as u64;
-                reader.seek(SeekFrom::Current(field.get_byte_size() as i64))?;
-                Field {
-                    field_type: field.clone(),
-                    data: if !is_fix_size_array_item {
-                        FieldValue::DataOffset(DataOffset::AbsDataOffset(this_offset))
-                    } else {
-                        FieldValue::DataOffset(DataOffset::ArrayItemOffset(this_offset))
-                    },
-                }
-            };
-
-            if field.is_align() {
-                let pos = reader.stream_position()?;
-                if pos % 4 != 0 {
-                    reader.seek(SeekFrom::Current((4 - (pos % 4)) as i64))?;
-                    *read_offset += 4 - (pos % 4);
-                }
-            }
-            // dbg!(reader.stream_position());
-            // dbg!(&field_value.data);
-            // field_value.display_field(&"".to_owned());
-            Ok(field_value)
-        }
-
         let start_pos = reader.stream_position()?;
         let mut index = 0;
         let mut data_buff_offset = 0;
-        let data = read(
+        let data = read_field(
             reader,
             endian,
             &args.class_args.type_fields,
             &mut index,
             &mut data_buff_offset,
             false,
+            0,
         )?;
         reader.seek(SeekFrom::Start(start_pos))?;
 
@@ -272,12 +95,324 @@ impl BinRead for TypeTreeObject {
                 },
             )?,
             external_data: None,
+            unity_version: None,
         })
     }
 }
 
+/// Read the layout of a field.
+///
+/// `align_base` is added to the reader position for alignment, it is not 0 when the reader is
+/// the data buffer of an object which does not start at an aligned position of its file.
+pub(super) fn read_field<R: Read + Seek>(
+    reader: &mut R,
+    endian: Endian,
+    type_fields: &[Arc<Box<dyn TypeField + Send + Sync>>],
+    field_index: &mut usize,
+    read_offset: &mut u64,
+    is_fix_size_array_item: bool,
+    align_base: u64,
+) -> BinResult<Field> {
+    let field = type_fields
+        .get(*field_index)
+        .ok_or(std::io::Error::from(ErrorKind::NotFound))?;
+    let field_level = field.get_level();
+    let field_value = if field.is_array() {
+        assert!(!is_fix_size_array_item);
+        *field_index += 1;
+        let size_start_pos = reader.stream_position()?;
+        let size_field = read_field(
+            reader,
+            endian,
+            type_fields,
+            field_index,
+            read_offset,
+            false,
+            align_base,
+        )?;
+        reader.seek(SeekFrom::Start(size_start_pos))?;
+        let size: i32 = size_field
+            .try_read_to(
+                reader,
+                &FieldCastArgs {
+                    endian,
+                    field_offset: None,
+                },
+            )
+            .map_err(|_| std::io::Error::from(ErrorKind::NotFound))?;
+        let size =
+            usize::try_from(size).map_err(|_| std::io::Error::from(ErrorKind::InvalidData))?;
+
+        *field_index += 1;
+        let item_field_index = *field_index;
+        type_fields
+            .get(item_field_index)
+            .ok_or(std::io::Error::from(ErrorKind::NotFound))?;
+        let item_field_end = get_item_type_fields_end(type_fields, item_field_index);
+        let item_type_fields = type_fields[item_field_index..item_field_end].to_vec();
+        *field_index = item_field_end - 1;
+
+        let pos = reader.stream_position()? + align_base;
+        if let Some(byte_size) = get_fix_item_size(&item_type_fields, pos) {
+            let this_offset = *read_offset;
+            let item_start_pos = reader.stream_position()?;
+            let mut item_field_offset = 0;
+            let item_field = read_field(
+                reader,
+                endian,
+                &item_type_fields,
+                &mut 0,
+                &mut item_field_offset,
+                true,
+                align_base,
+            )?;
+
+            *read_offset += (byte_size * size) as u64;
+            reader.seek(SeekFrom::Start(item_start_pos + (byte_size * size) as u64))?;
+
+            Field {
+                field_type: field.clone(),
+                data: FieldValue::Array(
+                    ArrayField {
+                        array_size: size_field,
+                        item_type_fields,
+                        item_field: Some(item_field),
+                        item_field_size: Some(byte_size as u64),
+                        data: ArrayFieldValue::DataOffset(DataOffset::AbsDataOffset(this_offset)),
+                    }
+                    .into(),
+                ),
+            }
+        } else {
+            // only the start of each item is kept, item layouts are read on first use.
+            let items_align_base = (pos + 4 - *read_offset % 4) % 4;
+            let mut offsets = Vec::with_capacity(size.min(0x10000));
+            for _ in 0..size {
+                offsets.push(*read_offset);
+                *field_index = item_field_index;
+                skip_field(
+                    reader,
+                    endian,
+                    type_fields,
+                    field_index,
+                    read_offset,
+                    align_base,
+                )?;
+            }
+            *field_index = item_field_end - 1;
+
+            Field {
+                field_type: field.clone(),
+                data: FieldValue::Array(
+                    ArrayField {
+                        array_size: size_field,
+                        item_type_fields: item_type_fields.clone(),
+                        item_field: None,
+                        item_field_size: None,
+                        data: ArrayFieldValue::ArrayItems(ArrayItems::new(
+                            endian,
+                            item_type_fields,
+                            offsets,
+                            items_align_base,
+                        )),
+                    }
+                    .into(),
+                ),
+            }
+        }
+    } else if has_children(type_fields, *field_index) {
+        let mut fields = IndexMap::new();
+        while let Some(child_index) = next_child_index(type_fields, field_level, *field_index)? {
+            *field_index = child_index;
+            let field_data = read_field(
+                reader,
+                endian,
+                type_fields,
+                field_index,
+                read_offset,
+                is_fix_size_array_item,
+                align_base,
+            )?;
+            fields.insert(field_data.get_name().clone(), field_data);
+        }
+
+        Field {
+            field_type: field.clone(),
+            data: FieldValue::Fields(fields),
+        }
+    } else {
+        let this_offset = *read_offset;
+        *read_offset += field.get_byte_size() as u64;
+        reader.seek(SeekFrom::Current(field.get_byte_size() as i64))?;
+        Field {
+            field_type: field.clone(),
+            data: if !is_fix_size_array_item {
+                FieldValue::DataOffset(DataOffset::AbsDataOffset(this_offset))
+            } else {
+                FieldValue::DataOffset(DataOffset::ArrayItemOffset(this_offset))
+            },
+        }
+    };
+
+    if field.is_align() {
+        let pos = reader.stream_position()? + align_base;
+        if !pos.is_multiple_of(4) {
+            reader.seek(SeekFrom::Current((4 - (pos % 4)) as i64))?;
+            *read_offset += 4 - (pos % 4);
+        }
+    }
+    Ok(field_value)
+}
+
+/// Move over a field like `read_field` without building its layout.
+fn skip_field<R: Read + Seek>(
+    reader: &mut R,
+    endian: Endian,
+    type_fields: &[Arc<Box<dyn TypeField + Send + Sync>>],
+    field_index: &mut usize,
+    read_offset: &mut u64,
+    align_base: u64,
+) -> BinResult<()> {
+    let field = type_fields
+        .get(*field_index)
+        .ok_or(std::io::Error::from(ErrorKind::NotFound))?;
+    let field_level = field.get_level();
+    if field.is_array() {
+        *field_index += 1;
+        let size_start_pos = reader.stream_position()?;
+        skip_field(
+            reader,
+            endian,
+            type_fields,
+            field_index,
+            read_offset,
+            align_base,
+        )?;
+        reader.seek(SeekFrom::Start(size_start_pos))?;
+        let size = i32::read_options(reader, endian, ())?;
+        let size =
+            usize::try_from(size).map_err(|_| std::io::Error::from(ErrorKind::InvalidData))?;
+
+        *field_index += 1;
+        let item_field_index = *field_index;
+        let item_field_end = get_item_type_fields_end(type_fields, item_field_index);
+
+        let pos = reader.stream_position()? + align_base;
+        if let Some(byte_size) =
+            get_fix_item_size(&type_fields[item_field_index..item_field_end], pos)
+        {
+            *read_offset += (byte_size * size) as u64;
+            reader.seek(SeekFrom::Current((byte_size * size) as i64))?;
+        } else {
+            for _ in 0..size {
+                *field_index = item_field_index;
+                skip_field(
+                    reader,
+                    endian,
+                    type_fields,
+                    field_index,
+                    read_offset,
+                    align_base,
+                )?;
+            }
+        }
+        *field_index = item_field_end - 1;
+    } else if has_children(type_fields, *field_index) {
+        while let Some(child_index) = next_child_index(type_fields, field_level, *field_index)? {
+            *field_index = child_index;
+            skip_field(
+                reader,
+                endian,
+                type_fields,
+                field_index,
+                read_offset,
+                align_base,
+            )?;
+        }
+    } else {
+        *read_offset += field.get_byte_size() as u64;
+        reader.seek(SeekFrom::Current(field.get_byte_size() as i64))?;
+    }
+
+    if field.is_align() {
+        let pos = reader.stream_position()? + align_base;
+        if !pos.is_multiple_of(4) {
+            reader.seek(SeekFrom::Current((4 - (pos % 4)) as i64))?;
+            *read_offset += 4 - (pos % 4);
+        }
+    }
+    Ok(())
+}
+
+fn has_children(type_fields: &[Arc<Box<dyn TypeField + Send + Sync>>], field_index: usize) -> bool {
+    type_fields.get(field_index + 1).is_some_and(|next_field| {
+        next_field
+            .get_level()
+            .checked_sub(type_fields[field_index].get_level())
+            == Some(1)
+    })
+}
+
+/// Index of the next child of a field of `field_level`, None after its last child.
+/// `last_index` is the last type field read, the field itself before its first child.
+///
+/// Type fields are in depth first order, a field more than one level under is a broken type tree.
+fn next_child_index(
+    type_fields: &[Arc<Box<dyn TypeField + Send + Sync>>],
+    field_level: u8,
+    last_index: usize,
+) -> BinResult<Option<usize>> {
+    let Some(next_field) = type_fields.get(last_index + 1) else {
+        return Ok(None);
+    };
+    match next_field.get_level().checked_sub(field_level) {
+        Some(1) => Ok(Some(last_index + 1)),
+        Some(0) | None => Ok(None),
+        Some(_) => Err(std::io::Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "type tree field {} at {} of level {} is not under a field of level {}",
+                next_field.get_name(),
+                last_index + 1,
+                next_field.get_level(),
+                field_level
+            ),
+        )
+        .into()),
+    }
+}
+
+/// Index after the type fields of the array item at `item_field_index`.
+fn get_item_type_fields_end(
+    type_fields: &[Arc<Box<dyn TypeField + Send + Sync>>],
+    item_field_index: usize,
+) -> usize {
+    let Some(item_type_field) = type_fields.get(item_field_index) else {
+        return item_field_index;
+    };
+    let mut end = item_field_index + 1;
+    while type_fields
+        .get(end)
+        .is_some_and(|next_field| next_field.get_level() >= item_type_field.get_level())
+    {
+        end += 1;
+    }
+    end
+}
+
+/// Item size of an array whose items can share one layout.
+fn get_fix_item_size(
+    item_type_fields: &[Arc<Box<dyn TypeField + Send + Sync>>],
+    pos: u64,
+) -> Option<usize> {
+    let byte_size = calc_no_array_field_size(item_type_fields, &mut 0, &mut 0)?;
+    ((pos.is_multiple_of(4) && byte_size.is_multiple_of(4))
+        || (item_type_fields.len() == 1 && !item_type_fields[0].is_align()))
+    .then_some(byte_size)
+}
+
 fn calc_no_array_field_size(
-    type_fields: &Vec<Arc<Box<dyn TypeField + Send + Sync>>>,
+    type_fields: &[Arc<Box<dyn TypeField + Send + Sync>>],
     field_index: &mut usize,
     read_size: &mut usize,
 ) -> Option<usize> {
@@ -285,20 +420,13 @@ fn calc_no_array_field_size(
     let field_level = field.get_level();
     if field.is_array() {
         return None;
-    } else if let Some(next_field) = type_fields.get(*field_index + 1) {
-        if next_field.get_level() == field_level + 1 {
-            while let Some(next_field) = type_fields.get(*field_index + 1) {
-                if next_field.get_level() == field_level + 1 {
-                    *field_index += 1;
-                    calc_no_array_field_size(type_fields, field_index, read_size)?;
-                } else if next_field.get_level() <= field_level {
-                    break;
-                } else {
-                    panic!("{:#?} {:#?} ", next_field.get_level(), field);
-                }
-            }
-        } else {
-            *read_size += field.get_byte_size() as usize;
+    } else if has_children(type_fields, *field_index) {
+        // a broken level is not a fixed size, it is reported when the items are read.
+        while let Some(child_index) =
+            next_child_index(type_fields, field_level, *field_index).ok()?
+        {
+            *field_index = child_index;
+            calc_no_array_field_size(type_fields, field_index, read_size)?;
         }
     } else {
         *read_size += field.get_byte_size() as usize;
@@ -309,3 +437,115 @@ fn calc_no_array_field_size(
     }
     Some(*read_size)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use crate::{
+        error::Error,
+        serialized_file::SerializedFile,
+        test_data::{
+            read_game_object_yaml, read_prefabs_cab, ASSET_BUNDLE, GAME_OBJECT_YAML, PLAYER,
+        },
+        type_tree::{convert::TryCastFrom, ArrayFieldValue, FieldValue, TypeTreeObject},
+    };
+
+    fn count_read_items(object: &TypeTreeObject, path: &str) -> (usize, usize) {
+        let path: Vec<_> = path.split('/').map(str::to_owned).collect();
+        let (field, _) = object.get_field_by_path_list(&path).unwrap();
+        let FieldValue::Array(array) = &field.data else {
+            panic!("{path:?} is not an array");
+        };
+        let ArrayFieldValue::ArrayItems(items) = &array.data else {
+            panic!("{path:?} items are not lazy");
+        };
+        let read = items
+            .items
+            .iter()
+            .filter(|item| item.get().is_some())
+            .count();
+        (items.len(), read)
+    }
+
+    #[test]
+    fn test_lazy_array_items() {
+        let tags = GAME_OBJECT_YAML.find("  - Body").unwrap();
+        let mut document = GAME_OBJECT_YAML[..tags].to_owned();
+        for index in 0..1000 {
+            document += &format!("  - tag{index}: {index}\n");
+        }
+        let object = read_game_object_yaml(&document);
+
+        assert_eq!(count_read_items(&object, "m_Tags/Array"), (1000, 0));
+        assert_eq!(
+            String::try_cast_from(&object, "/Base/m_Tags/Array/777/first").unwrap(),
+            "tag777"
+        );
+        assert_eq!(
+            i32::try_cast_from(&object, "/Base/m_Tags/Array/777/second").unwrap(),
+            777
+        );
+        assert_eq!(count_read_items(&object, "m_Tags/Array"), (1000, 1));
+        assert_eq!(u32::try_cast_from(&object, "/Base/m_Layer").unwrap(), 5);
+        assert!(String::try_cast_from(&object, "/Base/m_Tags/Array/1000/first").is_err());
+    }
+
+    #[test]
+    fn test_lazy_array_items_prefabs_bundle() {
+        let cab = read_prefabs_cab();
+        let serialized_file =
+            SerializedFile::read(Box::new(Cursor::new(cab.clone())), 0, None).unwrap();
+        let asset_bundle = serialized_file
+            .get_tt_object_by_path_id(ASSET_BUNDLE)
+            .unwrap()
+            .unwrap();
+        assert_eq!(count_read_items(&asset_bundle, "m_Container/Array"), (2, 0));
+        assert_eq!(
+            String::try_cast_from(&asset_bundle, "/Base/m_Container/Array/1/first").unwrap(),
+            "assets/prefabs/player.prefab"
+        );
+        assert_eq!(
+            i64::try_cast_from(
+                &asset_bundle,
+                "/Base/m_Container/Array/1/second/asset/m_PathID"
+            )
+            .unwrap(),
+            PLAYER
+        );
+        assert_eq!(count_read_items(&asset_bundle, "m_Container/Array"), (2, 1));
+        // fields after the lazy items are at their place.
+        assert_eq!(
+            String::try_cast_from(&asset_bundle, "/Base/m_AssetBundleName").unwrap(),
+            "prefabs"
+        );
+        assert_eq!(
+            i32::try_cast_from(&asset_bundle, "/Base/m_PathFlags").unwrap(),
+            7
+        );
+
+        // preloadIndex of m_Container items, the 20th type field of the AssetBundle type tree,
+        // moved one level under its parent.
+        let mut broken = cab;
+        let node = broken
+            .windows(12)
+            .position(|window| window == [4, 0, 0, 0, 20, 0, 0, 0, 0, 0, 0, 0])
+            .unwrap()
+            - 12;
+        assert_eq!(broken[node + 2], 5);
+        broken[node + 2] = 6;
+        let serialized_file = SerializedFile::read(Box::new(Cursor::new(broken)), 0, None).unwrap();
+        let Err(Error::ObjectReadError { source, .. }) =
+            serialized_file.get_tt_object_by_path_id(ASSET_BUNDLE)
+        else {
+            panic!("broken type tree is read");
+        };
+        let Error::BinrwError(err) = *source else {
+            panic!("broken type tree is not a binrw error");
+        };
+        let binrw::Error::Io(err) = err.root_cause() else {
+            panic!("broken type tree is not an io error");
+        };
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
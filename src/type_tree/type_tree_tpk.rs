@@ -0,0 +1,484 @@
+use super::provider::TypeTreeProvider;
+use super::{reader::TypeTreeObjectBinReadClassArgs, TypeField};
+use crate::error::{Error, ReadResult};
+use crate::until::binrw_parser::U8Bool;
+use binrw::{binread, BinRead, BinResult, Endian};
+use std::collections::HashMap;
+use std::io::{prelude::*, Cursor};
+use std::sync::{Arc, Mutex};
+
+#[binread]
+#[br(repr = u8)]
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum TpkCompressionType {
+    None = 0,
+    Lz4,
+    Lzma,
+    Brotli,
+}
+
+#[binread]
+#[br(repr = u8)]
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum TpkDataType {
+    TypeTreeInformation = 0,
+    EngineAssets,
+    Collection,
+    FileSystem,
+    Json,
+}
+
+#[binread]
+#[br(little, magic = b"TPK*")]
+#[derive(Debug)]
+struct TpkFile {
+    #[br(temp, assert(version == 1, "unsupported tpk version {}", version))]
+    version: u8,
+    compression_type: TpkCompressionType,
+    data_type: TpkDataType,
+    _reserved: u8,
+    _reserved2: u32,
+    #[br(temp)]
+    compressed_size: i32,
+    uncompressed_size: i32,
+    #[br(count = compressed_size)]
+    compressed_bytes: Vec<u8>,
+}
+
+impl TpkFile {
+    fn get_uncompressed_bytes(self) -> ReadResult<Vec<u8>> {
+        let uncompressed_bytes = match self.compression_type {
+            TpkCompressionType::None => self.compressed_bytes,
+            TpkCompressionType::Lz4 => {
+                lz4::block::decompress(&self.compressed_bytes, Some(self.uncompressed_size))?
+            }
+            TpkCompressionType::Lzma => {
+                let mut uncompressed_bytes = Vec::with_capacity(self.uncompressed_size as usize);
+                lzma_rs::lzma_decompress_with_options(
+                    &mut Cursor::new(&self.compressed_bytes),
+                    &mut uncompressed_bytes,
+                    &lzma_rs::decompress::Options {
+                        unpacked_size: lzma_rs::decompress::UnpackedSize::UseProvided(Some(
+                            self.uncompressed_size as u64,
+                        )),
+                        ..Default::default()
+                    },
+                )
+                .map_err(|e| Error::Other(e.to_string()))?;
+                uncompressed_bytes
+            }
+            TpkCompressionType::Brotli => {
+                let mut uncompressed_bytes = Vec::with_capacity(self.uncompressed_size as usize);
+                brotli_decompressor::Decompressor::new(Cursor::new(&self.compressed_bytes), 4096)
+                    .read_to_end(&mut uncompressed_bytes)?;
+                uncompressed_bytes
+            }
+        };
+        Ok(uncompressed_bytes)
+    }
+}
+
+/// String written by .NET BinaryWriter, prefixed with 7 bit encoded length.
+#[derive(Debug, PartialEq, Clone)]
+struct DotNetString(String);
+
+impl BinRead for DotNetString {
+    type Args<'a> = ();
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        _: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let mut length = 0usize;
+        let mut shift = 0;
+        loop {
+            let byte = <u8>::read_options(reader, endian, ())?;
+            length |= ((byte & 0x7F) as usize) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        let mut buf = vec![0u8; length];
+        reader.read_exact(&mut buf)?;
+        Ok(DotNetString(String::from_utf8_lossy(&buf).into_owned()))
+    }
+}
+
+#[binread]
+#[br(little)]
+#[derive(Debug)]
+struct TpkTypeTreeBlob {
+    _creation_time: i64,
+    #[br(temp)]
+    version_count: i32,
+    #[br(count = version_count)]
+    _versions: Vec<u64>,
+    #[br(temp)]
+    class_count: i32,
+    #[br(count = class_count)]
+    class_information: Vec<TpkClassInformation>,
+    _common_string: TpkCommonString,
+    #[br(temp)]
+    node_count: i32,
+    #[br(count = node_count)]
+    nodes: Vec<TpkUnityNode>,
+    #[br(temp)]
+    string_count: i32,
+    #[br(count = string_count)]
+    strings: Vec<DotNetString>,
+}
+
+#[binread]
+#[derive(Debug)]
+struct TpkClassInformation {
+    id: i32,
+    #[br(temp)]
+    class_count: i32,
+    /// Sorted by version, a class without data is not exist since that version.
+    #[br(count = class_count)]
+    classes: Vec<TpkVersionedClass>,
+}
+
+#[binread]
+#[derive(Debug)]
+struct TpkVersionedClass {
+    version: u64,
+    #[br(temp)]
+    has_data: U8Bool,
+    #[br(if(*has_data))]
+    class: Option<TpkUnityClass>,
+}
+
+#[binread]
+#[derive(Debug)]
+struct TpkUnityClass {
+    _name: u16,
+    _base: u16,
+    #[br(temp)]
+    flags: u8,
+    #[br(if(flags & 0x40 > 0))]
+    _editor_root_node: Option<u16>,
+    #[br(if(flags & 0x80 > 0))]
+    release_root_node: Option<u16>,
+}
+
+#[binread]
+#[derive(Debug)]
+struct TpkCommonString {
+    #[br(temp)]
+    version_count: i32,
+    #[br(count = version_count)]
+    _version_information: Vec<(u64, u8)>,
+    #[br(temp)]
+    index_count: i32,
+    #[br(count = index_count)]
+    _string_buffer_indices: Vec<u16>,
+}
+
+#[binread]
+#[derive(Debug)]
+struct TpkUnityNode {
+    type_name: u16,
+    name: u16,
+    byte_size: i32,
+    version: i16,
+    type_flags: u8,
+    meta_flag: u32,
+    #[br(temp)]
+    sub_node_count: u16,
+    #[br(args { count: sub_node_count.into(), inner: () })]
+    sub_nodes: Vec<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeTreeNode {
+    version: u16,
+    level: u8,
+    type_flags: u8,
+    type_name: String,
+    name: String,
+    byte_size: i32,
+    index: i32,
+    meta_flag: i32,
+}
+
+impl TypeField for TypeTreeNode {
+    fn get_version(&self) -> u16 {
+        self.version
+    }
+
+    fn get_level(&self) -> u8 {
+        self.level
+    }
+
+    fn is_array(&self) -> bool {
+        self.type_flags & 1 > 0
+    }
+
+    fn get_byte_size(&self) -> i32 {
+        self.byte_size
+    }
+
+    fn get_index(&self) -> i32 {
+        self.index
+    }
+
+    fn get_meta_flag(&self) -> i32 {
+        self.meta_flag
+    }
+
+    fn is_align(&self) -> bool {
+        self.meta_flag & 0x4000 > 0
+    }
+
+    fn get_ref_type_hash(&self) -> Option<u64> {
+        None
+    }
+
+    fn get_type(&self) -> &String {
+        &self.type_name
+    }
+
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+}
+
+/// Pack unity version like AssetRipper UnityVersion.
+/// major(16) minor(16) build(16) type(8) type_number(8)
+fn get_version_bits(version: &str) -> u64 {
+    let mut numbers = Vec::new();
+    let mut version_type = None;
+    let mut number = None;
+    for c in version.chars() {
+        if let Some(d) = c.to_digit(10) {
+            number = Some(number.unwrap_or(0u64) * 10 + d as u64);
+        } else {
+            if let Some(number) = number.take() {
+                numbers.push(number);
+            }
+            if c != '.' && version_type.is_none() {
+                version_type = Some(match c {
+                    'a' => 0,
+                    'b' => 1,
+                    'c' => 2,
+                    'f' => 3,
+                    'p' => 4,
+                    'x' => 5,
+                    _ => 3,
+                });
+            }
+        }
+    }
+    if let Some(number) = number {
+        numbers.push(number);
+    }
+
+    let get = |i: usize| numbers.get(i).copied().unwrap_or(0);
+    ((get(0) & 0xFFFF) << 48)
+        | ((get(1) & 0xFFFF) << 32)
+        | ((get(2) & 0xFFFF) << 16)
+        | ((version_type.unwrap_or(3) & 0xFF) << 8)
+        | (get(3) & 0xFF)
+}
+
+/// Type tree info from AssetRipper tpk file.
+/// see https://github.com/AssetRipper/Tpk
+/// The file contain type tree of all released unity versions in a few MiB.
+pub struct TpkTypeTreeProvider {
+    blob: TpkTypeTreeBlob,
+    class_map: HashMap<i32, usize>,
+    type_tree_object_bin_read_class_args_cache_map:
+        Mutex<HashMap<(u64, i32), Option<TypeTreeObjectBinReadClassArgs>>>,
+}
+
+impl TpkTypeTreeProvider {
+    pub fn read<R: Read + Seek>(reader: &mut R) -> ReadResult<Self> {
+        let tpk_file = TpkFile::read(reader)?;
+        if tpk_file.data_type != TpkDataType::TypeTreeInformation {
+            return Err(Error::Other(format!(
+                "tpk file contain {:?} instead of type tree information",
+                tpk_file.data_type
+            )));
+        }
+        let blob = TpkTypeTreeBlob::read(&mut Cursor::new(tpk_file.get_uncompressed_bytes()?))?;
+        let class_map = blob
+            .class_information
+            .iter()
+            .enumerate()
+            .map(|(i, class_information)| (class_information.id, i))
+            .collect();
+        Ok(TpkTypeTreeProvider {
+            blob,
+            class_map,
+            type_tree_object_bin_read_class_args_cache_map: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn get_class(&self, version: u64, class_id: i32) -> Option<&TpkUnityClass> {
+        let class_information = self
+            .blob
+            .class_information
+            .get(*self.class_map.get(&class_id)?)?;
+        class_information
+            .classes
+            .iter()
+            .take_while(|versioned_class| versioned_class.version <= version)
+            .last()?
+            .class
+            .as_ref()
+    }
+
+    fn get_string(&self, index: u16) -> Option<String> {
+        self.blob.strings.get(index as usize).map(|s| s.0.clone())
+    }
+
+    fn get_nodes(
+        &self,
+        type_tree_nodes: &mut Vec<TypeTreeNode>,
+        node_index: u16,
+        level: u8,
+    ) -> Option<()> {
+        let node = self.blob.nodes.get(node_index as usize)?;
+        type_tree_nodes.push(TypeTreeNode {
+            version: node.version as u16,
+            level,
+            type_flags: node.type_flags,
+            type_name: self.get_string(node.type_name)?,
+            name: self.get_string(node.name)?,
+            byte_size: node.byte_size,
+            index: type_tree_nodes.len() as i32,
+            meta_flag: node.meta_flag as i32,
+        });
+        for sub_node_index in &node.sub_nodes {
+            self.get_nodes(type_tree_nodes, *sub_node_index, level + 1)?;
+        }
+        Some(())
+    }
+}
+
+impl TypeTreeProvider for TpkTypeTreeProvider {
+    fn get_type_object_args_by_version_class_id(
+        &self,
+        version: &str,
+        class_id: i32,
+    ) -> Option<TypeTreeObjectBinReadClassArgs> {
+        let version = get_version_bits(version);
+        if let Ok(cache_map) = self.type_tree_object_bin_read_class_args_cache_map.lock() {
+            if let Some(read_args) = cache_map.get(&(version, class_id)) {
+                return read_args.clone();
+            }
+        }
+
+        let read_args = self
+            .get_class(version, class_id)
+            .and_then(|class| class.release_root_node)
+            .and_then(|root_node| {
+                let mut type_tree_nodes = Vec::new();
+                self.get_nodes(&mut type_tree_nodes, root_node, 0)?;
+                let type_fields = type_tree_nodes
+                    .into_iter()
+                    .map(|ttn| Arc::new(Box::new(ttn) as Box<dyn TypeField + Send + Sync>))
+                    .collect();
+                Some(TypeTreeObjectBinReadClassArgs::new(class_id, type_fields))
+            });
+
+        if let Ok(mut cache_map) = self.type_tree_object_bin_read_class_args_cache_map.lock() {
+            cache_map.insert((version, class_id), read_args.clone());
+        }
+        read_args
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    fn write_string(buf: &mut Vec<u8>, s: &str) {
+        buf.push(s.len() as u8);
+        buf.extend_from_slice(s.as_bytes());
+    }
+
+    fn write_node(buf: &mut Vec<u8>, type_name: u16, name: u16, byte_size: i32, sub_nodes: &[u16]) {
+        buf.extend_from_slice(&type_name.to_le_bytes());
+        buf.extend_from_slice(&name.to_le_bytes());
+        buf.extend_from_slice(&byte_size.to_le_bytes());
+        buf.extend_from_slice(&1i16.to_le_bytes());
+        buf.push(0);
+        buf.extend_from_slice(&0u32.to_le_bytes());
+        buf.extend_from_slice(&(sub_nodes.len() as u16).to_le_bytes());
+        for sub_node in sub_nodes {
+            buf.extend_from_slice(&sub_node.to_le_bytes());
+        }
+    }
+
+    fn build_tpk() -> Vec<u8> {
+        let mut blob = Vec::new();
+        blob.extend_from_slice(&0i64.to_le_bytes());
+        blob.extend_from_slice(&0i32.to_le_bytes());
+        // one class, exist from 5.0.0f1 and removed in 2020.1.0f1
+        blob.extend_from_slice(&1i32.to_le_bytes());
+        blob.extend_from_slice(&1i32.to_le_bytes());
+        blob.extend_from_slice(&2i32.to_le_bytes());
+        blob.extend_from_slice(&get_version_bits("5.0.0f1").to_le_bytes());
+        blob.push(1);
+        blob.extend_from_slice(&0u16.to_le_bytes());
+        blob.extend_from_slice(&0u16.to_le_bytes());
+        blob.push(0x80);
+        blob.extend_from_slice(&0u16.to_le_bytes());
+        blob.extend_from_slice(&get_version_bits("2020.1.0f1").to_le_bytes());
+        blob.push(0);
+        // common string
+        blob.extend_from_slice(&0i32.to_le_bytes());
+        blob.extend_from_slice(&0i32.to_le_bytes());
+        // nodes
+        blob.extend_from_slice(&2i32.to_le_bytes());
+        write_node(&mut blob, 0, 1, 4, &[1]);
+        write_node(&mut blob, 2, 3, 4, &[]);
+        // strings
+        blob.extend_from_slice(&4i32.to_le_bytes());
+        for s in ["GameObject", "Base", "int", "m_Value"] {
+            write_string(&mut blob, s);
+        }
+
+        let mut tpk = Vec::new();
+        tpk.extend_from_slice(b"TPK*");
+        tpk.extend_from_slice(&[1, 0, 0, 0]);
+        tpk.extend_from_slice(&0u32.to_le_bytes());
+        tpk.extend_from_slice(&(blob.len() as i32).to_le_bytes());
+        tpk.extend_from_slice(&(blob.len() as i32).to_le_bytes());
+        tpk.extend(blob);
+        tpk
+    }
+
+    #[test]
+    fn test_tpk() {
+        let provider = TpkTypeTreeProvider::read(&mut Cursor::new(build_tpk())).unwrap();
+
+        let args = provider
+            .get_type_object_args_by_version_class_id("2019.4.0f1", 1)
+            .unwrap();
+        let names: Vec<(String, u8)> = args
+            .get_type_fields()
+            .iter()
+            .map(|f| (f.get_name().clone(), f.get_level()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![("Base".to_owned(), 0), ("m_Value".to_owned(), 1)]
+        );
+
+        assert!(provider
+            .get_type_object_args_by_version_class_id("4.7.2f1", 1)
+            .is_none());
+        assert!(provider
+            .get_type_object_args_by_version_class_id("2021.3.0f1", 1)
+            .is_none());
+        assert!(provider
+            .get_type_object_args_by_version_class_id("2019.4.0f1", 2)
+            .is_none());
+    }
+}
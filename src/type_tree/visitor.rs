@@ -0,0 +1,279 @@
+use super::{
+    convert::{FieldCastArgs, TryCast},
+    Field, FieldValue, TypeField, TypeTreeObject, TypeTreeObjectRef,
+};
+use crate::error::{Error, ReadResult};
+
+/// What a node of the type tree hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// Number, bool or other data read by its type, see `FieldNode::read`.
+    Primitive,
+    /// Named fields like a class, a string or a vector.
+    Fields,
+    /// Items of an Array, visited with their index as name.
+    Array { size: usize },
+}
+
+/// A node given to a `TypeTreeVisitor`.
+pub struct FieldNode<'a> {
+    path: &'a [String],
+    field: &'a Field,
+    object: &'a TypeTreeObject,
+    field_cast_args: FieldCastArgs,
+}
+
+impl FieldNode<'_> {
+    /// Path from the root of the object, like `TypeTreeObjectRef::path`.
+    pub fn get_path(&self) -> &[String] {
+        self.path
+    }
+
+    /// Field name, item of an Array keep the name of the item type field like `data`.
+    pub fn get_name(&self) -> &String {
+        self.field.get_name()
+    }
+
+    pub fn get_type(&self) -> &String {
+        self.field.get_type()
+    }
+
+    /// Level, meta flags, byte size and other meta of the type field.
+    pub fn get_type_field(&self) -> &(dyn TypeField + Send + Sync) {
+        self.field.field_type.as_ref().as_ref()
+    }
+
+    pub fn get_kind(&self) -> FieldKind {
+        match &self.field.data {
+            FieldValue::DataOffset(_) => FieldKind::Primitive,
+            FieldValue::Fields(_) => FieldKind::Fields,
+            FieldValue::Array(array) => FieldKind::Array {
+                size: array
+                    .get_size(&self.object.data_buff, &self.field_cast_args)
+                    .unwrap_or_default(),
+            },
+        }
+    }
+
+    /// Read the node like `TryCastFrom`, for example `i64`, `String`, `Vec<u8>` or `glam::Vec3`.
+    pub fn read<T>(&self) -> ReadResult<T>
+    where
+        Field: TryCast<T, Error = Error>,
+    {
+        self.field
+            .try_cast_to(&self.object.data_buff, &self.field_cast_args)
+    }
+
+    /// Data of a primitive field or a fixed item size array.
+    pub fn as_slice(&self) -> ReadResult<&[u8]> {
+        self.field
+            .try_as_slice(&self.object.data_buff, &self.field_cast_args)
+    }
+}
+
+pub trait TypeTreeVisitor {
+    /// Called for every node before its children, return false to skip the children.
+    fn enter(&mut self, node: &FieldNode) -> ReadResult<bool>;
+
+    /// Called for every node after its children.
+    fn leave(&mut self, _node: &FieldNode) -> ReadResult<()> {
+        Ok(())
+    }
+}
+
+impl<F> TypeTreeVisitor for F
+where
+    F: FnMut(&FieldNode) -> ReadResult<bool>,
+{
+    fn enter(&mut self, node: &FieldNode) -> ReadResult<bool> {
+        self(node)
+    }
+}
+
+impl TypeTreeObject {
+    /// Visit every node of the object in declaration and item order.
+    pub fn visit<V: TypeTreeVisitor>(&self, visitor: &mut V) -> ReadResult<()> {
+        self.visit_path(&[], visitor)
+    }
+
+    fn visit_path<V: TypeTreeVisitor>(&self, path: &[String], visitor: &mut V) -> ReadResult<()> {
+        let (field, offset) = self
+            .get_field_by_path_list(path)
+            .ok_or_else(|| Error::FieldNotFound(path.to_vec()))?;
+        let mut field_cast_args = self.get_field_cast_args();
+        field_cast_args.field_offset = offset;
+        self.visit_field(field, &mut path.to_vec(), field_cast_args, visitor)
+    }
+
+    fn visit_field<V: TypeTreeVisitor>(
+        &self,
+        field: &Field,
+        path: &mut Vec<String>,
+        field_cast_args: FieldCastArgs,
+        visitor: &mut V,
+    ) -> ReadResult<()> {
+        let node = FieldNode {
+            path,
+            field,
+            object: self,
+            field_cast_args: field_cast_args.clone(),
+        };
+        if visitor.enter(&node)? {
+            match (&field.data, node.get_kind()) {
+                (FieldValue::Fields(fields), _) => {
+                    for (name, child) in fields {
+                        path.push(name.to_owned());
+                        self.visit_field(child, path, field_cast_args.clone(), visitor)?;
+                        path.pop();
+                    }
+                }
+                (FieldValue::Array(array), FieldKind::Array { size }) => {
+                    for index in 0..size {
+                        path.push(index.to_string());
+                        let (item_field, item_cast_args) =
+                            array.get_item(index, &self.data_buff, &field_cast_args)?;
+                        self.visit_field(item_field, path, item_cast_args, visitor)?;
+                        path.pop();
+                    }
+                }
+                _ => (),
+            }
+        }
+        let node = FieldNode {
+            path,
+            field,
+            object: self,
+            field_cast_args,
+        };
+        visitor.leave(&node)
+    }
+}
+
+impl TypeTreeObjectRef {
+    /// Visit the node of this ref and every node under it.
+    pub fn visit<V: TypeTreeVisitor>(&self, visitor: &mut V) -> ReadResult<()> {
+        self.inner
+            .read()
+            .map_err(|e| Error::Other(e.to_string()))?
+            .visit_path(&self.path, visitor)
+    }
+
+    /// Ref of another node of the same object, like one found by a visitor.
+    pub fn with_path(&self, path: Vec<String>) -> TypeTreeObjectRef {
+        TypeTreeObjectRef {
+            inner: self.inner.clone(),
+            path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{
+        read_game_object_yaml, read_prefabs_object, ASSET_BUNDLE, GAME_OBJECT_YAML, PICKUP, PLAYER,
+        PLAYER_TRANSFORM, WEAPON_TRANSFORM,
+    };
+
+    #[test]
+    fn test_visit() {
+        let object = TypeTreeObjectRef::from(read_game_object_yaml(GAME_OBJECT_YAML));
+
+        let mut path_ids = Vec::new();
+        object
+            .visit(&mut |node: &FieldNode| {
+                if node.get_name() == "m_PathID" {
+                    path_ids.push(node.read::<i64>()?);
+                }
+                Ok(node.get_name() != "m_Data")
+            })
+            .unwrap();
+        assert_eq!(path_ids, [4, 5]);
+
+        struct Collector(Vec<(String, FieldKind)>, usize);
+        impl TypeTreeVisitor for Collector {
+            fn enter(&mut self, node: &FieldNode) -> ReadResult<bool> {
+                self.0.push((node.get_path().join("/"), node.get_kind()));
+                Ok(node.get_type() != "string")
+            }
+            fn leave(&mut self, _node: &FieldNode) -> ReadResult<()> {
+                self.1 += 1;
+                Ok(())
+            }
+        }
+        let mut collector = Collector(Vec::new(), 0);
+        object
+            .with_path(vec!["m_Data".to_owned()])
+            .visit(&mut collector)
+            .unwrap();
+        assert_eq!(
+            collector.0,
+            [
+                ("m_Data".to_owned(), FieldKind::Fields),
+                ("m_Data/Array".to_owned(), FieldKind::Array { size: 2 }),
+                ("m_Data/Array/0".to_owned(), FieldKind::Primitive),
+                ("m_Data/Array/1".to_owned(), FieldKind::Primitive),
+            ]
+        );
+        assert_eq!(collector.1, 4);
+
+        let mut layer = None;
+        object
+            .visit(&mut |node: &FieldNode| {
+                if node.get_path() == ["m_Layer"] {
+                    layer = Some(node.read::<u32>()?);
+                }
+                Ok(true)
+            })
+            .unwrap();
+        assert_eq!(layer, Some(5));
+    }
+
+    #[test]
+    fn test_visit_prefabs_bundle() {
+        let collect_pptrs = |object: &TypeTreeObjectRef| {
+            let mut pptrs = Vec::new();
+            object
+                .visit(&mut |node: &FieldNode| {
+                    if node.get_name() == "m_PathID" {
+                        let path = node.get_path();
+                        pptrs.push((path[..path.len() - 1].join("/"), node.read::<i64>()?));
+                    }
+                    Ok(true)
+                })
+                .unwrap();
+            pptrs
+        };
+        assert_eq!(
+            collect_pptrs(&read_prefabs_object(PLAYER_TRANSFORM)),
+            [
+                ("m_GameObject".to_owned(), PLAYER),
+                ("m_Children/Array/0".to_owned(), WEAPON_TRANSFORM),
+                ("m_Father".to_owned(), 0),
+            ]
+        );
+
+        let asset_bundle = read_prefabs_object(ASSET_BUNDLE);
+        let pptrs = collect_pptrs(&asset_bundle);
+        assert_eq!(pptrs.len(), 9);
+        assert_eq!(
+            pptrs[6..],
+            [
+                ("m_Container/Array/0/second/asset".to_owned(), PICKUP),
+                ("m_Container/Array/1/second/asset".to_owned(), PLAYER),
+                ("m_MainAsset/asset".to_owned(), 0),
+            ]
+        );
+
+        let mut container_kind = None;
+        asset_bundle
+            .visit(&mut |node: &FieldNode| {
+                if node.get_path() == ["m_Container", "Array"] {
+                    container_kind = Some(node.get_kind());
+                }
+                Ok(true)
+            })
+            .unwrap();
+        assert_eq!(container_kind, Some(FieldKind::Array { size: 2 }));
+    }
+}
@@ -0,0 +1,652 @@
+use std::{io::Cursor, sync::Arc};
+
+use binrw::{BinRead, BinWrite, Endian};
+
+use super::{
+    convert::{FieldCastArgs, TryCast},
+    reader::{TypeTreeObjectBinReadArgs, TypeTreeObjectBinReadClassArgs},
+    ArrayField, Field, FieldValue, TypeField, TypeTreeObject, TypeTreeObjectRef,
+};
+use crate::error::{Error, ReadResult};
+
+/// Write a value to the data of a primitive field, the value must fit in the type of the field.
+pub trait TryWrite<T>: Sized {
+    type Error;
+
+    fn try_write_to(
+        &self,
+        value: T,
+        object_data_buff: &mut [u8],
+        field_cast_args: &FieldCastArgs,
+    ) -> Result<(), Self::Error>;
+}
+
+macro_rules! impl_try_write_int {
+    ($($t:ty)*) => {
+        $(
+            impl TryWrite<$t> for Field {
+                type Error = Error;
+
+                fn try_write_to(
+                    &self,
+                    value: $t,
+                    object_data_buff: &mut [u8],
+                    field_cast_args: &FieldCastArgs,
+                ) -> ReadResult<()> {
+                    self.write_int(
+                        value as i128,
+                        std::any::type_name::<$t>(),
+                        object_data_buff,
+                        field_cast_args,
+                    )
+                }
+            }
+        )*
+    };
+}
+
+impl_try_write_int!(i8 i16 i32 i64 u8 u16 u32 u64 usize);
+
+impl TryWrite<bool> for Field {
+    type Error = Error;
+
+    fn try_write_to(
+        &self,
+        value: bool,
+        object_data_buff: &mut [u8],
+        field_cast_args: &FieldCastArgs,
+    ) -> ReadResult<()> {
+        if self.get_type() == "bool" {
+            return self.write_bytes(value as u8, object_data_buff, field_cast_args);
+        }
+        Err(self.type_mismatch(std::any::type_name::<bool>()))
+    }
+}
+
+impl TryWrite<f32> for Field {
+    type Error = Error;
+
+    fn try_write_to(
+        &self,
+        value: f32,
+        object_data_buff: &mut [u8],
+        field_cast_args: &FieldCastArgs,
+    ) -> ReadResult<()> {
+        match self.get_type().as_str() {
+            "float" => self.write_bytes(value, object_data_buff, field_cast_args),
+            "double" => self.write_bytes(value as f64, object_data_buff, field_cast_args),
+            _ => Err(self.type_mismatch(std::any::type_name::<f32>())),
+        }
+    }
+}
+
+impl TryWrite<f64> for Field {
+    type Error = Error;
+
+    fn try_write_to(
+        &self,
+        value: f64,
+        object_data_buff: &mut [u8],
+        field_cast_args: &FieldCastArgs,
+    ) -> ReadResult<()> {
+        match self.get_type().as_str() {
+            "float" => self.write_bytes(value as f32, object_data_buff, field_cast_args),
+            "double" => self.write_bytes(value, object_data_buff, field_cast_args),
+            _ => Err(self.type_mismatch(std::any::type_name::<f64>())),
+        }
+    }
+}
+
+impl Field {
+    fn write_int(
+        &self,
+        value: i128,
+        want_to_write: &'static str,
+        object_data_buff: &mut [u8],
+        field_cast_args: &FieldCastArgs,
+    ) -> ReadResult<()> {
+        let out_of_range = || Error::ValueOutOfRange {
+            value: value.to_string(),
+            type_name: self.get_type().to_owned(),
+        };
+        let buff = object_data_buff;
+        let args = field_cast_args;
+        match (self.get_type().as_str(), self.field_type.get_byte_size()) {
+            ("SInt8", _) => {
+                self.write_bytes(i8::try_from(value).map_err(|_| out_of_range())?, buff, args)
+            }
+            ("SInt16" | "short", _) => self.write_bytes(
+                i16::try_from(value).map_err(|_| out_of_range())?,
+                buff,
+                args,
+            ),
+            ("SInt32" | "int", _) => self.write_bytes(
+                i32::try_from(value).map_err(|_| out_of_range())?,
+                buff,
+                args,
+            ),
+            ("SInt64" | "long long", _) => self.write_bytes(
+                i64::try_from(value).map_err(|_| out_of_range())?,
+                buff,
+                args,
+            ),
+            ("UInt8" | "char", _) => {
+                self.write_bytes(u8::try_from(value).map_err(|_| out_of_range())?, buff, args)
+            }
+            ("UInt16" | "unsigned short", _) => self.write_bytes(
+                u16::try_from(value).map_err(|_| out_of_range())?,
+                buff,
+                args,
+            ),
+            ("UInt32" | "unsigned int", _) | ("FileSize", 4) => self.write_bytes(
+                u32::try_from(value).map_err(|_| out_of_range())?,
+                buff,
+                args,
+            ),
+            ("UInt64" | "unsigned long long" | "FileSize", _) => self.write_bytes(
+                u64::try_from(value).map_err(|_| out_of_range())?,
+                buff,
+                args,
+            ),
+            _ => Err(self.type_mismatch(want_to_write)),
+        }
+    }
+
+    fn write_bytes<T>(
+        &self,
+        value: T,
+        object_data_buff: &mut [u8],
+        field_cast_args: &FieldCastArgs,
+    ) -> ReadResult<()>
+    where
+        T: for<'b> BinWrite<Args<'b> = ()>,
+    {
+        let FieldValue::DataOffset(_) = self.data else {
+            return Err(self.type_mismatch(std::any::type_name::<T>()));
+        };
+        let (pos, size) = self.get_data_range(object_data_buff, field_cast_args)?;
+        let mut writer = Cursor::new(&mut object_data_buff[pos as usize..(pos + size) as usize]);
+        value.write_options(&mut writer, field_cast_args.endian, ())?;
+        Ok(())
+    }
+
+    fn type_mismatch(&self, want_to_cast: &'static str) -> Error {
+        Error::TypeMisMatch {
+            want_to_cast,
+            found_type_name: self.get_type().to_owned(),
+        }
+    }
+
+    /// Type fields of the field in the order the reader want them.
+    fn collect_type_fields(&self, type_fields: &mut Vec<Arc<Box<dyn TypeField + Send + Sync>>>) {
+        type_fields.push(self.field_type.clone());
+        match &self.data {
+            FieldValue::DataOffset(_) => (),
+            FieldValue::Fields(fields) => {
+                for field in fields.values() {
+                    field.collect_type_fields(type_fields);
+                }
+            }
+            FieldValue::Array(array) => {
+                array.array_size.collect_type_fields(type_fields);
+                type_fields.extend(array.item_type_fields.iter().cloned());
+            }
+        }
+    }
+}
+
+/// Value that can be set to a field with `TypeTreeObject::set`.
+pub trait SetValue {
+    fn set_to(self, object: &mut TypeTreeObject, path: &[String]) -> ReadResult<()>;
+}
+
+macro_rules! impl_set_value {
+    ($($t:ty)*) => {
+        $(
+            impl SetValue for $t {
+                fn set_to(self, object: &mut TypeTreeObject, path: &[String]) -> ReadResult<()> {
+                    let (field, offset) = object
+                        .get_field_by_path_list(path)
+                        .ok_or_else(|| Error::FieldNotFound(path.to_vec()))?;
+                    let field = field.clone();
+                    let mut field_cast_args = object.get_field_cast_args();
+                    field_cast_args.field_offset = offset;
+                    field.try_write_to(self, &mut object.data_buff, &field_cast_args)
+                }
+            }
+        )*
+    };
+}
+
+impl_set_value!(bool i8 i16 i32 i64 u8 u16 u32 u64 usize f32 f64);
+
+/// Replace the text of a string field, the object is laid out again.
+impl SetValue for &str {
+    fn set_to(self, object: &mut TypeTreeObject, path: &[String]) -> ReadResult<()> {
+        self.as_bytes().to_vec().set_to(object, path)
+    }
+}
+
+impl SetValue for String {
+    fn set_to(self, object: &mut TypeTreeObject, path: &[String]) -> ReadResult<()> {
+        self.into_bytes().set_to(object, path)
+    }
+}
+
+/// Replace the data of a string or an array of bytes like `image data`, the object is laid out again.
+impl SetValue for Vec<u8> {
+    fn set_to(self, object: &mut TypeTreeObject, path: &[String]) -> ReadResult<()> {
+        let path = object.get_array_path(path)?;
+        let (field, _) = object
+            .get_field_by_path_list(&path)
+            .ok_or_else(|| Error::FieldNotFound(path.clone()))?;
+        if let FieldValue::Array(array) = &field.data {
+            if let [item_type] = array.item_type_fields.as_slice() {
+                if item_type.get_byte_size() == 1 {
+                    return object.rewrite(&path, ArrayEdit::Data(self));
+                }
+            }
+        }
+        Err(field.type_mismatch(std::any::type_name::<Vec<u8>>()))
+    }
+}
+
+impl TypeTreeObject {
+    /// Set the value of a field like `/Base/m_Name`.
+    /// Numbers are written in place, strings and byte arrays lay the object out again.
+    pub fn set<T: SetValue>(&mut self, path: &str, value: T) -> ReadResult<()> {
+        value.set_to(self, &split_path(path))
+    }
+
+    /// Resize a vector or an Array, new items are zero, empty or false.
+    pub fn resize_array(&mut self, path: &str, size: usize) -> ReadResult<()> {
+        let path = self.get_array_path(&split_path(path))?;
+        self.rewrite(&path, ArrayEdit::Resize(size))
+    }
+
+    /// Add an item at the end of an array and return its index, the item can be set by path after.
+    pub fn push_array_item(&mut self, path: &str) -> ReadResult<usize> {
+        let path = self.get_array_path(&split_path(path))?;
+        let size = self.get_array_size(&path)?;
+        self.rewrite(&path, ArrayEdit::Resize(size + 1))?;
+        Ok(size)
+    }
+
+    pub fn remove_array_item(&mut self, path: &str, index: usize) -> ReadResult<()> {
+        let path = self.get_array_path(&split_path(path))?;
+        if index >= self.get_array_size(&path)? {
+            let mut path = path;
+            path.push(index.to_string());
+            return Err(Error::FieldNotFound(path));
+        }
+        self.rewrite(&path, ArrayEdit::Remove(index))
+    }
+
+    /// Object data as it is in a serialized file, with the external data after the type tree data.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = self.data_buff.clone();
+        if let Some(external_data) = &self.external_data {
+            data.extend_from_slice(external_data);
+        }
+        data
+    }
+
+    /// Path of the Array field, vector and string are resolved to their Array.
+    fn get_array_path(&self, path: &[String]) -> ReadResult<Vec<String>> {
+        let (field, _) = self
+            .get_field_by_path_list(path)
+            .ok_or_else(|| Error::FieldNotFound(path.to_vec()))?;
+        let mut path = path.to_vec();
+        match &field.data {
+            FieldValue::Array(_) => Ok(path),
+            FieldValue::Fields(fields) => match fields.get("Array") {
+                Some(Field {
+                    data: FieldValue::Array(_),
+                    ..
+                }) if fields.len() == 1 => {
+                    path.push("Array".to_owned());
+                    Ok(path)
+                }
+                _ => Err(Error::ArrayFieldNotFound(path)),
+            },
+            FieldValue::DataOffset(_) => Err(Error::ArrayFieldNotFound(path)),
+        }
+    }
+
+    fn get_array_size(&self, path: &[String]) -> ReadResult<usize> {
+        let (field, _) = self
+            .get_field_by_path_list(path)
+            .ok_or_else(|| Error::FieldNotFound(path.to_vec()))?;
+        let FieldValue::Array(array) = &field.data else {
+            return Err(Error::ArrayFieldNotFound(path.to_vec()));
+        };
+        let size: i32 = array
+            .array_size
+            .try_cast_to(&self.data_buff, &self.get_field_cast_args())?;
+        Ok(size.max(0) as usize)
+    }
+
+    /// Write the object again with the edit of the array at path, then read the layout of the new data.
+    fn rewrite(&mut self, path: &[String], edit: ArrayEdit) -> ReadResult<()> {
+        let mut rewriter = ObjectRewriter {
+            object: self,
+            path: Vec::new(),
+            edit_path: path,
+            edit: Some(edit),
+            data: Vec::new(),
+        };
+        rewriter.write_field(&self.data_layout, None)?;
+        if rewriter.edit.is_some() {
+            return Err(Error::ArrayFieldNotFound(path.to_vec()));
+        }
+        let data_buff = rewriter.data;
+
+        let mut type_fields = Vec::new();
+        self.data_layout.collect_type_fields(&mut type_fields);
+        let args = TypeTreeObjectBinReadArgs::new(
+            self.serialized_file_id,
+            self.path_id,
+            TypeTreeObjectBinReadClassArgs::new(self.class_id, type_fields),
+        );
+        let object = TypeTreeObject::read_options(&mut Cursor::new(&data_buff), self.endian, args)?;
+        self.data_layout = object.data_layout;
+        self.data_buff = object.data_buff;
+        Ok(())
+    }
+}
+
+impl TypeTreeObjectRef {
+    /// Set the value of a field under this ref, see `TypeTreeObject::set`.
+    pub fn set<T: SetValue>(&self, path: &str, value: T) -> ReadResult<()> {
+        let path = self.get_full_path(path);
+        let mut type_tree_obj = self.write()?;
+        value.set_to(&mut type_tree_obj, &path)
+    }
+
+    pub fn resize_array(&self, path: &str, size: usize) -> ReadResult<()> {
+        let path = self.get_full_path(path);
+        let mut type_tree_obj = self.write()?;
+        let path = type_tree_obj.get_array_path(&path)?;
+        type_tree_obj.rewrite(&path, ArrayEdit::Resize(size))
+    }
+
+    pub fn push_array_item(&self, path: &str) -> ReadResult<usize> {
+        let path = self.get_full_path(path);
+        let mut type_tree_obj = self.write()?;
+        let path = type_tree_obj.get_array_path(&path)?;
+        let size = type_tree_obj.get_array_size(&path)?;
+        type_tree_obj.rewrite(&path, ArrayEdit::Resize(size + 1))?;
+        Ok(size)
+    }
+
+    pub fn remove_array_item(&self, path: &str, index: usize) -> ReadResult<()> {
+        let path = self.get_full_path(path);
+        let mut type_tree_obj = self.write()?;
+        let path = type_tree_obj.get_array_path(&path)?;
+        if index >= type_tree_obj.get_array_size(&path)? {
+            let mut path = path;
+            path.push(index.to_string());
+            return Err(Error::FieldNotFound(path));
+        }
+        type_tree_obj.rewrite(&path, ArrayEdit::Remove(index))
+    }
+
+    /// Object data of the whole object this ref is in.
+    pub fn to_bytes(&self) -> ReadResult<Vec<u8>> {
+        Ok(self
+            .inner
+            .read()
+            .map_err(|e| Error::Other(e.to_string()))?
+            .to_bytes())
+    }
+
+    fn get_full_path(&self, path: &str) -> Vec<String> {
+        let mut full_path = self.path.clone();
+        full_path.extend(split_path(path));
+        full_path
+    }
+
+    fn write(&self) -> ReadResult<std::sync::RwLockWriteGuard<'_, Box<TypeTreeObject>>> {
+        self.inner.write().map_err(|e| Error::Other(e.to_string()))
+    }
+}
+
+/// Path like `/Base/m_Name` without the root.
+fn split_path(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter(|s| !s.is_empty())
+        .skip(1)
+        .map(|s| s.to_string())
+        .collect()
+}
+
+enum ArrayEdit {
+    Resize(usize),
+    Remove(usize),
+    /// Items of an array of bytes.
+    Data(Vec<u8>),
+}
+
+struct ObjectRewriter<'a> {
+    object: &'a TypeTreeObject,
+    path: Vec<String>,
+    edit_path: &'a [String],
+    edit: Option<ArrayEdit>,
+    data: Vec<u8>,
+}
+
+impl ObjectRewriter<'_> {
+    fn write_field(&mut self, field: &Field, field_offset: Option<i64>) -> ReadResult<()> {
+        match &field.data {
+            FieldValue::DataOffset(_) => {
+                let mut field_cast_args = self.object.get_field_cast_args();
+                field_cast_args.field_offset = field_offset;
+                self.data.extend_from_slice(
+                    field.try_as_slice(&self.object.data_buff, &field_cast_args)?,
+                );
+            }
+            FieldValue::Fields(fields) => {
+                for field in fields.values() {
+                    self.path.push(field.get_name().to_owned());
+                    self.write_field(field, field_offset)?;
+                    self.path.pop();
+                }
+            }
+            FieldValue::Array(array) => self.write_array(array)?,
+        }
+        if field.field_type.is_align() {
+            self.align();
+        }
+        Ok(())
+    }
+
+    fn write_array(&mut self, array: &ArrayField) -> ReadResult<()> {
+        let object = self.object;
+        let object_data_buff = &object.data_buff;
+        let size: i32 = array
+            .array_size
+            .try_cast_to(object_data_buff, &object.get_field_cast_args())?;
+        let size = size.max(0) as usize;
+        let edit = if self.path == self.edit_path {
+            self.edit.take()
+        } else {
+            None
+        };
+        let (new_size, removed) = match &edit {
+            Some(ArrayEdit::Resize(new_size)) => (*new_size, None),
+            Some(ArrayEdit::Remove(index)) => (size - 1, Some(*index)),
+            Some(ArrayEdit::Data(data)) => (data.len(), None),
+            None => (size, None),
+        };
+        self.write_i32(i32::try_from(new_size).map_err(|_| Error::ValueOutOfRange {
+            value: new_size.to_string(),
+            type_name: array.array_size.get_type().to_owned(),
+        })?);
+        if let Some(ArrayEdit::Data(data)) = edit {
+            self.data.extend_from_slice(&data);
+            return Ok(());
+        }
+
+        let kept = size.min(new_size + removed.is_some() as usize);
+        let field_cast_args = object.get_field_cast_args();
+        for index in (0..kept).filter(|index| Some(*index) != removed) {
+            let (item_field, item_cast_args) =
+                array.get_item(index, object_data_buff, &field_cast_args)?;
+            self.path.push(index.to_string());
+            self.write_field(item_field, item_cast_args.field_offset)?;
+            self.path.pop();
+        }
+        for _ in kept..new_size {
+            self.write_default(&array.item_type_fields, 0);
+        }
+        Ok(())
+    }
+
+    /// Write the zero value of the type field at index, return the index after its children.
+    fn write_default(
+        &mut self,
+        type_fields: &[Arc<Box<dyn TypeField + Send + Sync>>],
+        index: usize,
+    ) -> usize {
+        let field = &type_fields[index];
+        let mut end = index + 1;
+        while type_fields
+            .get(end)
+            .is_some_and(|next_field| next_field.get_level() > field.get_level())
+        {
+            end += 1;
+        }
+        if field.is_array() {
+            self.write_i32(0);
+        } else if end == index + 1 {
+            self.data
+                .resize(self.data.len() + field.get_byte_size().max(0) as usize, 0);
+        } else {
+            let mut child = index + 1;
+            while child < end {
+                child = self.write_default(type_fields, child);
+            }
+        }
+        if field.is_align() {
+            self.align();
+        }
+        end
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        match self.object.endian {
+            Endian::Little => self.data.extend_from_slice(&value.to_le_bytes()),
+            Endian::Big => self.data.extend_from_slice(&value.to_be_bytes()),
+        }
+    }
+
+    fn align(&mut self) {
+        let size = self.data.len();
+        if !size.is_multiple_of(4) {
+            self.data.resize(size + 4 - size % 4, 0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        serialized_file::SerializedFile,
+        test_data::{
+            read_game_object_yaml, read_prefabs_cab, read_prefabs_object, GAME_OBJECT_YAML, PLAYER,
+        },
+        type_tree::convert::TryCastFrom,
+    };
+
+    #[test]
+    fn test_set_and_relayout() {
+        let mut object = read_game_object_yaml(GAME_OBJECT_YAML);
+        object.set("/Base/m_Layer", 7u8).unwrap();
+        object.set("/Base/m_LocalScale/y", 0.5f64).unwrap();
+        assert!(matches!(
+            object.set("/Base/m_Layer", -1i32),
+            Err(Error::ValueOutOfRange { .. })
+        ));
+        assert!(matches!(
+            object.set("/Base/m_Layer", 1.0f32),
+            Err(Error::TypeMisMatch { .. })
+        ));
+
+        object.set("/Base/m_Name", "Heroine").unwrap();
+        object.set("/Base/m_Data", vec![1u8, 2, 3]).unwrap();
+        let index = object.push_array_item("/Base/m_Component").unwrap();
+        object
+            .set(
+                &format!("/Base/m_Component/Array/{index}/component/m_PathID"),
+                6i64,
+            )
+            .unwrap();
+        object.remove_array_item("/Base/m_Tags", 0).unwrap();
+        object.resize_array("/Base/m_Tags", 2).unwrap();
+        assert!(object.remove_array_item("/Base/m_Tags", 2).is_err());
+        object.set("/Base/m_Tags/Array/1/first", "c").unwrap();
+        object.set("/Base/m_Tags/Array/1/second", 3i32).unwrap();
+
+        assert_eq!(
+            String::try_cast_from(&object, "/Base/m_Name").unwrap(),
+            "Heroine"
+        );
+        assert!(bool::try_cast_from(&object, "/Base/m_IsActive").unwrap());
+        let expected = read_game_object_yaml(
+            r#"--- !u!1 &1
+GameObject:
+  m_Component:
+  - component: {fileID: 4}
+  - component: {fileID: 5}
+  - component: {fileID: 6}
+  m_Layer: 7
+  m_Name: Heroine
+  m_IsActive: 1
+  m_LocalScale: {x: 1, y: 0.5, z: 3}
+  m_Data: 010203
+  m_Tags:
+  - Head: 2
+  - c: 3
+"#,
+        );
+        assert_eq!(object.to_bytes(), expected.to_bytes());
+    }
+
+    #[test]
+    fn test_prefabs_bundle_round_trip() {
+        let cab = read_prefabs_cab();
+        let serialized_file =
+            SerializedFile::read(Box::new(Cursor::new(cab.clone())), 0, None).unwrap();
+        // objects of the fixture are stored in path id order, aligned to 8.
+        let mut data = Vec::new();
+        for path_id in serialized_file.get_object_map().keys() {
+            let object = serialized_file
+                .get_tt_object_by_path_id(*path_id)
+                .unwrap()
+                .unwrap();
+            data.resize(data.len().next_multiple_of(8), 0);
+            data.extend(object.to_bytes());
+        }
+        let data_offset = u32::from_be_bytes(cab[12..16].try_into().unwrap()) as usize;
+        assert_eq!(data, cab[data_offset..]);
+
+        let player = read_prefabs_object(PLAYER);
+        let original = player.to_bytes().unwrap();
+        player.set("/Base/m_Name", "Player One").unwrap();
+        player.set("/Base/m_Layer", 3u32).unwrap();
+        assert_eq!(
+            String::try_cast_from(&player, "/Base/m_Name").unwrap(),
+            "Player One"
+        );
+        assert!(bool::try_cast_from(&player, "/Base/m_IsActive").unwrap());
+
+        // m_Component with one item, m_Layer, then the aligned m_Name "Player" till 32.
+        let mut expected = original[..16].to_vec();
+        expected.extend(3u32.to_le_bytes());
+        expected.extend(10u32.to_le_bytes());
+        expected.extend(b"Player One\0\0");
+        expected.extend(&original[32..]);
+        assert_eq!(player.to_bytes().unwrap(), expected);
+    }
+}
@@ -0,0 +1,435 @@
+use std::fmt::Write;
+
+use super::{
+    convert::{FieldCastArgs, TryCast},
+    Field, FieldValue, TypeTreeObject, TypeTreeObjectRef,
+};
+use crate::{
+    error::{Error, ReadResult},
+    serialized_file::{version17::FileIdentifier, SerializedFile},
+};
+
+/// Written once before the documents of a file.
+pub const UNITY_YAML_HEADER: &str = "%YAML 1.1\n%TAG !u! tag:unity3d.com,2011:\n";
+
+/// kTransferUsingFlowMappingStyle, set for small structs like Vector3f and ColorRGBA.
+const FLOW_MAPPING_STYLE_FLAG: i32 = 1 << 21;
+/// kTransferHex64, 64 bit integers like hashes are written as hex.
+pub(super) const HEX64_FLAG: i32 = 1 << 24;
+
+/// Value of the editor text format, scalars are kept as unquoted text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum YamlValue {
+    Scalar(String),
+    Mapping {
+        entries: Vec<(String, YamlValue)>,
+        flow: bool,
+    },
+    Sequence(Vec<YamlValue>),
+}
+
+impl TypeTreeObject {
+    /// One `--- !u!<class> &<path_id>` document as the editor write it.
+    /// externals are the externals of the serialized file of the object, for guid of PPtr.
+    pub fn to_yaml(&self, externals: &[FileIdentifier]) -> ReadResult<String> {
+        let yaml_args = YamlArgs {
+            object_data_buff: &self.data_buff,
+            externals,
+        };
+        let value = self
+            .data_layout
+            .to_yaml(&yaml_args, &self.get_field_cast_args())?;
+        let mut yaml = format!("--- !u!{} &{}\n", self.class_id, self.path_id);
+        write_entry(&mut yaml, self.data_layout.get_type(), &value, 0);
+        Ok(yaml)
+    }
+}
+
+impl TypeTreeObjectRef {
+    pub fn to_yaml(&self, externals: &[FileIdentifier]) -> ReadResult<String> {
+        self.inner
+            .read()
+            .map_err(|e| Error::Other(e.to_string()))?
+            .to_yaml(externals)
+    }
+}
+
+impl SerializedFile {
+    /// Every object of the file in path id order, like a scene or prefab saved by the editor.
+    pub fn to_yaml(&self) -> ReadResult<String> {
+        let externals = self.get_externals();
+        let mut yaml = UNITY_YAML_HEADER.to_owned();
+        for path_id in self.get_object_map().keys() {
+            if let Some(object) = self.get_tt_object_by_path_id(*path_id)? {
+                yaml.push_str(&object.to_yaml(&externals)?);
+            }
+        }
+        Ok(yaml)
+    }
+}
+
+struct YamlArgs<'a> {
+    object_data_buff: &'a [u8],
+    externals: &'a [FileIdentifier],
+}
+
+impl Field {
+    fn to_yaml(
+        &self,
+        yaml_args: &YamlArgs,
+        field_cast_args: &FieldCastArgs,
+    ) -> ReadResult<YamlValue> {
+        let object_data_buff = yaml_args.object_data_buff;
+        match &self.data {
+            FieldValue::DataOffset(_) => self.primitive_to_yaml(object_data_buff, field_cast_args),
+            FieldValue::Fields(fields) => {
+                if self.get_type() == "string" {
+                    let value: String = self.try_cast_to(object_data_buff, field_cast_args)?;
+                    return Ok(YamlValue::Scalar(value));
+                }
+                if self.get_type().starts_with("PPtr<") {
+                    if let Some(pptr) = self.pptr_to_yaml(yaml_args, field_cast_args) {
+                        return Ok(pptr);
+                    }
+                }
+                // vector, map and staticvector only wrap their Array.
+                if let (1, Some(array)) = (fields.len(), fields.get("Array")) {
+                    if let FieldValue::Array(_) = array.data {
+                        return array.to_yaml(yaml_args, field_cast_args);
+                    }
+                }
+                // pair with a string key is written as a one entry mapping, like m_TexEnvs of Material.
+                if self.get_type() == "pair" {
+                    if let (Some(first), Some(second)) = (fields.get("first"), fields.get("second"))
+                    {
+                        if first.get_type() == "string" {
+                            let key: String =
+                                first.try_cast_to(object_data_buff, field_cast_args)?;
+                            return Ok(YamlValue::Mapping {
+                                entries: vec![(key, second.to_yaml(yaml_args, field_cast_args)?)],
+                                flow: false,
+                            });
+                        }
+                    }
+                }
+                let mut entries = Vec::new();
+                for field in fields.values() {
+                    entries.push((
+                        field.get_name().to_owned(),
+                        field.to_yaml(yaml_args, field_cast_args)?,
+                    ));
+                }
+                Ok(YamlValue::Mapping {
+                    entries,
+                    flow: self.field_type.get_meta_flag() & FLOW_MAPPING_STYLE_FLAG != 0,
+                })
+            }
+            FieldValue::Array(array) => {
+                if let Some((item_type, 1)) = self.try_get_buff_type_and_type_size() {
+                    if ["UInt8", "SInt8", "char"].contains(&item_type.as_str()) {
+                        let data: Vec<u8> = self.try_cast_to(object_data_buff, field_cast_args)?;
+                        return Ok(YamlValue::Scalar(to_hex(&data)));
+                    }
+                }
+                array
+                    .iter_items(object_data_buff, field_cast_args)?
+                    .map(|item| {
+                        let (item, item_cast_args) = item?;
+                        item.to_yaml(yaml_args, &item_cast_args)
+                    })
+                    .collect::<ReadResult<_>>()
+                    .map(YamlValue::Sequence)
+            }
+        }
+    }
+
+    fn primitive_to_yaml(
+        &self,
+        object_data_buff: &[u8],
+        field_cast_args: &FieldCastArgs,
+    ) -> ReadResult<YamlValue> {
+        let hex64 = self.field_type.get_meta_flag() & HEX64_FLAG != 0;
+        let scalar = match self.get_type().as_str() {
+            "bool" => {
+                let value: bool = self.try_cast_to(object_data_buff, field_cast_args)?;
+                (value as u8).to_string()
+            }
+            "SInt8" | "SInt16" | "short" | "SInt32" | "int" => {
+                let value: i64 = self.try_cast_to(object_data_buff, field_cast_args)?;
+                value.to_string()
+            }
+            "SInt64" | "long long" => {
+                let value: i64 = self.try_cast_to(object_data_buff, field_cast_args)?;
+                if hex64 {
+                    format!("{value:016x}")
+                } else {
+                    value.to_string()
+                }
+            }
+            "UInt8" | "char" | "UInt16" | "unsigned short" | "UInt32" | "unsigned int" => {
+                let value: u64 = self.try_cast_to(object_data_buff, field_cast_args)?;
+                value.to_string()
+            }
+            "UInt64" | "unsigned long long" => {
+                let value: u64 = self.try_cast_to(object_data_buff, field_cast_args)?;
+                if hex64 {
+                    format!("{value:016x}")
+                } else {
+                    value.to_string()
+                }
+            }
+            "FileSize" => {
+                let value: usize = self.try_cast_to(object_data_buff, field_cast_args)?;
+                value.to_string()
+            }
+            "float" => {
+                let value: f32 = self.try_cast_to(object_data_buff, field_cast_args)?;
+                float_to_yaml(value as f64, format!("{value}"), format!("{value:e}"))
+            }
+            "double" => {
+                let value: f64 = self.try_cast_to(object_data_buff, field_cast_args)?;
+                float_to_yaml(value, format!("{value}"), format!("{value:e}"))
+            }
+            _ => to_hex(self.try_as_slice(object_data_buff, field_cast_args)?),
+        };
+        Ok(YamlValue::Scalar(scalar))
+    }
+
+    /// `{fileID: path_id}` for objects in the same file, guid and type are added for externals.
+    fn pptr_to_yaml(
+        &self,
+        yaml_args: &YamlArgs,
+        field_cast_args: &FieldCastArgs,
+    ) -> Option<YamlValue> {
+        let FieldValue::Fields(fields) = &self.data else {
+            return None;
+        };
+        let file_id: i32 = fields
+            .get("m_FileID")?
+            .try_cast_to(yaml_args.object_data_buff, field_cast_args)
+            .ok()?;
+        let path_id: i64 = fields
+            .get("m_PathID")?
+            .try_cast_to(yaml_args.object_data_buff, field_cast_args)
+            .ok()?;
+        let mut entries = vec![("fileID".to_owned(), YamlValue::Scalar(path_id.to_string()))];
+        if file_id != 0 && path_id != 0 {
+            if let Some(external) = yaml_args.externals.get(file_id as usize - 1) {
+                entries.push((
+                    "guid".to_owned(),
+                    YamlValue::Scalar(guid_to_string(&external.guid)),
+                ));
+                entries.push((
+                    "type".to_owned(),
+                    YamlValue::Scalar(external.r#type.to_string()),
+                ));
+            }
+        }
+        Some(YamlValue::Mapping {
+            entries,
+            flow: true,
+        })
+    }
+}
+
+fn write_entry(yaml: &mut String, key: &str, value: &YamlValue, indent: usize) {
+    let _ = write!(yaml, "{:indent$}{}:", "", quote_string(key, false));
+    match value {
+        YamlValue::Scalar(scalar) => {
+            let _ = writeln!(yaml, " {}", quote_string(scalar, false));
+        }
+        YamlValue::Mapping { entries, flow } if *flow || entries.is_empty() => {
+            let _ = writeln!(yaml, " {}", to_flow(value));
+        }
+        YamlValue::Mapping { entries, .. } => {
+            yaml.push('\n');
+            for (key, value) in entries {
+                write_entry(yaml, key, value, indent + 2);
+            }
+        }
+        YamlValue::Sequence(items) if items.is_empty() => yaml.push_str(" []\n"),
+        YamlValue::Sequence(items) => {
+            yaml.push('\n');
+            // sequence items are not indented under their key.
+            for item in items {
+                write_item(yaml, item, indent);
+            }
+        }
+    }
+}
+
+fn write_item(yaml: &mut String, value: &YamlValue, indent: usize) {
+    let mut item = String::new();
+    match value {
+        YamlValue::Mapping {
+            entries,
+            flow: false,
+        } if !entries.is_empty() => {
+            for (key, value) in entries {
+                write_entry(&mut item, key, value, indent + 2);
+            }
+        }
+        YamlValue::Sequence(items) if !items.is_empty() => {
+            for item_value in items {
+                write_item(&mut item, item_value, indent + 2);
+            }
+        }
+        value => {
+            let _ = writeln!(yaml, "{:indent$}- {}", "", to_flow(value));
+            return;
+        }
+    }
+    // the first line of the item follow the dash.
+    let _ = write!(yaml, "{:indent$}- {}", "", &item[indent + 2..]);
+}
+
+fn to_flow(value: &YamlValue) -> String {
+    match value {
+        YamlValue::Scalar(scalar) => quote_string(scalar, true),
+        YamlValue::Mapping { entries, .. } => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| format!("{}: {}", quote_string(key, true), to_flow(value)))
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        }
+        YamlValue::Sequence(items) => {
+            let items: Vec<String> = items.iter().map(to_flow).collect();
+            format!("[{}]", items.join(", "))
+        }
+    }
+}
+
+/// Shortest text of the value, very small and large values use exponent like 1e-10.
+fn float_to_yaml(value: f64, text: String, exp_text: String) -> String {
+    if value.is_nan() {
+        "NaN".to_owned()
+    } else if value.is_infinite() {
+        if value > 0.0 { "Infinity" } else { "-Infinity" }.to_owned()
+    } else if value != 0.0 && !(1e-4..1e16).contains(&value.abs()) {
+        exp_text
+    } else {
+        text
+    }
+}
+
+/// Plain when possible, single quoted when printable and double quoted with escapes otherwise.
+/// Plain scalars in flow style can not hold flow indicators.
+fn quote_string(value: &str, in_flow: bool) -> String {
+    if value.chars().any(|c| c.is_control()) {
+        let mut quoted = String::from("\"");
+        for c in value.chars() {
+            match c {
+                '"' => quoted.push_str("\\\""),
+                '\\' => quoted.push_str("\\\\"),
+                '\n' => quoted.push_str("\\n"),
+                '\t' => quoted.push_str("\\t"),
+                '\r' => quoted.push_str("\\r"),
+                c if c.is_control() => {
+                    let _ = write!(quoted, "\\x{:02X}", c as u32);
+                }
+                c => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        return quoted;
+    }
+    let is_plain = if value.is_empty() {
+        !in_flow
+    } else {
+        !value.starts_with(|c: char| "?:,[]{}#&*!|>'\"%@` ".contains(c))
+            && !value.starts_with("- ")
+            && value != "-"
+            && !value.ends_with([' ', ':'])
+            && !value.contains(": ")
+            && !value.contains(" #")
+            && !(in_flow && value.contains([',', '[', ']', '{', '}']))
+    };
+    if is_plain {
+        value.to_owned()
+    } else {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+}
+
+/// Guid text of the editor, the low nibble of each byte come first.
+pub(super) fn guid_to_string(guid: &[u8; 16]) -> String {
+    guid.iter()
+        .map(|byte| format!("{:x}{:x}", byte & 0xf, byte >> 4))
+        .collect()
+}
+
+/// Byte arrays are written as hex by the exporters and the yaml importer.
+pub(super) fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_yaml() {
+        let mut pptr = TypeTreeObject::new_pptr(0, 0, 42);
+        pptr.class_id = 4;
+        pptr.path_id = 7;
+        assert_eq!(
+            pptr.to_yaml(&[]).unwrap(),
+            "--- !u!4 &7\nPPtr<Object>: {fileID: 42}\n"
+        );
+
+        let value = YamlValue::Mapping {
+            entries: vec![
+                ("m_Name".to_owned(), YamlValue::Scalar(String::new())),
+                (
+                    "m_Component".to_owned(),
+                    YamlValue::Sequence(vec![YamlValue::Mapping {
+                        entries: vec![(
+                            "component".to_owned(),
+                            YamlValue::Mapping {
+                                entries: vec![(
+                                    "fileID".to_owned(),
+                                    YamlValue::Scalar("4".to_owned()),
+                                )],
+                                flow: true,
+                            },
+                        )],
+                        flow: false,
+                    }]),
+                ),
+                ("m_Children".to_owned(), YamlValue::Sequence(Vec::new())),
+                (
+                    "m_LocalScale".to_owned(),
+                    YamlValue::Mapping {
+                        entries: vec![
+                            ("x".to_owned(), YamlValue::Scalar("1".to_owned())),
+                            ("y".to_owned(), YamlValue::Scalar("-0.5".to_owned())),
+                        ],
+                        flow: true,
+                    },
+                ),
+            ],
+            flow: false,
+        };
+        let mut yaml = String::new();
+        write_entry(&mut yaml, "GameObject", &value, 0);
+        assert_eq!(
+            yaml,
+            "GameObject:\n  m_Name: \n  m_Component:\n  - component: {fileID: 4}\n  m_Children: []\n  m_LocalScale: {x: 1, y: -0.5}\n"
+        );
+
+        assert_eq!(
+            float_to_yaml(1e-10, format!("{}", 1e-10f32), format!("{:e}", 1e-10f32)),
+            "1e-10"
+        );
+        assert_eq!(quote_string("Main Camera", false), "Main Camera");
+        assert_eq!(quote_string("a, b", true), "'a, b'");
+        assert_eq!(quote_string("a: b", false), "'a: b'");
+        assert_eq!(quote_string("line\n", false), "\"line\\n\"");
+        let mut guid = [0u8; 16];
+        guid[8] = 0x0e;
+        // unity default resources
+        assert_eq!(guid_to_string(&guid), "0000000000000000e000000000000000");
+        assert_eq!(to_hex(&[0, 15, 255]), "000fff");
+    }
+}
@@ -0,0 +1,793 @@
+use std::{io::Cursor, sync::Arc};
+
+use binrw::{BinRead, BinWrite, Endian};
+
+use super::{
+    provider,
+    reader::{TypeTreeObjectBinReadArgs, TypeTreeObjectBinReadClassArgs},
+    yaml_export::{guid_to_string, to_hex, YamlValue, HEX64_FLAG},
+    TypeField, TypeTreeObject,
+};
+use crate::{
+    error::{Error, ReadResult},
+    serialized_file::version17::FileIdentifier,
+};
+
+/// One `--- !u!<class> &<path_id>` document of a text asset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlDocument {
+    pub class_id: i32,
+    pub path_id: i64,
+    /// Objects of a prefab instance written with only their source reference.
+    pub stripped: bool,
+    /// Key of the document root, the class name like GameObject.
+    pub root_name: String,
+    pub value: YamlValue,
+}
+
+/// Read the documents of a `.asset`, `.prefab` or `.unity` file written by the editor.
+pub fn parse_unity_yaml(text: &str) -> ReadResult<Vec<YamlDocument>> {
+    let mut documents = Vec::new();
+    let mut header: Option<(usize, &str)> = None;
+    let mut lines = Vec::new();
+    for (line_number, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.starts_with("---") {
+            if let Some(header) = header.take() {
+                documents.push(parse_document(header, &lines)?);
+            }
+            header = Some((line_number + 1, line));
+            lines.clear();
+        } else if header.is_some() {
+            let text = line.trim_start_matches(' ');
+            if !text.trim().is_empty() && !text.starts_with('#') {
+                lines.push((line_number + 1, line.len() - text.len(), text.trim_end()));
+            }
+        }
+        // %YAML and %TAG directives before the first document are skipped.
+    }
+    if let Some(header) = header {
+        documents.push(parse_document(header, &lines)?);
+    }
+    Ok(documents)
+}
+
+fn parse_document(
+    (line_number, header): (usize, &str),
+    lines: &[(usize, usize, &str)],
+) -> ReadResult<YamlDocument> {
+    let error = |message: &str| Error::YamlError {
+        line: line_number,
+        message: format!("{message} : {header}"),
+    };
+    let mut class_id = None;
+    let mut path_id = None;
+    let mut stripped = false;
+    for token in header.split_whitespace().skip(1) {
+        if let Some(class) = token.strip_prefix("!u!") {
+            class_id = class.parse().ok();
+        } else if let Some(anchor) = token.strip_prefix('&') {
+            path_id = anchor.parse().ok();
+        } else if token == "stripped" {
+            stripped = true;
+        }
+    }
+    let (Some(class_id), Some(path_id)) = (class_id, path_id) else {
+        return Err(error("document header without class or anchor"));
+    };
+    let Some(&(_, indent, _)) = lines.first() else {
+        return Err(error("empty document"));
+    };
+    let mut parser = BlockParser {
+        lines: lines.to_vec(),
+        pos: 0,
+    };
+    let value = parser.parse_node(indent)?;
+    if let Some(&(line, _, text)) = parser.lines.get(parser.pos) {
+        return Err(Error::YamlError {
+            line,
+            message: format!("unexpected indent : {text}"),
+        });
+    }
+    match value {
+        YamlValue::Mapping { mut entries, .. } if entries.len() == 1 => {
+            let (root_name, value) = entries.pop().unwrap();
+            Ok(YamlDocument {
+                class_id,
+                path_id,
+                stripped,
+                root_name,
+                value,
+            })
+        }
+        _ => Err(error("document root is not a class name")),
+    }
+}
+
+/// Block style of the editor, lines are (line number, indent, text).
+struct BlockParser<'a> {
+    lines: Vec<(usize, usize, &'a str)>,
+    pos: usize,
+}
+
+impl<'a> BlockParser<'a> {
+    fn parse_node(&mut self, indent: usize) -> ReadResult<YamlValue> {
+        let (line, _, text) = self.lines[self.pos];
+        if is_sequence_item(text) {
+            self.parse_sequence(indent)
+        } else if split_key(text)
+            .map_err(|message| Error::YamlError { line, message })?
+            .is_some()
+        {
+            self.parse_mapping(indent)
+        } else {
+            self.pos += 1;
+            self.parse_inline(text, line, indent)
+        }
+    }
+
+    fn parse_sequence(&mut self, indent: usize) -> ReadResult<YamlValue> {
+        let mut items = Vec::new();
+        while let Some(&(line, line_indent, text)) = self.lines.get(self.pos) {
+            if line_indent != indent || !is_sequence_item(text) {
+                break;
+            }
+            let rest = text[1..].trim_start();
+            if rest.is_empty() {
+                self.pos += 1;
+                items.push(self.parse_nested(indent, false)?);
+                continue;
+            }
+            let is_block = is_sequence_item(rest)
+                || split_key(rest)
+                    .map_err(|message| Error::YamlError { line, message })?
+                    .is_some();
+            if is_block {
+                // the item continue on the next lines at the column after the dash.
+                let rest_indent = line_indent + text.len() - rest.len();
+                self.lines[self.pos] = (line, rest_indent, rest);
+                items.push(self.parse_node(rest_indent)?);
+            } else {
+                self.pos += 1;
+                items.push(self.parse_inline(rest, line, indent)?);
+            }
+        }
+        Ok(YamlValue::Sequence(items))
+    }
+
+    fn parse_mapping(&mut self, indent: usize) -> ReadResult<YamlValue> {
+        let mut entries = Vec::new();
+        while let Some(&(line, line_indent, text)) = self.lines.get(self.pos) {
+            if line_indent != indent || is_sequence_item(text) {
+                break;
+            }
+            let (key, rest) = split_key(text)
+                .map_err(|message| Error::YamlError { line, message })?
+                .ok_or_else(|| Error::YamlError {
+                    line,
+                    message: format!("expect a key : {text}"),
+                })?;
+            self.pos += 1;
+            let value = if rest.is_empty() {
+                self.parse_nested(indent, true)?
+            } else {
+                self.parse_inline(rest, line, indent)?
+            };
+            entries.push((key, value));
+        }
+        Ok(YamlValue::Mapping {
+            entries,
+            flow: false,
+        })
+    }
+
+    /// Value on the lines after a key or a dash, the editor write sequences of a key at the key indent.
+    fn parse_nested(&mut self, indent: usize, is_key: bool) -> ReadResult<YamlValue> {
+        match self.lines.get(self.pos) {
+            Some(&(_, line_indent, text))
+                if line_indent > indent
+                    || (is_key && line_indent == indent && is_sequence_item(text)) =>
+            {
+                self.parse_node(line_indent)
+            }
+            _ => Ok(YamlValue::Scalar(String::new())),
+        }
+    }
+
+    /// Scalar or flow value, long values are wrapped to the next more indented lines.
+    fn parse_inline(&mut self, text: &str, line: usize, indent: usize) -> ReadResult<YamlValue> {
+        let mut text = text.to_owned();
+        while let Some(&(_, line_indent, next_text)) = self.lines.get(self.pos) {
+            if line_indent <= indent {
+                break;
+            }
+            // a double quoted scalar broken with an escaped line break.
+            if text.starts_with('"') && text.ends_with('\\') {
+                text.pop();
+            } else {
+                text.push(' ');
+            }
+            text.push_str(next_text);
+            self.pos += 1;
+        }
+        let mut parser = FlowParser {
+            text: &text,
+            pos: 0,
+        };
+        parser
+            .parse_value(false)
+            .and_then(|value| {
+                parser.skip_spaces();
+                if parser.pos == text.len() {
+                    Ok(value)
+                } else {
+                    Err(format!("unexpected text : {}", &text[parser.pos..]))
+                }
+            })
+            .map_err(|message| Error::YamlError { line, message })
+    }
+}
+
+fn is_sequence_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Key and the rest of a `key: value` line, None when the line is not a mapping entry.
+fn split_key(text: &str) -> Result<Option<(String, &str)>, String> {
+    if text.starts_with(['\'', '"']) {
+        let mut parser = FlowParser { text, pos: 0 };
+        let key = parser.parse_quoted()?;
+        parser.skip_spaces();
+        return Ok(parser.text[parser.pos..]
+            .strip_prefix(':')
+            .map(|rest| (key, rest.trim_start())));
+    }
+    if text.starts_with(['{', '[']) {
+        return Ok(None);
+    }
+    for (index, _) in text.match_indices(':') {
+        let rest = &text[index + 1..];
+        if rest.is_empty() || rest.starts_with(' ') {
+            return Ok(Some((
+                text[..index].trim_end().to_owned(),
+                rest.trim_start(),
+            )));
+        }
+    }
+    Ok(None)
+}
+
+struct FlowParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> FlowParser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek() == Some(' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_spaces();
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            c => Err(format!("expect {expected:?} but found {c:?}")),
+        }
+    }
+
+    fn parse_value(&mut self, in_flow: bool) -> Result<YamlValue, String> {
+        self.skip_spaces();
+        match self.peek() {
+            Some('{') => self.parse_flow_mapping(),
+            Some('[') => self.parse_flow_sequence(),
+            Some('\'' | '"') => self.parse_quoted().map(YamlValue::Scalar),
+            _ => Ok(YamlValue::Scalar(self.parse_plain(in_flow, false))),
+        }
+    }
+
+    fn parse_flow_mapping(&mut self) -> Result<YamlValue, String> {
+        self.expect('{')?;
+        let mut entries = Vec::new();
+        self.skip_spaces();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+        } else {
+            loop {
+                self.skip_spaces();
+                let key = if let Some('\'' | '"') = self.peek() {
+                    self.parse_quoted()?
+                } else {
+                    self.parse_plain(true, true)
+                };
+                self.expect(':')?;
+                self.skip_spaces();
+                let value = match self.peek() {
+                    Some(',' | '}') => YamlValue::Scalar(String::new()),
+                    _ => self.parse_value(true)?,
+                };
+                entries.push((key, value));
+                self.skip_spaces();
+                match self.next() {
+                    Some(',') => (),
+                    Some('}') => break,
+                    c => return Err(format!("expect ',' or '}}' but found {c:?}")),
+                }
+            }
+        }
+        Ok(YamlValue::Mapping {
+            entries,
+            flow: true,
+        })
+    }
+
+    fn parse_flow_sequence(&mut self) -> Result<YamlValue, String> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_spaces();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+        } else {
+            loop {
+                items.push(self.parse_value(true)?);
+                self.skip_spaces();
+                match self.next() {
+                    Some(',') => (),
+                    Some(']') => break,
+                    c => return Err(format!("expect ',' or ']' but found {c:?}")),
+                }
+            }
+        }
+        Ok(YamlValue::Sequence(items))
+    }
+
+    /// Plain scalar, in flow it end at a flow indicator and a key end at ':'.
+    fn parse_plain(&mut self, in_flow: bool, is_key: bool) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if in_flow && [',', '[', ']', '{', '}'].contains(&c) {
+                break;
+            }
+            if is_key && c == ':' {
+                let rest = &self.text[self.pos + 1..];
+                if rest.is_empty() || rest.starts_with([' ', ',', '}']) {
+                    break;
+                }
+            }
+            self.pos += c.len_utf8();
+        }
+        self.text[start..self.pos].trim().to_owned()
+    }
+
+    fn parse_quoted(&mut self) -> Result<String, String> {
+        let quote = self.next();
+        let mut value = String::new();
+        loop {
+            match (quote, self.next()) {
+                (_, None) => return Err("unterminated quoted scalar".to_owned()),
+                (Some('\''), Some('\'')) => {
+                    if self.peek() == Some('\'') {
+                        self.pos += 1;
+                        value.push('\'');
+                    } else {
+                        return Ok(value);
+                    }
+                }
+                (Some('"'), Some('"')) => return Ok(value),
+                (Some('"'), Some('\\')) => value.push(self.parse_escape()?),
+                (_, Some(c)) => value.push(c),
+            }
+        }
+    }
+
+    fn parse_escape(&mut self) -> Result<char, String> {
+        let c = self.next().ok_or("unterminated escape")?;
+        let hex_size = match c {
+            '0' => return Ok('\0'),
+            'a' => return Ok('\x07'),
+            'b' => return Ok('\x08'),
+            't' | '\t' => return Ok('\t'),
+            'n' => return Ok('\n'),
+            'v' => return Ok('\x0b'),
+            'f' => return Ok('\x0c'),
+            'r' => return Ok('\r'),
+            'e' => return Ok('\x1b'),
+            ' ' | '"' | '/' | '\\' => return Ok(c),
+            'N' => return Ok('\u{85}'),
+            '_' => return Ok('\u{a0}'),
+            'L' => return Ok('\u{2028}'),
+            'P' => return Ok('\u{2029}'),
+            'x' => 2,
+            'u' => 4,
+            'U' => 8,
+            c => return Err(format!("unknown escape \\{c}")),
+        };
+        let hex = self
+            .text
+            .get(self.pos..self.pos + hex_size)
+            .ok_or("unterminated escape")?;
+        self.pos += hex_size;
+        u32::from_str_radix(hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| format!("invalid escape \\{c}{hex}"))
+    }
+}
+
+impl YamlDocument {
+    /// Type tree of the document class from the type tree providers.
+    pub fn get_class_args(&self, unity_version: &str) -> Option<TypeTreeObjectBinReadClassArgs> {
+        #[cfg(feature = "type-tree-json")]
+        if let Some(class_args) = super::type_tree_json::get_type_object_args_by_version_class_id(
+            &unity_version.to_owned(),
+            self.class_id,
+        ) {
+            return Some(class_args);
+        }
+        provider::get_type_object_args_by_version_class_id(unity_version, self.class_id)
+    }
+
+    /// Object data laid out by the type tree, as it is stored in a serialized file.
+    /// PPtr with a guid get the file id of the external with the same guid.
+    pub fn to_object_bytes(
+        &self,
+        class_args: &TypeTreeObjectBinReadClassArgs,
+        endian: Endian,
+        externals: &[FileIdentifier],
+    ) -> ReadResult<Vec<u8>> {
+        let mut writer = ObjectWriter {
+            type_fields: class_args.get_type_fields(),
+            endian,
+            externals,
+            data: Cursor::new(Vec::new()),
+            path: Vec::new(),
+        };
+        if writer.type_fields.is_empty() {
+            return Err(Error::TypeTreeObjectBinReadArgsBuild);
+        }
+        writer.write_field(0, &self.value)?;
+        Ok(writer.data.into_inner())
+    }
+
+    pub fn to_type_tree_object(
+        &self,
+        class_args: &TypeTreeObjectBinReadClassArgs,
+        endian: Endian,
+        externals: &[FileIdentifier],
+        serialized_file_id: i64,
+    ) -> ReadResult<TypeTreeObject> {
+        let data = self.to_object_bytes(class_args, endian, externals)?;
+        Ok(TypeTreeObject::read_options(
+            &mut Cursor::new(data),
+            endian,
+            TypeTreeObjectBinReadArgs::new(serialized_file_id, self.path_id, class_args.clone()),
+        )?)
+    }
+}
+
+struct ObjectWriter<'a> {
+    type_fields: &'a [Arc<Box<dyn TypeField + Send + Sync>>],
+    endian: Endian,
+    externals: &'a [FileIdentifier],
+    data: Cursor<Vec<u8>>,
+    path: Vec<String>,
+}
+
+impl<'a> ObjectWriter<'a> {
+    fn error(&self, message: String) -> Error {
+        Error::YamlValueError {
+            path: self.path.clone(),
+            message,
+        }
+    }
+
+    /// Index after the children of the field.
+    fn get_field_end(&self, index: usize) -> usize {
+        let level = self.type_fields[index].get_level();
+        self.type_fields[index + 1..]
+            .iter()
+            .position(|field| field.get_level() <= level)
+            .map_or(self.type_fields.len(), |position| index + 1 + position)
+    }
+
+    fn get_children(&self, index: usize) -> Vec<usize> {
+        let level = self.type_fields[index].get_level();
+        (index + 1..self.get_field_end(index))
+            .filter(|child| self.type_fields[*child].get_level() == level + 1)
+            .collect()
+    }
+
+    fn write_number<T>(&mut self, value: T) -> ReadResult<()>
+    where
+        T: for<'b> BinWrite<Args<'b> = ()>,
+    {
+        value.write_options(&mut self.data, self.endian, ())?;
+        Ok(())
+    }
+
+    fn write_field(&mut self, index: usize, value: &YamlValue) -> ReadResult<()> {
+        let field = self.type_fields[index].clone();
+        self.path.push(field.get_name().to_owned());
+        if field.is_array() {
+            self.write_array(index, value)?;
+        } else if self.get_field_end(index) == index + 1 {
+            self.write_primitive(field.as_ref().as_ref(), value)?;
+        } else {
+            self.write_fields(index, value)?;
+        }
+        if field.is_align() {
+            let size = self.data.get_ref().len();
+            if !size.is_multiple_of(4) {
+                self.data.get_mut().resize(size + 4 - size % 4, 0);
+                self.data.set_position(self.data.get_ref().len() as u64);
+            }
+        }
+        self.path.pop();
+        Ok(())
+    }
+
+    fn write_fields(&mut self, index: usize, value: &YamlValue) -> ReadResult<()> {
+        let field = self.type_fields[index].clone();
+        let children = self.get_children(index);
+        let child_names: Vec<&str> = children
+            .iter()
+            .map(|child| self.type_fields[*child].get_name().as_str())
+            .collect();
+        if let (YamlValue::Scalar(text), [array]) = (value, children.as_slice()) {
+            if field.get_type() == "string" {
+                let data = YamlValue::Scalar(to_hex(text.as_bytes()));
+                return self.write_field(*array, &data);
+            }
+        }
+        // vector, map and staticvector are written as their Array.
+        if let ([array], ["Array"]) = (children.as_slice(), child_names.as_slice()) {
+            if self.type_fields[*array].is_array() {
+                return self.write_field(*array, value);
+            }
+        }
+        let YamlValue::Mapping { entries, .. } = value else {
+            return Err(self.error(format!("expect a mapping for {}", field.get_type())));
+        };
+        let get = |name: &str| {
+            entries
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value)
+        };
+        let mut converted = None;
+        if field.get_type().starts_with("PPtr<") && child_names == ["m_FileID", "m_PathID"] {
+            let path_id =
+                get("fileID").ok_or_else(|| self.error("PPtr without fileID".to_owned()))?;
+            let file_id = match get("guid") {
+                Some(YamlValue::Scalar(guid)) if !guid.chars().all(|c| c == '0') => {
+                    let external = self
+                        .externals
+                        .iter()
+                        .position(|external| guid_to_string(&external.guid) == *guid)
+                        .ok_or_else(|| self.error(format!("no external has guid {guid}")))?;
+                    external + 1
+                }
+                _ => 0,
+            };
+            converted = Some(vec![
+                (
+                    "m_FileID".to_owned(),
+                    YamlValue::Scalar(file_id.to_string()),
+                ),
+                ("m_PathID".to_owned(), path_id.clone()),
+            ]);
+        } else if field.get_type() == "pair"
+            && entries.len() == 1
+            && child_names == ["first", "second"]
+            && get("first").is_none()
+        {
+            // pair with a string key written as a one entry mapping.
+            let (key, value) = &entries[0];
+            converted = Some(vec![
+                ("first".to_owned(), YamlValue::Scalar(key.to_owned())),
+                ("second".to_owned(), value.clone()),
+            ]);
+        }
+        let entries = converted.as_ref().unwrap_or(entries);
+        for (child, name) in children.iter().zip(child_names) {
+            let value = entries
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value)
+                .ok_or_else(|| {
+                    let mut path = self.path.clone();
+                    path.push(name.to_owned());
+                    Error::FieldNotFound(path)
+                })?;
+            self.write_field(*child, value)?;
+        }
+        Ok(())
+    }
+
+    fn write_array(&mut self, index: usize, value: &YamlValue) -> ReadResult<()> {
+        let children = self.get_children(index);
+        let [_size, item] = children.as_slice() else {
+            return Err(self.error("array without size and data".to_owned()));
+        };
+        let item_field = self.type_fields[*item].clone();
+        let is_byte_item = self.get_field_end(*item) == item + 1
+            && ["UInt8", "SInt8", "char"].contains(&item_field.get_type().as_str());
+        match value {
+            YamlValue::Sequence(items) => {
+                self.write_number(items.len() as i32)?;
+                for item_value in items {
+                    self.write_field(*item, item_value)?;
+                }
+            }
+            // byte arrays are written as hex.
+            YamlValue::Scalar(text) if is_byte_item => {
+                let data =
+                    from_hex(text).ok_or_else(|| self.error(format!("invalid hex {text:?}")))?;
+                self.write_number(data.len() as i32)?;
+                self.data.get_mut().extend(data);
+                self.data.set_position(self.data.get_ref().len() as u64);
+            }
+            YamlValue::Scalar(text) if text.is_empty() => self.write_number(0i32)?,
+            _ => return Err(self.error("expect a sequence".to_owned())),
+        }
+        Ok(())
+    }
+
+    fn write_primitive(
+        &mut self,
+        field: &(dyn TypeField + Send + Sync),
+        value: &YamlValue,
+    ) -> ReadResult<()> {
+        let YamlValue::Scalar(text) = value else {
+            return Err(self.error(format!("expect a scalar for {}", field.get_type())));
+        };
+        let text = text.as_str();
+        let hex64 = field.get_meta_flag() & HEX64_FLAG != 0;
+        let invalid = || self.error(format!("can not read {text:?} as {}", field.get_type()));
+        match (field.get_type().as_str(), field.get_byte_size()) {
+            ("bool", _) => {
+                let value = match text {
+                    "0" | "false" => 0u8,
+                    "1" | "true" => 1u8,
+                    _ => return Err(invalid()),
+                };
+                self.write_number(value)
+            }
+            ("SInt8", _) => self.write_number(text.parse::<i8>().map_err(|_| invalid())?),
+            ("SInt16" | "short", _) => {
+                self.write_number(text.parse::<i16>().map_err(|_| invalid())?)
+            }
+            ("SInt32" | "int", _) => self.write_number(text.parse::<i32>().map_err(|_| invalid())?),
+            ("SInt64" | "long long", _) => {
+                let value = if hex64 {
+                    u64::from_str_radix(text, 16).map(|value| value as i64).ok()
+                } else {
+                    text.parse::<i64>().ok()
+                };
+                self.write_number(value.ok_or_else(invalid)?)
+            }
+            ("UInt8" | "char", _) => self.write_number(text.parse::<u8>().map_err(|_| invalid())?),
+            ("UInt16" | "unsigned short", _) => {
+                self.write_number(text.parse::<u16>().map_err(|_| invalid())?)
+            }
+            ("UInt32" | "unsigned int", _) | ("FileSize", 4) => {
+                self.write_number(text.parse::<u32>().map_err(|_| invalid())?)
+            }
+            ("UInt64" | "unsigned long long" | "FileSize", _) => {
+                let value = if hex64 {
+                    u64::from_str_radix(text, 16).ok()
+                } else {
+                    text.parse::<u64>().ok()
+                };
+                self.write_number(value.ok_or_else(invalid)?)
+            }
+            ("float", _) => self.write_number(parse_float(text).ok_or_else(invalid)? as f32),
+            ("double", _) => self.write_number(parse_float(text).ok_or_else(invalid)?),
+            (_, byte_size) => {
+                let data = from_hex(text)
+                    .filter(|data| data.len() == byte_size as usize)
+                    .ok_or_else(invalid)?;
+                self.data.get_mut().extend(data);
+                self.data.set_position(self.data.get_ref().len() as u64);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn parse_float(text: &str) -> Option<f64> {
+    match text {
+        "NaN" | ".nan" | ".NaN" => Some(f64::NAN),
+        "Infinity" | ".inf" | ".Inf" => Some(f64::INFINITY),
+        "-Infinity" | "-.inf" | "-.Inf" => Some(f64::NEG_INFINITY),
+        text => text.parse().ok(),
+    }
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(text.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::build_game_object_class_args;
+
+    #[test]
+    fn test_yaml_round_trip() {
+        let class_args = build_game_object_class_args();
+        let mut guid = [0u8; 16];
+        guid[8] = 0x0e;
+        let externals = [FileIdentifier {
+            temp_empty: "".into(),
+            guid,
+            r#type: 2,
+            path: "Library/unity default resources".into(),
+        }];
+        let document = r#"--- !u!1 &-42
+GameObject:
+  m_Component:
+  - component: {fileID: 4}
+  - component: {fileID: 11400000, guid: 0000000000000000e000000000000000, type: 2}
+  m_Layer: 5
+  m_Name: 'Main: Camera'
+  m_IsActive: 1
+  m_LocalScale: {x: 1, y: -0.5, z: 1e-10}
+  m_Data: 00ff10
+  m_Tags:
+  - a: 1
+  - "b\tc": -2
+"#;
+        let documents = parse_unity_yaml(&format!(
+            "%YAML 1.1\n%TAG !u! tag:unity3d.com,2011:\n{document}"
+        ))
+        .unwrap();
+        assert_eq!(documents.len(), 1);
+        assert_eq!((documents[0].class_id, documents[0].path_id), (1, -42));
+        let object = documents[0]
+            .to_type_tree_object(&class_args, Endian::Little, &externals, 0)
+            .unwrap();
+        assert_eq!(object.to_yaml(&externals).unwrap(), document);
+
+        let documents = parse_unity_yaml(
+            "--- !u!114 &1 stripped\nMonoBehaviour:\n  m_Text: first\n    second\n  m_List:\n  - - 1\n    - 2\n  - []\n  m_Empty: \n",
+        )
+        .unwrap();
+        let scalar = |text: &str| YamlValue::Scalar(text.to_owned());
+        assert!(documents[0].stripped);
+        assert_eq!(
+            documents[0].value,
+            YamlValue::Mapping {
+                entries: vec![
+                    ("m_Text".to_owned(), scalar("first second")),
+                    (
+                        "m_List".to_owned(),
+                        YamlValue::Sequence(vec![
+                            YamlValue::Sequence(vec![scalar("1"), scalar("2")]),
+                            YamlValue::Sequence(vec![]),
+                        ])
+                    ),
+                    ("m_Empty".to_owned(), scalar("")),
+                ],
+                flow: false,
+            }
+        );
+    }
+}
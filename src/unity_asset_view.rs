@@ -1,35 +1,337 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    cell::Cell,
+    collections::{hash_map::Entry, BTreeMap, HashMap, HashSet},
     fs::OpenOptions,
-    io::{BufReader, Cursor},
+    io::{BufReader, Cursor, Read, Seek, Write},
     path::{Path, PathBuf},
+    sync::{Arc, Mutex},
 };
 
-use walkdir::WalkDir;
+use once_cell::{sync::Lazy, unsync::OnceCell};
+use regex::Regex;
 
 use crate::{
-    classes::{p_ptr::PPtr, ClassIDType},
+    asset_source::{open_if_exists, AssetSource, DirectorySource},
+    classes::{
+        asset_bundle_manifest::{AssetBundleInfo, AssetBundleManifest, AssetBundleManifestObject},
+        p_ptr::PPtr,
+        ClassIDType,
+    },
+    load_report::{detect_file_kind_by_header, FileKind, LoadReport, LoadedFile, FILE_HEADER_SIZE},
+    reference_graph::{ObjectId, ReferenceGraph, UnresolvedReference},
     serialized_file::SerializedFile,
-    type_tree::TypeTreeObject,
-    unityfs::UnityFS,
-    unityfs::UnityResource,
+    type_tree::{provider, reader::TypeTreeObjectBinReadClassArgs, TypeTreeObject},
+    unityfs::{UnityFS, UnityFSNode, UnityResource},
+    until::file_pool::{FilePool, PooledFile},
+    viewer_index::{
+        IndexedContainer, IndexedFile, IndexedObject, IndexedSerializedFile, ViewerIndex,
+    },
+    zip_package::ZipPackage,
 };
 use crate::{
     classes::{p_ptr::PPtrObject, SerializedFileRef},
     type_tree::TypeTreeObjectRef,
 };
-use crate::{error::ReadResult, type_tree::convert::TryCastFrom};
+use crate::{
+    error::{Error, ReadResult},
+    type_tree::convert::TryCastFrom,
+};
+
+/// Where a lazily loaded serialized file is read from.
+enum SerializedFileSource {
+    UnityFS { unity_fs_id: i64, path: String },
+    AssetSource { asset_source_id: i64, path: String },
+}
+
+/// What add_unity_fs added for a bundle.
+struct AddedUnityFS {
+    unity_fs_id: i64,
+    /// Ids and node paths of its serialized files.
+    serialized_files: Vec<(i64, String)>,
+    /// Archive paths of nodes which another bundle added before already has.
+    duplicate_nodes: Vec<String>,
+}
+
+/// Bundles listed by an AssetBundleManifest, their files are next to the manifest bundle.
+struct AssetBundleManifestEntry {
+    asset_source_id: i64,
+    dir: String,
+    asset_bundle_infos: BTreeMap<String, AssetBundleInfo>,
+}
+
+/// Bundle of the viewer, a bundle restored from an index is read on first access.
+struct UnityFSEntry {
+    /// Asset source and path the bundle is read from.
+    source: Option<(i64, String)>,
+    resource_search_path: Option<String>,
+    unity_fs: OnceCell<UnityFS>,
+}
+
+/// File read from an asset source, kept to reload it and to write it by save_index.
+struct FileRecord {
+    asset_source_id: i64,
+    path: String,
+    load_serialized_file: bool,
+    unity_fs_id: Option<i64>,
+    /// Serialized files of the file with their path in the bundle.
+    serialized_files: Vec<(i64, String)>,
+    /// Size and modified time, for sources which have them.
+    indexed_file: Option<IndexedFile>,
+}
+
+struct SerializedFileEntry {
+    source: Option<SerializedFileSource>,
+    resource_search_path: Option<String>,
+    serialized_file: OnceCell<SerializedFile>,
+    /// Access tick of the viewer when the file was last got, see unload_serialized_files.
+    last_access: Cell<u64>,
+}
+
+#[derive(Default)]
+struct ContainerIndex {
+    container_maps: HashMap<String, Vec<(i64, TypeTreeObjectRef)>>,
+    container_name_maps: HashMap<i64, HashMap<i64, String>>,
+}
+
+impl ContainerIndex {
+    fn add_serialized_file(&mut self, serialized_file: &SerializedFile) {
+        self.add_containers(
+            serialized_file.get_serialized_file_id(),
+            read_containers(serialized_file),
+        );
+    }
+
+    fn remove_serialized_file(&mut self, serialized_file_id: i64) {
+        self.container_maps.retain(|_, objs| {
+            objs.retain(|(id, _)| *id != serialized_file_id);
+            !objs.is_empty()
+        });
+        self.container_name_maps.remove(&serialized_file_id);
+    }
+
+    fn add_containers(
+        &mut self,
+        serialized_file_id: i64,
+        containers: Vec<(String, TypeTreeObjectRef)>,
+    ) {
+        if containers.is_empty() {
+            return;
+        }
+        let mut name_map = HashMap::new();
+        for (name, pptr) in containers {
+            if let Ok(path_id) = PPtr::new(&pptr).get_path_id() {
+                name_map.insert(path_id, name.clone());
+            }
+            self.container_maps
+                .entry(name)
+                .or_default()
+                .push((serialized_file_id, pptr));
+        }
+        self.container_name_maps
+            .insert(serialized_file_id, name_map);
+    }
+}
+
+/// m_Container entries of the AssetBundle or ResourceManager of a serialized file.
+fn read_containers(serialized_file: &SerializedFile) -> Vec<(String, TypeTreeObjectRef)> {
+    let mut containers = Vec::new();
+    if let Ok(Some(asset_bundle)) = serialized_file.get_tt_object_by_path_id(1) {
+        // a container name repeat for each sub asset, like sprites of a texture.
+        if let Ok(asset_bundle_containers) = <Vec<(String, TypeTreeObjectRef)>>::try_cast_from(
+            &asset_bundle.into(),
+            "/Base/m_Container/Array",
+        ) {
+            for (name, asset_info) in asset_bundle_containers {
+                if let Ok(pptr) = TypeTreeObjectRef::try_cast_from(&asset_info, "/Base/asset") {
+                    containers.push((name, pptr));
+                }
+            }
+        }
+    }
+
+    for (path_id, obj) in serialized_file.get_object_map() {
+        if obj.class == ClassIDType::ResourceManager as i32 {
+            if let Ok(Some(resource_manager)) = serialized_file.get_tt_object_by_path_id(*path_id) {
+                if let Ok(resource_containers) = <Vec<(String, TypeTreeObjectRef)>>::try_cast_from(
+                    &resource_manager.into(),
+                    "/Base/m_Container/Array",
+                ) {
+                    containers.extend(resource_containers);
+                }
+            }
+        }
+    }
+    containers
+}
+
+/// m_Container entries saved in a viewer index.
+fn get_indexed_containers(
+    serialized_file_id: i64,
+    indexed_serialized_file: &IndexedSerializedFile,
+) -> Vec<(String, TypeTreeObjectRef)> {
+    indexed_serialized_file
+        .containers
+        .iter()
+        .map(|container| {
+            (
+                container.name.clone(),
+                TypeTreeObject::new_pptr(serialized_file_id, container.file_id, container.path_id)
+                    .into(),
+            )
+        })
+        .collect()
+}
+
+/// One object of a container, a container name can have many objects.
+pub struct ContainerEntry<'a> {
+    pub container_name: &'a String,
+    /// File which hold the container, the object may be in an external file.
+    pub serialized_file_id: i64,
+    pub pptr: &'a TypeTreeObjectRef,
+    viewer: &'a UnityAssetViewer,
+}
+
+impl<'a> ContainerEntry<'a> {
+    /// File which hold the object.
+    pub fn get_object_serialized_file(&self) -> ReadResult<&'a SerializedFile> {
+        let serialized_file = self
+            .viewer
+            .get_serialized_file_by_id(self.serialized_file_id)?;
+        PPtr::new(self.pptr).get_serialized_file(serialized_file, Some(self.viewer))
+    }
+
+    pub fn get_path_id(&self) -> ReadResult<i64> {
+        PPtr::new(self.pptr).get_path_id()
+    }
+
+    /// Id of the file which hold the object, resolved by the index when the container file is indexed.
+    pub fn get_object_serialized_file_id(&self) -> ReadResult<i64> {
+        if let Some(indexed_serialized_file) = self
+            .viewer
+            .indexed_serialized_files
+            .get(&self.serialized_file_id)
+        {
+            let file_id = PPtr::new(self.pptr).get_file_id()?;
+            if file_id == 0 {
+                return Ok(self.serialized_file_id);
+            }
+            return usize::try_from(file_id)
+                .ok()
+                .and_then(|file_id| indexed_serialized_file.externals.get(file_id - 1))
+                .and_then(|file_name| self.viewer.cab_maps.get(file_name))
+                .copied()
+                .ok_or(Error::ExternalSerializedFileNotFound);
+        }
+        Ok(self.get_object_serialized_file()?.get_serialized_file_id())
+    }
+
+    /// Class of the object, read from the index or the object table without parsing the object.
+    pub fn get_class_id(&self) -> Option<i32> {
+        let serialized_file_id = self.get_object_serialized_file_id().ok()?;
+        let path_id = self.get_path_id().ok()?;
+        self.viewer.get_object_class_id(serialized_file_id, path_id)
+    }
+
+    /// m_Name of the object, the object is parsed.
+    pub fn get_name(&self) -> Option<String> {
+        let object = self.get_type_tree_object().ok()??;
+        String::try_cast_from(&object, "/Base/m_Name").ok()
+    }
+
+    /// Bundle which hold the container.
+    pub fn get_unity_fs_id(&self) -> Option<i64> {
+        self.viewer
+            .serialized_file_to_unity_fs_map
+            .get(&self.serialized_file_id)
+            .copied()
+    }
+
+    pub fn get_unity_fs(&self) -> Option<&'a UnityFS> {
+        self.viewer.get_unity_fs_by_id(self.get_unity_fs_id()?)
+    }
+
+    pub fn get_type_tree_object(&self) -> ReadResult<Option<TypeTreeObject>> {
+        let serialized_file = self
+            .viewer
+            .get_serialized_file_by_id(self.serialized_file_id)?;
+        PPtr::new(self.pptr).get_type_tree_object(serialized_file, Some(self.viewer))
+    }
+}
+
+/// Container glob, `*` and `?` do not match '/' while `**` match any path.
+fn glob_to_regex(pattern: &str) -> Regex {
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                regex.push_str(".*");
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).unwrap()
+}
+
+/// Sort key which keep levelN and sharedassetsN.assets in index order.
+fn get_data_file_order(file_name: &str) -> (u8, u64, String) {
+    static DATA_FILE_NAME_REGEX: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"^(?:level(\d+)|sharedassets(\d+)\.assets)$").unwrap());
+
+    let captures = DATA_FILE_NAME_REGEX.captures(file_name);
+    let (group, index) = match captures.as_ref().map(|c| (c.get(1), c.get(2))) {
+        Some((Some(index), _)) => (0, index.as_str().parse().unwrap_or(u64::MAX)),
+        Some((_, Some(index))) => (1, index.as_str().parse().unwrap_or(u64::MAX)),
+        _ => (2, 0),
+    };
+    (group, index, file_name.to_owned())
+}
+
+/// Dir of a file in its asset source and dir of its display path,
+/// stream data of the file is searched in the same dir of the source.
+fn get_source_dirs(path: &str, loaded_file: &LoadedFile) -> (String, Option<String>) {
+    let dir = path
+        .rsplit_once('/')
+        .map(|(dir, _)| dir.to_owned())
+        .unwrap_or_default();
+    let resource_search_path = loaded_file
+        .path
+        .parent()
+        .map(|parent| parent.to_string_lossy().to_string());
+    (dir, resource_search_path)
+}
 
 #[derive(Default)]
 pub struct UnityAssetViewer {
     pub cab_maps: HashMap<String, i64>,
-    pub serialized_file_map: BTreeMap<i64, SerializedFile>,
+    serialized_file_map: BTreeMap<i64, SerializedFileEntry>,
     serialized_file_count: i64,
-    unity_fs_map: BTreeMap<i64, UnityFS>,
+    unity_fs_map: BTreeMap<i64, UnityFSEntry>,
     unity_fs_count: i64,
     serialized_file_to_unity_fs_map: BTreeMap<i64, i64>,
-    pub container_maps: HashMap<String, Vec<(i64, TypeTreeObjectRef)>>,
-    container_name_maps: HashMap<i64, HashMap<i64, String>>,
+    resource_node_map: HashMap<String, (i64, String)>,
+    asset_source_map: BTreeMap<i64, Arc<dyn AssetSource>>,
+    asset_source_count: i64,
+    /// Source and dir files were read from, stream data is searched there.
+    serialized_file_to_asset_source_map: BTreeMap<i64, (i64, String)>,
+    unity_fs_to_asset_source_map: BTreeMap<i64, (i64, String)>,
+    asset_bundle_manifest: Option<AssetBundleManifestEntry>,
+    /// Bundles added by load_asset_bundle.
+    asset_bundle_map: HashMap<String, i64>,
+    container_index: OnceCell<ContainerIndex>,
+    /// Files of a loaded index by display path.
+    indexed_files: HashMap<PathBuf, IndexedFile>,
+    /// Serialized files restored from an index, their object tables and containers are known.
+    indexed_serialized_files: BTreeMap<i64, IndexedSerializedFile>,
+    /// Files read from asset sources by display path.
+    file_records: BTreeMap<PathBuf, FileRecord>,
+    file_pool: Option<Arc<Mutex<FilePool>>>,
+    strict: bool,
+    access_tick: Cell<u64>,
 }
 
 impl UnityAssetViewer {
@@ -37,20 +339,51 @@ impl UnityAssetViewer {
         Self::default()
     }
 
-    pub fn read_bundle_dir<P: AsRef<Path>>(&mut self, dir_path: P) -> ReadResult<()> {
-        for entry in WalkDir::new(dir_path).into_iter().flatten() {
-            if entry.file_type().is_file() {
-                let file = OpenOptions::new().read(true).open(entry.path())?;
-                let file = Box::new(BufReader::new(file));
-                let _unity_fs_id = self
-                    .add_bundle_file(
-                        file,
-                        Some(entry.path().parent().unwrap().to_string_lossy().to_string()),
-                    )
-                    .unwrap_or_default();
-            }
+    /// Viewer which only record where bundles and serialized files are,
+    /// serialized files are parsed on first access and
+    /// at most max_open_files files opened by the dir readers are kept open.
+    /// Parsed files stay in memory until unload_serialized_files drop them.
+    pub fn new_lazy(max_open_files: usize) -> Self {
+        UnityAssetViewer {
+            file_pool: Some(FilePool::new(max_open_files)),
+            ..Self::default()
         }
-        Ok(())
+    }
+
+    pub fn is_lazy(&self) -> bool {
+        self.file_pool.is_some()
+    }
+
+    fn open_file(&self, path: &Path) -> ReadResult<Box<dyn UnityResource + Send + Sync>> {
+        Ok(if let Some(file_pool) = &self.file_pool {
+            Box::new(BufReader::new(PooledFile::open(file_pool, path)?))
+        } else {
+            Box::new(BufReader::new(OpenOptions::new().read(true).open(path)?))
+        })
+    }
+
+    /// Return the first error of the dir readers instead of recording it in the load report.
+    /// The failing file add nothing to the viewer, files read before it stay added.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    fn get_serialized_file_ids_by_unity_fs_id(&self, unity_fs_id: i64) -> Vec<i64> {
+        self.serialized_file_to_unity_fs_map
+            .iter()
+            .filter(|(_, id)| **id == unity_fs_id)
+            .map(|(serialized_file_id, _)| *serialized_file_id)
+            .collect()
+    }
+
+    /// Add every bundle under dir_path, other files are only listed in the report.
+    pub fn read_bundle_dir<P: AsRef<Path>>(&mut self, dir_path: P) -> ReadResult<LoadReport> {
+        let asset_source = self.new_directory_source(dir_path);
+        self.add_asset_source(Arc::new(asset_source), "", false)
     }
 
     pub fn add_bundle_file(
@@ -58,25 +391,127 @@ impl UnityAssetViewer {
         bundle_file_reader: Box<dyn UnityResource + Send + Sync>,
         resource_search_path: Option<String>,
     ) -> ReadResult<i64> {
+        self.add_unity_fs(bundle_file_reader, resource_search_path)
+            .map(|added| added.unity_fs_id)
+    }
+
+    fn add_unity_fs(
+        &mut self,
+        bundle_file_reader: Box<dyn UnityResource + Send + Sync>,
+        resource_search_path: Option<String>,
+    ) -> ReadResult<AddedUnityFS> {
         let unity_fs = UnityFS::read(bundle_file_reader, resource_search_path)?;
+        // every serialized file is read before any is added, so a bad one add nothing of the bundle.
+        let serialized_file_paths = unity_fs.get_serialized_file_paths();
+        let mut serialized_files = Vec::new();
+        if !self.is_lazy() {
+            for path in &serialized_file_paths {
+                let serialized_file_id = self.serialized_file_count;
+                self.serialized_file_count += 1;
+                let node_data = unity_fs.get_file_data_by_path(path)?;
+                let serialized_file = SerializedFile::read(
+                    Box::new(Cursor::new(node_data)),
+                    serialized_file_id,
+                    None,
+                )?;
+                serialized_files.push((path.clone(), serialized_file));
+            }
+        }
+
         let unity_fs_id = self.unity_fs_count;
         self.unity_fs_count += 1;
-        for cab_path in unity_fs.get_cab_path() {
-            let cab_buff = unity_fs.get_file_data_by_path(&cab_path)?;
-            let cab_buff_reader = Box::new(Cursor::new(cab_buff));
-            // let cab_buff_reader = Box::new(BufReader::new(
-            //     unity_fs
-            //         .get_file_reader_by_path(&cab_path)
-            //         .ok_or(Error::Other("can not get cab reader".to_owned()))?,
-            // ));
-
-            let serialized_file_id = self.add_serialized_file(cab_buff_reader, None)?;
-            self.serialized_file_to_unity_fs_map
-                .insert(serialized_file_id, unity_fs_id);
-            self.cab_maps.insert(cab_path, serialized_file_id);
+        let mut node_serialized_files = Vec::new();
+        if self.is_lazy() {
+            for path in serialized_file_paths {
+                let serialized_file_id = self.add_serialized_file_entry(
+                    SerializedFileSource::UnityFS {
+                        unity_fs_id,
+                        path: path.clone(),
+                    },
+                    None,
+                );
+                self.add_unity_fs_serialized_file(unity_fs_id, path.clone(), serialized_file_id);
+                node_serialized_files.push((serialized_file_id, path));
+            }
+        }
+        for (path, serialized_file) in serialized_files {
+            let serialized_file_id = serialized_file.get_serialized_file_id();
+            self.insert_serialized_file(serialized_file, None);
+            self.add_unity_fs_serialized_file(unity_fs_id, path.clone(), serialized_file_id);
+            node_serialized_files.push((serialized_file_id, path));
+        }
+        let duplicate_nodes = self.add_unity_fs_nodes(unity_fs_id, &unity_fs.get_file_paths());
+        self.unity_fs_map.insert(
+            unity_fs_id,
+            UnityFSEntry {
+                source: None,
+                resource_search_path: None,
+                unity_fs: OnceCell::with_value(unity_fs),
+            },
+        );
+        if self.is_lazy() {
+            for (serialized_file_id, _) in &node_serialized_files {
+                self.add_to_container_index(*serialized_file_id);
+            }
+        }
+        Ok(AddedUnityFS {
+            unity_fs_id,
+            serialized_files: node_serialized_files,
+            duplicate_nodes,
+        })
+    }
+
+    fn add_unity_fs_serialized_file(
+        &mut self,
+        unity_fs_id: i64,
+        cab_path: String,
+        serialized_file_id: i64,
+    ) {
+        self.serialized_file_to_unity_fs_map
+            .insert(serialized_file_id, unity_fs_id);
+        self.cab_maps.insert(cab_path, serialized_file_id);
+    }
+
+    /// Nodes whose archive path is taken by a bundle added before are not used,
+    /// their archive paths are returned.
+    fn add_unity_fs_nodes(&mut self, unity_fs_id: i64, node_paths: &[String]) -> Vec<String> {
+        let mut duplicate_nodes = Vec::new();
+        // unity mount bundle nodes at archive:/<cab name>/<node path>.
+        for node_path in node_paths {
+            let archive_name = node_path.split('.').next().unwrap_or(node_path);
+            let archive_path = format!("archive:/{archive_name}/{node_path}");
+            match self.resource_node_map.entry(archive_path) {
+                Entry::Occupied(entry) => duplicate_nodes.push(entry.key().clone()),
+                Entry::Vacant(entry) => {
+                    entry.insert((unity_fs_id, node_path.clone()));
+                }
+            }
         }
-        self.unity_fs_map.insert(unity_fs_id, unity_fs);
-        Ok(unity_fs_id)
+        duplicate_nodes
+    }
+
+    /// Get bundle, a bundle restored from an index is read first.
+    pub fn get_unity_fs_by_id(&self, unity_fs_id: i64) -> Option<&UnityFS> {
+        let entry = self.unity_fs_map.get(&unity_fs_id)?;
+        entry
+            .unity_fs
+            .get_or_try_init(|| self.load_unity_fs(entry))
+            .ok()
+    }
+
+    fn load_unity_fs(&self, entry: &UnityFSEntry) -> ReadResult<UnityFS> {
+        let (asset_source_id, path) = entry
+            .source
+            .as_ref()
+            .ok_or_else(|| Error::Other("bundle has no source".to_owned()))?;
+        let asset_source = self
+            .asset_source_map
+            .get(asset_source_id)
+            .ok_or_else(|| Error::ResourceNotFound(path.clone()))?;
+        Ok(UnityFS::read(
+            asset_source.open(path)?,
+            entry.resource_search_path.clone(),
+        )?)
     }
 
     pub fn add_serialized_file(
@@ -90,134 +525,991 @@ impl UnityAssetViewer {
         let serialized_file = SerializedFile::read(
             serialized_file_reader,
             serialized_file_id,
-            resource_search_path,
+            resource_search_path.clone(),
         )?;
-        if let Ok(Some(asset_bundle)) = serialized_file.get_tt_object_by_path_id(1) {
-            if let Ok(containers) = <HashMap<String, TypeTreeObjectRef>>::try_cast_from(
-                &asset_bundle.into(),
-                "/Base/m_Container/Array",
+        self.insert_serialized_file(serialized_file, resource_search_path);
+        Ok(serialized_file_id)
+    }
+
+    fn insert_serialized_file(
+        &mut self,
+        serialized_file: SerializedFile,
+        resource_search_path: Option<String>,
+    ) {
+        if let Some(container_index) = self.container_index.get_mut() {
+            container_index.add_serialized_file(&serialized_file);
+        }
+        self.serialized_file_map.insert(
+            serialized_file.get_serialized_file_id(),
+            SerializedFileEntry {
+                source: None,
+                resource_search_path,
+                serialized_file: OnceCell::with_value(serialized_file),
+                last_access: Cell::new(0),
+            },
+        );
+    }
+
+    /// Remove a serialized file with its containers, PPtr to it from other files become unresolved.
+    /// Ids of removed files are not given again.
+    pub fn remove_serialized_file(&mut self, serialized_file_id: i64) -> ReadResult<()> {
+        self.serialized_file_map
+            .remove(&serialized_file_id)
+            .ok_or(Error::SerializedFileNotFound)?;
+        self.cab_maps.retain(|_, id| *id != serialized_file_id);
+        self.serialized_file_to_unity_fs_map
+            .remove(&serialized_file_id);
+        self.serialized_file_to_asset_source_map
+            .remove(&serialized_file_id);
+        self.indexed_serialized_files.remove(&serialized_file_id);
+        if let Some(container_index) = self.container_index.get_mut() {
+            container_index.remove_serialized_file(serialized_file_id);
+        }
+        // the file it was read from is not complete anymore.
+        self.file_records.retain(|_, record| {
+            !record
+                .serialized_files
+                .iter()
+                .any(|(id, _)| *id == serialized_file_id)
+        });
+        Ok(())
+    }
+
+    /// Remove a bundle with its serialized files and nodes.
+    pub fn remove_bundle(&mut self, unity_fs_id: i64) -> ReadResult<()> {
+        if !self.unity_fs_map.contains_key(&unity_fs_id) {
+            return Err(Error::UnityFSNotFound);
+        }
+        for serialized_file_id in self.get_serialized_file_ids_by_unity_fs_id(unity_fs_id) {
+            self.remove_serialized_file(serialized_file_id)?;
+        }
+        self.unity_fs_map.remove(&unity_fs_id);
+        self.unity_fs_to_asset_source_map.remove(&unity_fs_id);
+        self.resource_node_map
+            .retain(|_, (id, _)| *id != unity_fs_id);
+        self.asset_bundle_map.retain(|_, id| *id != unity_fs_id);
+        self.file_records
+            .retain(|_, record| record.unity_fs_id != Some(unity_fs_id));
+        Ok(())
+    }
+
+    fn remove_file_record(&mut self, record: &FileRecord) -> ReadResult<()> {
+        if let Some(unity_fs_id) = record.unity_fs_id {
+            if self.unity_fs_map.contains_key(&unity_fs_id) {
+                self.remove_bundle(unity_fs_id)?;
+            }
+        }
+        for (serialized_file_id, _) in &record.serialized_files {
+            if self.serialized_file_map.contains_key(serialized_file_id) {
+                self.remove_serialized_file(*serialized_file_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a file added from an asset source again, like a bundle replaced by a build.
+    /// Its bundle and serialized files are removed first and the new ones get new ids.
+    /// path is the path of the file in load reports.
+    pub fn reload_file<P: AsRef<Path>>(&mut self, path: P) -> ReadResult<LoadReport> {
+        let display_path = path.as_ref().to_path_buf();
+        let record = self
+            .file_records
+            .remove(&display_path)
+            .ok_or_else(|| Error::ResourceNotFound(display_path.to_string_lossy().to_string()))?;
+        self.remove_file_record(&record)?;
+        let asset_source = self
+            .asset_source_map
+            .get(&record.asset_source_id)
+            .cloned()
+            .ok_or_else(|| Error::ResourceNotFound(record.path.clone()))?;
+
+        let mut loaded_file = LoadedFile::new(display_path);
+        loaded_file.error = self
+            .add_asset_source_file(
+                record.asset_source_id,
+                asset_source.as_ref(),
+                &record.path,
+                record.load_serialized_file,
+                &mut loaded_file,
+            )
+            .err();
+        let file_name = record.path.rsplit('/').next().unwrap_or(&record.path);
+        self.add_serialized_file_name(file_name.to_owned(), &loaded_file);
+        let mut report = LoadReport::default();
+        report.push(loaded_file, self.strict)?;
+        Ok(report)
+    }
+
+    /// Reload files whose size or modified time changed since they were read
+    /// and remove files which do not exist anymore.
+    /// Only files of sources with modified times which were read without error are checked.
+    pub fn reload_changed_files(&mut self) -> ReadResult<LoadReport> {
+        let mut changed_files = Vec::new();
+        let mut removed_files = Vec::new();
+        for (display_path, record) in &self.file_records {
+            let (Some(indexed_file), Some(asset_source)) = (
+                &record.indexed_file,
+                self.asset_source_map.get(&record.asset_source_id),
+            ) else {
+                continue;
+            };
+            if !asset_source.exists(&record.path) {
+                removed_files.push(display_path.clone());
+                continue;
+            }
+            let is_up_to_date = match (
+                asset_source.get_file_size(&record.path),
+                asset_source.get_modified_time(&record.path),
             ) {
-                let mut name_map = HashMap::new();
-                for (name, asset_info) in containers {
-                    if let Ok(pptr) = TypeTreeObjectRef::try_cast_from(&asset_info, "/Base/asset") {
-                        if let Ok(path_id) = PPtr::new(&pptr).get_path_id() {
-                            name_map.insert(path_id, name.clone());
-                        }
+                (Ok(size), Some(modified)) => indexed_file.is_up_to_date(size, modified),
+                _ => false,
+            };
+            if !is_up_to_date {
+                changed_files.push(display_path.clone());
+            }
+        }
 
-                        if let Some(objs) = self.container_maps.get_mut(&name) {
-                            objs.push((serialized_file_id, pptr));
-                        } else {
-                            self.container_maps
-                                .insert(name, vec![(serialized_file_id, pptr)]);
-                        }
-                    }
-                }
-                self.container_name_maps
-                    .insert(serialized_file_id, name_map);
-            }
-        }
-
-        for (path_id, obj) in serialized_file.get_object_map() {
-            if obj.class == ClassIDType::ResourceManager as i32 {
-                if let Ok(Some(resource_manager)) =
-                    serialized_file.get_tt_object_by_path_id(*path_id)
-                {
-                    if let Ok(containers) = <HashMap<String, TypeTreeObjectRef>>::try_cast_from(
-                        &resource_manager.into(),
-                        "/Base/m_Container/Array",
-                    ) {
-                        let mut name_map = HashMap::new();
-                        for (name, pptr) in containers {
-                            if let Ok(path_id) = PPtr::new(&pptr).get_path_id() {
-                                name_map.insert(path_id, name.clone());
-                            }
-
-                            if let Some(objs) = self.container_maps.get_mut(&name) {
-                                objs.push((serialized_file_id, pptr));
-                            } else {
-                                self.container_maps
-                                    .insert(name, vec![(serialized_file_id, pptr)]);
-                            }
-                        }
-                        self.container_name_maps
-                            .insert(serialized_file_id, name_map);
-                    }
-                }
+        for display_path in removed_files {
+            if let Some(record) = self.file_records.remove(&display_path) {
+                self.remove_file_record(&record)?;
             }
         }
+        let mut report = LoadReport::default();
+        for display_path in changed_files {
+            report.extend(self.reload_file(display_path)?);
+        }
+        Ok(report)
+    }
 
-        self.serialized_file_map
-            .insert(serialized_file_id, serialized_file);
-        Ok(serialized_file_id)
+    fn add_serialized_file_entry(
+        &mut self,
+        source: SerializedFileSource,
+        resource_search_path: Option<String>,
+    ) -> i64 {
+        let serialized_file_id = self.serialized_file_count;
+        self.serialized_file_count += 1;
+        self.serialized_file_map.insert(
+            serialized_file_id,
+            SerializedFileEntry {
+                source: Some(source),
+                resource_search_path,
+                serialized_file: OnceCell::new(),
+                last_access: Cell::new(0),
+            },
+        );
+        serialized_file_id
     }
 
-    pub fn read_data_dir<P: AsRef<Path>>(&mut self, data_dir_path: P) -> ReadResult<()> {
-        for i in 0..u8::MAX {
-            let file_name = format!("level{i}");
-            if let Ok(file) = OpenOptions::new()
-                .read(true)
-                .open(data_dir_path.as_ref().join(&file_name))
-            {
-                let serialized_file_id = self.add_serialized_file(
-                    Box::new(BufReader::new(file)),
-                    Some(data_dir_path.as_ref().to_string_lossy().to_string()),
-                )?;
-                self.cab_maps.insert(file_name, serialized_file_id);
-            } else {
-                break;
+    fn load_serialized_file(
+        &self,
+        serialized_file_id: i64,
+        entry: &SerializedFileEntry,
+    ) -> ReadResult<SerializedFile> {
+        let reader: Box<dyn UnityResource + Send + Sync> = match &entry.source {
+            Some(SerializedFileSource::UnityFS { unity_fs_id, path }) => Box::new(BufReader::new(
+                self.get_unity_fs_by_id(*unity_fs_id)
+                    .and_then(|unity_fs| unity_fs.get_file_reader_by_path(path))
+                    .ok_or(Error::SerializedFileNotFound)?,
+            )),
+            Some(SerializedFileSource::AssetSource {
+                asset_source_id,
+                path,
+            }) => self
+                .asset_source_map
+                .get(asset_source_id)
+                .ok_or(Error::SerializedFileNotFound)?
+                .open(path)?,
+            None => return Err(Error::SerializedFileNotFound),
+        };
+        Ok(SerializedFile::read(
+            reader,
+            serialized_file_id,
+            entry.resource_search_path.clone(),
+        )?)
+    }
+
+    /// Get serialized file, parse it first if it is not loaded yet.
+    pub fn get_serialized_file_by_id(
+        &self,
+        serialized_file_id: i64,
+    ) -> ReadResult<&SerializedFile> {
+        let entry = self
+            .serialized_file_map
+            .get(&serialized_file_id)
+            .ok_or(Error::SerializedFileNotFound)?;
+        self.access_tick.set(self.access_tick.get() + 1);
+        entry.last_access.set(self.access_tick.get());
+        entry
+            .serialized_file
+            .get_or_try_init(|| self.load_serialized_file(serialized_file_id, entry))
+    }
+
+    /// Drop parsed serialized files except the keep most recently got ones and return how many
+    /// were dropped, they are parsed again on next access. Only files added lazily can be read
+    /// again, files given to add_serialized_file and files of eager viewers are kept.
+    pub fn unload_serialized_files(&mut self, keep: usize) -> usize {
+        let mut loaded_files: Vec<(u64, i64)> = self
+            .serialized_file_map
+            .iter()
+            .filter(|(_, entry)| entry.source.is_some() && entry.serialized_file.get().is_some())
+            .map(|(serialized_file_id, entry)| (entry.last_access.get(), *serialized_file_id))
+            .collect();
+        loaded_files.sort_unstable();
+        let unload_count = loaded_files.len().saturating_sub(keep);
+        for (_, serialized_file_id) in &loaded_files[..unload_count] {
+            if let Some(entry) = self.serialized_file_map.get_mut(serialized_file_id) {
+                entry.serialized_file.take();
             }
         }
-        for i in 0..u8::MAX {
-            let file_name = format!("sharedassets{i}.assets");
-            if let Ok(file) = OpenOptions::new()
-                .read(true)
-                .open(data_dir_path.as_ref().join(&file_name))
-            {
-                let serialized_file_id = self.add_serialized_file(
-                    Box::new(BufReader::new(file)),
-                    Some(data_dir_path.as_ref().to_string_lossy().to_string()),
-                )?;
-                self.cab_maps.insert(file_name, serialized_file_id);
-            } else {
-                break;
+        unload_count
+    }
+
+    pub fn get_serialized_file_ids(&self) -> Vec<i64> {
+        self.serialized_file_map.keys().copied().collect()
+    }
+
+    /// Iterate all serialized files, lazily added files are parsed while iterating.
+    pub fn iter_serialized_files(
+        &self,
+    ) -> impl Iterator<Item = (i64, ReadResult<&SerializedFile>)> + '_ {
+        self.serialized_file_map.keys().map(|serialized_file_id| {
+            (
+                *serialized_file_id,
+                self.get_serialized_file_by_id(*serialized_file_id),
+            )
+        })
+    }
+
+    /// Containers of a file from the index, the parsed file or a file parsed only for them,
+    /// so building the container index do not keep every file of a lazy viewer parsed.
+    fn read_serialized_file_containers(
+        &self,
+        serialized_file_id: i64,
+    ) -> Vec<(String, TypeTreeObjectRef)> {
+        if let Some(indexed_serialized_file) =
+            self.indexed_serialized_files.get(&serialized_file_id)
+        {
+            return get_indexed_containers(serialized_file_id, indexed_serialized_file);
+        }
+        let Some(entry) = self.serialized_file_map.get(&serialized_file_id) else {
+            return Vec::new();
+        };
+        if let Some(serialized_file) = entry.serialized_file.get() {
+            return read_containers(serialized_file);
+        }
+        self.load_serialized_file(serialized_file_id, entry)
+            .map(|serialized_file| read_containers(&serialized_file))
+            .unwrap_or_default()
+    }
+
+    fn get_container_index(&self) -> &ContainerIndex {
+        self.container_index.get_or_init(|| {
+            let mut container_index = ContainerIndex::default();
+            for serialized_file_id in self.serialized_file_map.keys() {
+                container_index.add_containers(
+                    *serialized_file_id,
+                    self.read_serialized_file_containers(*serialized_file_id),
+                );
             }
+            container_index
+        })
+    }
+
+    /// Keep the container index of a lazy viewer when a file is added after it was built.
+    fn add_to_container_index(&mut self, serialized_file_id: i64) {
+        if self.container_index.get().is_none() {
+            return;
         }
+        let containers = self.read_serialized_file_containers(serialized_file_id);
+        if let Some(container_index) = self.container_index.get_mut() {
+            container_index.add_containers(serialized_file_id, containers);
+        }
+    }
 
-        let file_names = [
-            "resources.assets",
-            "globalgamemanagers.assets",
-            "globalgamemanagers",
-        ];
-        for file_name in file_names {
-            if let Ok(file) = OpenOptions::new()
-                .read(true)
-                .open(data_dir_path.as_ref().join(file_name))
-            {
-                let serialized_file_id = self.add_serialized_file(
-                    Box::new(BufReader::new(file)),
-                    Some(data_dir_path.as_ref().to_string_lossy().to_string()),
-                )?;
+    /// Container path to objects. The first call read the containers of every serialized file
+    /// not restored from an index, a lazy viewer parse these files once without keeping them.
+    pub fn get_container_maps(&self) -> &HashMap<String, Vec<(i64, TypeTreeObjectRef)>> {
+        &self.get_container_index().container_maps
+    }
+
+    /// The former public field `container_maps`.
+    #[deprecated(note = "use get_container_maps or iter_containers")]
+    pub fn container_maps(&self) -> &HashMap<String, Vec<(i64, TypeTreeObjectRef)>> {
+        self.get_container_maps()
+    }
+
+    /// The former public field `serialized_file_map`, every serialized file is parsed
+    /// and files which can not be parsed are left out.
+    #[deprecated(note = "use get_serialized_file_by_id or iter_serialized_files")]
+    pub fn serialized_file_map(&self) -> BTreeMap<i64, &SerializedFile> {
+        self.iter_serialized_files()
+            .filter_map(|(serialized_file_id, serialized_file)| {
+                Some((serialized_file_id, serialized_file.ok()?))
+            })
+            .collect()
+    }
+
+    fn new_directory_source<P: AsRef<Path>>(&self, dir_path: P) -> DirectorySource {
+        match &self.file_pool {
+            Some(file_pool) => DirectorySource::new_pooled(dir_path, file_pool.clone()),
+            None => DirectorySource::new(dir_path),
+        }
+    }
+
+    /// Add every bundle and serialized file under a player Data folder, files are detected by header.
+    /// Serialized files are registered by file name, which is how externals of their siblings refer to them.
+    pub fn read_data_dir<P: AsRef<Path>>(&mut self, data_dir_path: P) -> ReadResult<LoadReport> {
+        let asset_source = self.new_directory_source(data_dir_path);
+        self.add_asset_source(Arc::new(asset_source), "", true)
+    }
+
+    /// Add the Unity files of an apk, obb or other zip package, like read_data_dir does for
+    /// assets/bin/Data plus every bundle under assets/. Paths in the report are prefixed with package_path.
+    pub fn read_zip_package<P: AsRef<Path>>(&mut self, package_path: P) -> ReadResult<LoadReport> {
+        let file = self.open_file(package_path.as_ref())?;
+        let mut report = self.add_zip_package(file)?;
+        for loaded_file in &mut report.files {
+            loaded_file.path = package_path.as_ref().join(&loaded_file.path);
+        }
+        Ok(report)
+    }
+
+    pub fn add_zip_package(
+        &mut self,
+        zip_package_reader: Box<dyn UnityResource + Send + Sync>,
+    ) -> ReadResult<LoadReport> {
+        let zip_package = ZipPackage::read(zip_package_reader)?;
+        self.add_asset_source(Arc::new(zip_package), "assets", true)
+    }
+
+    /// Add files of the asset source under dir, files are detected by header.
+    /// Bundles are always added, serialized files only when load_serialized_file is set.
+    pub fn add_asset_source(
+        &mut self,
+        asset_source: Arc<dyn AssetSource>,
+        dir: &str,
+        load_serialized_file: bool,
+    ) -> ReadResult<LoadReport> {
+        let dir = asset_source.join(dir, "");
+        let mut paths: Vec<_> = asset_source
+            .list()?
+            .into_iter()
+            .filter(|path| dir.is_empty() || path.starts_with(&format!("{dir}/")))
+            .map(|path| {
+                let file_name = path.rsplit('/').next().unwrap_or(&path).to_owned();
+                // files nearer the root win when names collide.
+                (
+                    path.matches('/').count(),
+                    get_data_file_order(&file_name),
+                    path,
+                )
+            })
+            .collect();
+        paths.sort();
+
+        let asset_source_id = self.asset_source_count;
+        self.asset_source_count += 1;
+        self.asset_source_map
+            .insert(asset_source_id, asset_source.clone());
+
+        let mut report = LoadReport::default();
+        for (_, (_, _, file_name), path) in paths {
+            let mut loaded_file = LoadedFile::new(asset_source.get_display_path(&path));
+            loaded_file.error = self
+                .add_asset_source_file(
+                    asset_source_id,
+                    asset_source.as_ref(),
+                    &path,
+                    load_serialized_file,
+                    &mut loaded_file,
+                )
+                .err();
+            self.add_serialized_file_name(file_name, &loaded_file);
+            report.push(loaded_file, self.strict)?;
+        }
+        Ok(report)
+    }
+
+    /// Serialized files read directly are registered by file name,
+    /// which is how externals of their siblings refer to them.
+    fn add_serialized_file_name(&mut self, file_name: String, loaded_file: &LoadedFile) {
+        if loaded_file.kind == FileKind::SerializedFile {
+            if let Some(serialized_file_id) = loaded_file.serialized_file_ids.first() {
                 self.cab_maps
-                    .insert(file_name.to_owned(), serialized_file_id);
+                    .entry(file_name)
+                    .or_insert(*serialized_file_id);
+            }
+        }
+    }
+
+    fn add_asset_source_file(
+        &mut self,
+        asset_source_id: i64,
+        asset_source: &dyn AssetSource,
+        path: &str,
+        load_serialized_file: bool,
+        loaded_file: &mut LoadedFile,
+    ) -> ReadResult<()> {
+        let mut record = FileRecord {
+            asset_source_id,
+            path: path.to_owned(),
+            load_serialized_file,
+            unity_fs_id: None,
+            serialized_files: Vec::new(),
+            indexed_file: None,
+        };
+        // a failed file add nothing, it is recorded so it can be reloaded once fixed
+        // unless the error is returned in strict mode.
+        let result = self.read_asset_source_file(asset_source, &mut record, loaded_file);
+        if result.is_ok() || !self.strict {
+            self.file_records.insert(loaded_file.path.clone(), record);
+        }
+        result
+    }
+
+    fn read_asset_source_file(
+        &mut self,
+        asset_source: &dyn AssetSource,
+        record: &mut FileRecord,
+        loaded_file: &mut LoadedFile,
+    ) -> ReadResult<()> {
+        let asset_source_id = record.asset_source_id;
+        let path = record.path.as_str();
+        let file_size = asset_source.get_file_size(path)?;
+        let modified = asset_source.get_modified_time(path);
+        if let (Some(modified), true) = (modified, self.is_lazy()) {
+            if let Some(indexed_file) = self
+                .indexed_files
+                .get(&loaded_file.path)
+                .filter(|indexed_file| indexed_file.is_up_to_date(file_size, modified))
+                .cloned()
+            {
+                self.add_indexed_file(record, indexed_file, loaded_file);
+                return Ok(());
             }
         }
+
+        let (dir, resource_search_path) = get_source_dirs(path, loaded_file);
+
+        let header = asset_source.read_header(path, FILE_HEADER_SIZE)?;
+        loaded_file.kind = detect_file_kind_by_header(&header, file_size, Some(Path::new(path)));
+        let mut node_paths = Vec::new();
+
+        match loaded_file.kind {
+            FileKind::UnityFS => {
+                let file = asset_source.open(path)?;
+                let AddedUnityFS {
+                    unity_fs_id,
+                    serialized_files,
+                    duplicate_nodes,
+                } = self.add_unity_fs(file, resource_search_path)?;
+                loaded_file.duplicate_nodes = duplicate_nodes;
+                self.unity_fs_to_asset_source_map
+                    .insert(unity_fs_id, (asset_source_id, dir));
+                loaded_file.unity_fs_id = Some(unity_fs_id);
+                loaded_file.serialized_file_ids =
+                    serialized_files.iter().map(|(id, _)| *id).collect();
+                record.unity_fs_id = Some(unity_fs_id);
+                record.serialized_files = serialized_files;
+                if let Some(unity_fs) = self.get_unity_fs_by_id(unity_fs_id) {
+                    node_paths = unity_fs.get_file_paths();
+                }
+            }
+            FileKind::SerializedFile if record.load_serialized_file => {
+                let serialized_file_id = if self.is_lazy() {
+                    let serialized_file_id = self.add_serialized_file_entry(
+                        SerializedFileSource::AssetSource {
+                            asset_source_id,
+                            path: path.to_owned(),
+                        },
+                        resource_search_path,
+                    );
+                    self.add_to_container_index(serialized_file_id);
+                    serialized_file_id
+                } else {
+                    let file = asset_source.open(path)?;
+                    self.add_serialized_file(file, resource_search_path)?
+                };
+                self.serialized_file_to_asset_source_map
+                    .insert(serialized_file_id, (asset_source_id, dir));
+                loaded_file.serialized_file_ids = vec![serialized_file_id];
+                record
+                    .serialized_files
+                    .push((serialized_file_id, String::new()));
+            }
+            _ => (),
+        }
+        record.indexed_file = modified.map(|modified| IndexedFile {
+            path: loaded_file.path.to_string_lossy().to_string(),
+            size: file_size,
+            modified,
+            kind: loaded_file.kind,
+            node_paths,
+            serialized_files: Vec::new(),
+        });
         Ok(())
     }
 
+    fn add_indexed_file(
+        &mut self,
+        record: &mut FileRecord,
+        indexed_file: IndexedFile,
+        loaded_file: &mut LoadedFile,
+    ) {
+        let asset_source_id = record.asset_source_id;
+        let (dir, resource_search_path) = get_source_dirs(&record.path, loaded_file);
+        loaded_file.kind = indexed_file.kind;
+        loaded_file.from_index = true;
+        match indexed_file.kind {
+            FileKind::UnityFS => {
+                let unity_fs_id = self.unity_fs_count;
+                self.unity_fs_count += 1;
+                for indexed_serialized_file in &indexed_file.serialized_files {
+                    let cab_path = indexed_serialized_file.path.clone();
+                    let serialized_file_id = self.add_serialized_file_entry(
+                        SerializedFileSource::UnityFS {
+                            unity_fs_id,
+                            path: cab_path.clone(),
+                        },
+                        None,
+                    );
+                    self.add_unity_fs_serialized_file(
+                        unity_fs_id,
+                        cab_path.clone(),
+                        serialized_file_id,
+                    );
+                    self.indexed_serialized_files
+                        .insert(serialized_file_id, indexed_serialized_file.clone());
+                    record.serialized_files.push((serialized_file_id, cab_path));
+                }
+                loaded_file.duplicate_nodes =
+                    self.add_unity_fs_nodes(unity_fs_id, &indexed_file.node_paths);
+                self.unity_fs_map.insert(
+                    unity_fs_id,
+                    UnityFSEntry {
+                        source: Some((asset_source_id, record.path.clone())),
+                        resource_search_path,
+                        unity_fs: OnceCell::new(),
+                    },
+                );
+                self.unity_fs_to_asset_source_map
+                    .insert(unity_fs_id, (asset_source_id, dir));
+                loaded_file.unity_fs_id = Some(unity_fs_id);
+                record.unity_fs_id = Some(unity_fs_id);
+            }
+            FileKind::SerializedFile if record.load_serialized_file => {
+                let serialized_file_id = self.add_serialized_file_entry(
+                    SerializedFileSource::AssetSource {
+                        asset_source_id,
+                        path: record.path.clone(),
+                    },
+                    resource_search_path,
+                );
+                self.serialized_file_to_asset_source_map
+                    .insert(serialized_file_id, (asset_source_id, dir));
+                // the file may be indexed by a loader which did not load serialized files.
+                if let Some(indexed_serialized_file) = indexed_file.serialized_files.first() {
+                    self.indexed_serialized_files
+                        .insert(serialized_file_id, indexed_serialized_file.clone());
+                }
+                record
+                    .serialized_files
+                    .push((serialized_file_id, String::new()));
+            }
+            _ => (),
+        }
+        loaded_file.serialized_file_ids =
+            record.serialized_files.iter().map(|(id, _)| *id).collect();
+        for serialized_file_id in &loaded_file.serialized_file_ids {
+            self.add_to_container_index(*serialized_file_id);
+        }
+        record.indexed_file = Some(IndexedFile {
+            serialized_files: Vec::new(),
+            ..indexed_file
+        });
+    }
+
+    /// Use an index written by save_index. Files of later loaders whose size and modified time
+    /// did not change are restored from it instead of being read.
+    /// Only lazy viewers can use it, eager viewers return an error.
+    pub fn load_index<P: AsRef<Path>>(&mut self, index_path: P) -> ReadResult<()> {
+        self.check_index_use()?;
+        self.use_index(ViewerIndex::read_from_path(index_path)?);
+        Ok(())
+    }
+
+    /// Like load_index, the index is read from reader.
+    pub fn read_index<R: Read + Seek>(&mut self, reader: &mut R) -> ReadResult<()> {
+        self.check_index_use()?;
+        self.use_index(ViewerIndex::read_from(reader)?);
+        Ok(())
+    }
+
+    fn check_index_use(&self) -> ReadResult<()> {
+        if self.is_lazy() {
+            Ok(())
+        } else {
+            Err(Error::Other(
+                "viewer index can only be used by viewers created by new_lazy".to_owned(),
+            ))
+        }
+    }
+
+    fn use_index(&mut self, index: ViewerIndex) {
+        self.indexed_files = index
+            .files
+            .into_iter()
+            .map(|file| (PathBuf::from(&file.path), file))
+            .collect();
+    }
+
+    /// Write file list, cab maps, object tables and containers of files read from dirs.
+    /// Serialized files not indexed yet are parsed, files which can not be parsed are left out
+    /// and listed in the report with their error, in strict mode the error is returned instead.
+    pub fn save_index<P: AsRef<Path>>(&self, index_path: P) -> ReadResult<LoadReport> {
+        let (index, report) = self.build_index()?;
+        index.write_to_path(index_path)?;
+        Ok(report)
+    }
+
+    /// Like save_index, the index is written to writer.
+    pub fn write_index<W: Write + Seek>(&self, writer: &mut W) -> ReadResult<LoadReport> {
+        let (index, report) = self.build_index()?;
+        index.write_to(writer)?;
+        Ok(report)
+    }
+
+    fn build_index(&self) -> ReadResult<(ViewerIndex, LoadReport)> {
+        let mut index = ViewerIndex::default();
+        let mut report = LoadReport::default();
+        for (display_path, record) in &self.file_records {
+            let Some(indexed_file) = &record.indexed_file else {
+                continue;
+            };
+            let serialized_files: ReadResult<Vec<_>> = record
+                .serialized_files
+                .iter()
+                .map(|(serialized_file_id, path)| {
+                    self.get_indexed_serialized_file(*serialized_file_id, path)
+                })
+                .collect();
+            match serialized_files {
+                Ok(serialized_files) => index.files.push(IndexedFile {
+                    serialized_files,
+                    ..indexed_file.clone()
+                }),
+                Err(err) => {
+                    let mut left_out_file = LoadedFile::new(display_path.clone());
+                    left_out_file.kind = indexed_file.kind;
+                    left_out_file.unity_fs_id = record.unity_fs_id;
+                    left_out_file.serialized_file_ids =
+                        record.serialized_files.iter().map(|(id, _)| *id).collect();
+                    left_out_file.error = Some(err);
+                    report.push(left_out_file, self.strict)?;
+                }
+            }
+        }
+        Ok((index, report))
+    }
+
+    fn get_indexed_serialized_file(
+        &self,
+        serialized_file_id: i64,
+        path: &str,
+    ) -> ReadResult<IndexedSerializedFile> {
+        if let Some(indexed_serialized_file) =
+            self.indexed_serialized_files.get(&serialized_file_id)
+        {
+            return Ok(indexed_serialized_file.clone());
+        }
+        let serialized_file = self.get_serialized_file_by_id(serialized_file_id)?;
+        let containers = read_containers(serialized_file)
+            .into_iter()
+            .filter_map(|(name, pptr)| {
+                let pptr = PPtr::new(&pptr);
+                Some(IndexedContainer {
+                    name,
+                    file_id: pptr.get_file_id().ok()? as i32,
+                    path_id: pptr.get_path_id().ok()?,
+                })
+            })
+            .collect();
+        Ok(IndexedSerializedFile {
+            path: path.to_owned(),
+            externals: serialized_file
+                .get_externals()
+                .iter()
+                .map(|external| {
+                    PathBuf::from(&external.path.to_string())
+                        .file_name()
+                        .map(|file_name| file_name.to_string_lossy().into_owned())
+                        .unwrap_or_default()
+                })
+                .collect(),
+            objects: serialized_file
+                .get_object_map()
+                .iter()
+                .map(|(path_id, obj)| IndexedObject {
+                    path_id: *path_id,
+                    class_id: obj.class,
+                })
+                .collect(),
+            containers,
+        })
+    }
+
+    /// Class of an object, from the index when its file is indexed so the file is not parsed.
+    pub fn get_object_class_id(&self, serialized_file_id: i64, path_id: i64) -> Option<i32> {
+        if let Some(indexed_serialized_file) =
+            self.indexed_serialized_files.get(&serialized_file_id)
+        {
+            // objects are saved in path id order.
+            let objects = &indexed_serialized_file.objects;
+            return objects
+                .binary_search_by_key(&path_id, |object| object.path_id)
+                .ok()
+                .map(|index| objects[index].class_id);
+        }
+        let serialized_file = self.get_serialized_file_by_id(serialized_file_id).ok()?;
+        Some(serialized_file.get_object_map().get(&path_id)?.class)
+    }
+
+    /// Path id and class of every object of a file in path id order,
+    /// from the index when the file is indexed so the file is not parsed.
+    pub fn get_object_class_ids(&self, serialized_file_id: i64) -> ReadResult<Vec<(i64, i32)>> {
+        if let Some(indexed_serialized_file) =
+            self.indexed_serialized_files.get(&serialized_file_id)
+        {
+            return Ok(indexed_serialized_file
+                .objects
+                .iter()
+                .map(|object| (object.path_id, object.class_id))
+                .collect());
+        }
+        let serialized_file = self.get_serialized_file_by_id(serialized_file_id)?;
+        let mut objects: Vec<_> = serialized_file
+            .get_object_map()
+            .iter()
+            .map(|(path_id, object)| (*path_id, object.class))
+            .collect();
+        objects.sort_unstable();
+        Ok(objects)
+    }
+
+    /// Read the AssetBundleManifest bundle of a bundle build, which is named after the build dir.
+    /// Bundles it lists are only added when asked by load_asset_bundle.
+    pub fn read_asset_bundle_manifest<P: AsRef<Path>>(
+        &mut self,
+        manifest_path: P,
+    ) -> ReadResult<()> {
+        let manifest_path = manifest_path.as_ref();
+        let file_name = manifest_path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let asset_source =
+            self.new_directory_source(manifest_path.parent().unwrap_or(Path::new("")));
+        self.add_asset_bundle_manifest(Arc::new(asset_source), &file_name)
+    }
+
+    /// Add the AssetBundleManifest bundle at path of the asset source,
+    /// bundle names of the manifest are paths relative to its dir.
+    pub fn add_asset_bundle_manifest(
+        &mut self,
+        asset_source: Arc<dyn AssetSource>,
+        path: &str,
+    ) -> ReadResult<()> {
+        let path = asset_source.join("", path);
+        let asset_source_id = self.asset_source_count;
+        self.asset_source_count += 1;
+        self.asset_source_map
+            .insert(asset_source_id, asset_source.clone());
+
+        let mut loaded_file = LoadedFile::new(asset_source.get_display_path(&path));
+        let asset_bundle_infos = self
+            .add_asset_source_file(
+                asset_source_id,
+                asset_source.as_ref(),
+                &path,
+                false,
+                &mut loaded_file,
+            )
+            .and_then(|_| self.read_asset_bundle_infos(&loaded_file.serialized_file_ids))
+            .map_err(|err| Error::FileLoadError {
+                source: Box::new(err),
+                path: loaded_file.path.clone(),
+            })?;
+        if let Some(unity_fs_id) = loaded_file.unity_fs_id {
+            let name = path.rsplit('/').next().unwrap_or(&path).to_owned();
+            self.asset_bundle_map.insert(name, unity_fs_id);
+        }
+        self.asset_bundle_manifest = Some(AssetBundleManifestEntry {
+            asset_source_id,
+            dir: path
+                .rsplit_once('/')
+                .map(|(dir, _)| dir.to_owned())
+                .unwrap_or_default(),
+            asset_bundle_infos,
+        });
+        Ok(())
+    }
+
+    fn read_asset_bundle_infos(
+        &self,
+        serialized_file_ids: &[i64],
+    ) -> ReadResult<BTreeMap<String, AssetBundleInfo>> {
+        for serialized_file_id in serialized_file_ids {
+            let serialized_file = self.get_serialized_file_by_id(*serialized_file_id)?;
+            for (path_id, obj) in serialized_file.get_object_map() {
+                if obj.class != ClassIDType::AssetBundleManifest as i32 {
+                    continue;
+                }
+                if let Some(manifest) = serialized_file.get_tt_object_by_path_id(*path_id)? {
+                    let manifest = manifest.into();
+                    return AssetBundleManifest::new(&manifest).get_asset_bundle_infos();
+                }
+            }
+        }
+        Err(Error::Other(
+            "AssetBundleManifest object not found".to_owned(),
+        ))
+    }
+
+    /// Hash and dependencies of every bundle listed by the manifest.
+    pub fn get_asset_bundle_infos(&self) -> Option<&BTreeMap<String, AssetBundleInfo>> {
+        self.asset_bundle_manifest
+            .as_ref()
+            .map(|manifest| &manifest.asset_bundle_infos)
+    }
+
+    /// Bundles needed by name directly or not, dependencies come before bundles needing them.
+    /// Cycles in the manifest are allowed.
+    pub fn get_asset_bundle_dependencies(&self, name: &str) -> ReadResult<Vec<String>> {
+        let asset_bundle_infos = self
+            .get_asset_bundle_infos()
+            .ok_or_else(|| Error::Other("no AssetBundleManifest read".to_owned()))?;
+        let mut dependencies = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![(name.to_owned(), false)];
+        while let Some((name, expanded)) = stack.pop() {
+            if expanded {
+                dependencies.push(name);
+                continue;
+            }
+            if !visited.insert(name.clone()) {
+                continue;
+            }
+            let asset_bundle_info = asset_bundle_infos
+                .get(&name)
+                .ok_or_else(|| Error::AssetBundleNotFound(name.clone()))?;
+            stack.push((name, true));
+            for dependency in asset_bundle_info.dependencies.iter().rev() {
+                stack.push((dependency.clone(), false));
+            }
+        }
+        dependencies.pop();
+        Ok(dependencies)
+    }
+
+    /// Add the bundle listed by the manifest with every bundle it depend on,
+    /// bundles added before are skipped.
+    pub fn load_asset_bundle(&mut self, name: &str) -> ReadResult<LoadReport> {
+        let mut names = self.get_asset_bundle_dependencies(name)?;
+        names.push(name.to_owned());
+        let Some(manifest) = &self.asset_bundle_manifest else {
+            return Ok(LoadReport::default());
+        };
+        let asset_source_id = manifest.asset_source_id;
+        let dir = manifest.dir.clone();
+        let asset_source = self.asset_source_map[&asset_source_id].clone();
+
+        let mut report = LoadReport::default();
+        for name in names {
+            if self.asset_bundle_map.contains_key(&name) {
+                continue;
+            }
+            let path = asset_source.join(&dir, &name);
+            let mut loaded_file = LoadedFile::new(asset_source.get_display_path(&path));
+            loaded_file.error = self
+                .add_asset_source_file(
+                    asset_source_id,
+                    asset_source.as_ref(),
+                    &path,
+                    false,
+                    &mut loaded_file,
+                )
+                .err();
+            if let Some(unity_fs_id) = loaded_file.unity_fs_id {
+                self.asset_bundle_map.insert(name, unity_fs_id);
+            }
+            report.push(loaded_file, self.strict)?;
+        }
+        Ok(report)
+    }
+
+    /// Bundle added by load_asset_bundle or the manifest bundle itself.
+    pub fn get_unity_fs_by_asset_bundle_name(&self, name: &str) -> Option<&UnityFS> {
+        self.get_unity_fs_by_id(*self.asset_bundle_map.get(name)?)
+    }
+
     pub fn get_serialized_file_by_path(&self, path: &String) -> Option<&SerializedFile> {
         if let Some(serialized_file_id) = self.cab_maps.get(path) {
-            if let Some(serialized_file) = self.serialized_file_map.get(serialized_file_id) {
+            if let Ok(serialized_file) = self.get_serialized_file_by_id(*serialized_file_id) {
                 return Some(serialized_file);
             }
         }
         None
     }
 
+    /// Read object in view. MonoBehaviour stored without typetree
+    /// is read with script fields from type tree providers when its m_Script can be resolved.
+    pub fn get_type_tree_object_by_serialized_file_id_and_path_id(
+        &self,
+        serialized_file_id: i64,
+        path_id: i64,
+    ) -> ReadResult<Option<TypeTreeObject>> {
+        let serialized_file = self.get_serialized_file_by_id(serialized_file_id)?;
+        if let Some(class_args) = self.get_mono_behaviour_type_object_args(serialized_file, path_id)
+        {
+            return serialized_file.get_tt_object_by_path_id_with_class_args(path_id, class_args);
+        }
+        serialized_file.get_tt_object_by_path_id(path_id)
+    }
+
+    fn get_mono_behaviour_type_object_args(
+        &self,
+        serialized_file: &SerializedFile,
+        path_id: i64,
+    ) -> Option<TypeTreeObjectBinReadClassArgs> {
+        let obj = serialized_file.get_object_map().get(&path_id)?;
+        if obj.class != ClassIDType::MonoBehaviour as i32 || serialized_file.get_enable_type_tree()
+        {
+            return None;
+        }
+        let base_class_args = serialized_file.get_type_object_args_by_path_id(path_id)?;
+        let mono_behaviour = serialized_file.get_tt_object_by_path_id(path_id).ok()??;
+        let script_pptr =
+            TypeTreeObjectRef::try_cast_from(&mono_behaviour.into(), "/Base/m_Script").ok()?;
+        let script = PPtr::new(&script_pptr)
+            .get_type_tree_object(serialized_file, Some(self))
+            .ok()??;
+        let class_name = String::try_cast_from(&script, "/Base/m_ClassName").ok()?;
+        let namespace = String::try_cast_from(&script, "/Base/m_Namespace").unwrap_or_default();
+        let assembly_name = String::try_cast_from(&script, "/Base/m_AssemblyName").ok()?;
+        provider::get_mono_behaviour_type_object_args(
+            &serialized_file.get_unity_version(),
+            &base_class_args,
+            &assembly_name,
+            &namespace,
+            &class_name,
+        )
+    }
+
     pub fn get_unity_fs_by_cab_path(&self, path: &String) -> Option<&UnityFS> {
         if let Some(serialized_file_id) = self.cab_maps.get(path) {
             if let Some(unity_fs_id) = self.serialized_file_to_unity_fs_map.get(serialized_file_id)
             {
-                if let Some(unity_fs) = self.unity_fs_map.get(unity_fs_id) {
+                if let Some(unity_fs) = self.get_unity_fs_by_id(*unity_fs_id) {
                     return Some(unity_fs);
                 }
             }
@@ -233,7 +1525,7 @@ impl UnityAssetViewer {
             .serialized_file_to_unity_fs_map
             .get(&serialized_file.get_serialized_file_id())
         {
-            if let Some(unity_fs) = self.unity_fs_map.get(unity_fs_id) {
+            if let Some(unity_fs) = self.get_unity_fs_by_id(*unity_fs_id) {
                 return Some(unity_fs);
             }
         }
@@ -246,7 +1538,7 @@ impl UnityAssetViewer {
             .serialized_file_to_unity_fs_map
             .get(&serialized_file_id)
         {
-            if let Some(unity_fs) = self.unity_fs_map.get(unity_fs_id) {
+            if let Some(unity_fs) = self.get_unity_fs_by_id(*unity_fs_id) {
                 return Some(unity_fs);
             }
         }
@@ -262,7 +1554,7 @@ impl UnityAssetViewer {
             .serialized_file_to_unity_fs_map
             .get(&type_tree_object.serialized_file_id)
         {
-            if let Some(unity_fs) = self.unity_fs_map.get(unity_fs_id) {
+            if let Some(unity_fs) = self.get_unity_fs_by_id(*unity_fs_id) {
                 return Some(unity_fs);
             }
         }
@@ -275,7 +1567,11 @@ impl UnityAssetViewer {
         path_id: i64,
     ) -> Option<&String> {
         if let Some(serialized_file_id) = self.cab_maps.get(cab_name) {
-            if let Some(name_map) = self.container_name_maps.get(serialized_file_id) {
+            if let Some(name_map) = self
+                .get_container_index()
+                .container_name_maps
+                .get(serialized_file_id)
+            {
                 return name_map.get(&path_id);
             }
         }
@@ -287,7 +1583,11 @@ impl UnityAssetViewer {
         serialized_file_id: i64,
         path_id: i64,
     ) -> Option<&String> {
-        if let Some(name_map) = self.container_name_maps.get(&serialized_file_id) {
+        if let Some(name_map) = self
+            .get_container_index()
+            .container_name_maps
+            .get(&serialized_file_id)
+        {
             return name_map.get(&path_id);
         }
         None
@@ -295,7 +1595,11 @@ impl UnityAssetViewer {
 
     pub fn get_container_name_by_pptr(&self, pptr: &PPtr) -> Option<&String> {
         let serialized_file_id = pptr.get_serialized_file_id();
-        if let Some(name_map) = self.container_name_maps.get(&serialized_file_id) {
+        if let Some(name_map) = self
+            .get_container_index()
+            .container_name_maps
+            .get(&serialized_file_id)
+        {
             if let Ok(path_id) = pptr.get_path_id() {
                 return name_map.get(&path_id);
             }
@@ -303,13 +1607,14 @@ impl UnityAssetViewer {
         None
     }
 
+    /// Object of the first candidate of the container, see get_type_tree_objects_by_container_name for all.
     pub fn get_type_tree_object_by_container_name(
         &self,
         container_name: &String,
     ) -> ReadResult<Option<TypeTreeObject>> {
-        if let Some(serialized_file_id) = self.container_maps.get(container_name) {
-            if let Some((serialized_file_id, pptr)) = serialized_file_id.get(0) {
-                if let Some(serialized_file) = self.serialized_file_map.get(serialized_file_id) {
+        if let Some(objs) = self.get_container_maps().get(container_name) {
+            if let Some((serialized_file_id, pptr)) = objs.first() {
+                if let Ok(serialized_file) = self.get_serialized_file_by_id(*serialized_file_id) {
                     return PPtr::new(pptr).get_type_tree_object(serialized_file, Some(self));
                 }
             }
@@ -317,79 +1622,912 @@ impl UnityAssetViewer {
         Ok(None)
     }
 
+    /// Objects of every candidate of the container, like sprites of a texture.
+    pub fn get_type_tree_objects_by_container_name(
+        &self,
+        container_name: &String,
+    ) -> ReadResult<Vec<TypeTreeObject>> {
+        let mut objs = Vec::new();
+        for entry in self.get_container_entries(container_name) {
+            if let Some(obj) = entry.get_type_tree_object()? {
+                objs.push(obj);
+            }
+        }
+        Ok(objs)
+    }
+
+    /// File of the first candidate of the container, see get_serialized_files_by_container_name for all.
     pub fn get_serialized_file_by_container_name(
         &self,
         container_name: &String,
     ) -> Option<&SerializedFile> {
-        if let Some(serialized_file_id) = self.container_maps.get(container_name) {
-            if let Some((serialized_file_id, _pptr)) = serialized_file_id.get(0) {
-                return self.serialized_file_map.get(serialized_file_id);
+        if let Some(objs) = self.get_container_maps().get(container_name) {
+            if let Some((serialized_file_id, _pptr)) = objs.first() {
+                return self.get_serialized_file_by_id(*serialized_file_id).ok();
             }
         }
         None
     }
 
+    /// Files which hold the container, each file once.
+    pub fn get_serialized_files_by_container_name(
+        &self,
+        container_name: &String,
+    ) -> Vec<&SerializedFile> {
+        let mut serialized_file_ids: Vec<i64> = self
+            .get_container_entries(container_name)
+            .map(|entry| entry.serialized_file_id)
+            .collect();
+        serialized_file_ids.sort_unstable();
+        serialized_file_ids.dedup();
+        serialized_file_ids
+            .into_iter()
+            .filter_map(|serialized_file_id| {
+                self.get_serialized_file_by_id(serialized_file_id).ok()
+            })
+            .collect()
+    }
+
+    pub fn get_container_entries<'a>(
+        &'a self,
+        container_name: &String,
+    ) -> impl Iterator<Item = ContainerEntry<'a>> + 'a {
+        self.get_container_maps()
+            .get_key_value(container_name)
+            .into_iter()
+            .flat_map(move |(container_name, objs)| {
+                objs.iter()
+                    .map(move |(serialized_file_id, pptr)| ContainerEntry {
+                        container_name,
+                        serialized_file_id: *serialized_file_id,
+                        pptr,
+                        viewer: self,
+                    })
+            })
+    }
+
+    /// Every object of every container, ordered by container name.
+    pub fn iter_containers(&self) -> impl Iterator<Item = ContainerEntry<'_>> + '_ {
+        self.iter_containers_by_filter(|_| true)
+    }
+
+    pub fn iter_containers_by_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = ContainerEntry<'a>> + 'a {
+        self.iter_containers_by_filter(move |container_name| container_name.starts_with(prefix))
+    }
+
+    /// Filter containers by glob like assets/**/*.png, `*` and `?` do not match '/'.
+    pub fn iter_containers_by_glob(
+        &self,
+        pattern: &str,
+    ) -> impl Iterator<Item = ContainerEntry<'_>> + '_ {
+        let regex = glob_to_regex(pattern);
+        self.iter_containers_by_filter(move |container_name| regex.is_match(container_name))
+    }
+
+    fn iter_containers_by_filter<'a, F: Fn(&str) -> bool + 'a>(
+        &'a self,
+        filter: F,
+    ) -> impl Iterator<Item = ContainerEntry<'a>> + 'a {
+        let mut container_names: Vec<&String> = self
+            .get_container_maps()
+            .keys()
+            .filter(|container_name| filter(container_name))
+            .collect();
+        container_names.sort();
+        container_names
+            .into_iter()
+            .flat_map(move |container_name| self.get_container_entries(container_name))
+    }
+
+    /// Build references of every object by walking its PPtr fields, external file ids are resolved
+    /// to files added to the viewer. Every serialized file is parsed.
+    pub fn build_reference_graph(&self) -> ReferenceGraph {
+        let mut graph = ReferenceGraph::new();
+        for (serialized_file_id, serialized_file) in self.iter_serialized_files() {
+            let serialized_file = match serialized_file {
+                Ok(serialized_file) => serialized_file,
+                Err(err) => {
+                    graph
+                        .errors
+                        .push((ObjectId::new(serialized_file_id, 0), err));
+                    continue;
+                }
+            };
+            let externals: Vec<(String, Option<i64>)> = serialized_file
+                .get_externals()
+                .iter()
+                .map(|external| {
+                    let path = external.path.to_string();
+                    let serialized_file_id = PathBuf::from(&path)
+                        .file_name()
+                        .and_then(|file_name| self.cab_maps.get(&*file_name.to_string_lossy()))
+                        .copied();
+                    (path, serialized_file_id)
+                })
+                .collect();
+
+            let mut path_ids: Vec<i64> = serialized_file.get_object_map().keys().copied().collect();
+            path_ids.sort_unstable();
+            for path_id in path_ids {
+                let from = ObjectId::new(serialized_file_id, path_id);
+                graph.add_object(from);
+                let object = match self.get_type_tree_object_by_serialized_file_id_and_path_id(
+                    serialized_file_id,
+                    path_id,
+                ) {
+                    Ok(Some(object)) => object,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        graph.errors.push((from, err));
+                        continue;
+                    }
+                };
+                let pptr_paths = object.get_pptr_field_paths();
+                let object = TypeTreeObjectRef::from(object);
+                for pptr_path in pptr_paths {
+                    let Ok(pptr) = TypeTreeObjectRef::try_cast_from(&object, &pptr_path[..]) else {
+                        continue;
+                    };
+                    let pptr = PPtr::new(&pptr);
+                    let (Ok(file_id), Ok(path_id)) = (pptr.get_file_id(), pptr.get_path_id())
+                    else {
+                        continue;
+                    };
+                    if path_id == 0 {
+                        continue;
+                    }
+                    let to_serialized_file_id = if file_id == 0 {
+                        Ok(serialized_file_id)
+                    } else {
+                        match usize::try_from(file_id - 1)
+                            .ok()
+                            .and_then(|i| externals.get(i))
+                        {
+                            Some((_, Some(to_serialized_file_id))) => Ok(*to_serialized_file_id),
+                            Some((external_path, None)) => Err(external_path.clone()),
+                            None => Err(String::new()),
+                        }
+                    };
+                    match to_serialized_file_id {
+                        Ok(to_serialized_file_id) => {
+                            graph.add_reference(from, ObjectId::new(to_serialized_file_id, path_id))
+                        }
+                        Err(external_path) => {
+                            graph.unresolved_references.push(UnresolvedReference {
+                                from,
+                                external_path,
+                                path_id,
+                            })
+                        }
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    /// Get node of any loaded bundle by full archive path like archive:/CAB-xxx/CAB-xxx.resS.
+    pub fn get_resource_node_by_archive_path(&self, path: &String) -> Option<UnityFSNode> {
+        let (unity_fs_id, node_path) = self.resource_node_map.get(path)?;
+        self.get_unity_fs_by_id(*unity_fs_id)?
+            .get_file_reader_by_path(node_path)
+    }
+
     pub fn get_resource_file_by_serialized_file_id_and_path(
         &self,
         serialized_file_id: i64,
         path: &String,
-    ) -> Option<Box<dyn UnityResource>> {
+    ) -> ReadResult<Box<dyn UnityResource>> {
+        if let Some(node) = self.get_resource_node_by_archive_path(path) {
+            return Ok(Box::new(node));
+        }
+        if !path.starts_with("archive:/") {
+            let source_dir = self
+                .serialized_file_to_asset_source_map
+                .get(&serialized_file_id)
+                .or_else(|| {
+                    self.serialized_file_to_unity_fs_map
+                        .get(&serialized_file_id)
+                        .and_then(|unity_fs_id| self.unity_fs_to_asset_source_map.get(unity_fs_id))
+                });
+            // a file of an asset source only see that source, it may not be on the disk at all.
+            if let Some((asset_source_id, dir)) = source_dir {
+                if let Some(asset_source) = self.asset_source_map.get(asset_source_id) {
+                    let file_name = path.rsplit('/').next().unwrap_or(path);
+                    if let Some(file) =
+                        open_if_exists(asset_source.as_ref(), &asset_source.join(dir, file_name))
+                    {
+                        return Ok(file);
+                    }
+                }
+                return Err(Error::ResourceNotFound(path.to_owned()));
+            }
+        }
         get_resource_file_by_path(
             path,
-            self.serialized_file_map.get(&serialized_file_id),
+            self.get_serialized_file_by_id(serialized_file_id).ok(),
             self.serialized_file_to_unity_fs_map
                 .get(&serialized_file_id)
-                .and_then(|fs_id| self.unity_fs_map.get(fs_id)),
+                .and_then(|fs_id| self.get_unity_fs_by_id(*fs_id)),
             None,
         )
+        .ok_or_else(|| Error::ResourceNotFound(path.to_owned()))
     }
 }
 
+/// Find stream data of a file, in the bundle for archive paths,
+/// otherwise in search_path and the resource search path of the file.
 pub fn get_resource_file_by_path(
     path: &String,
     serialized_file: Option<&SerializedFile>,
     unityfs: Option<&UnityFS>,
     search_path: Option<&String>,
 ) -> Option<Box<dyn UnityResource>> {
-    if let Some(file_name) = PathBuf::from(&path)
+    let file_name = PathBuf::from(&path)
         .file_name()
-        .map(|f| f.to_string_lossy().into_owned())
-    {
-        if path.starts_with("archive:/") {
-            if let Some(unityfs) = unityfs {
-                if let Some(file_reader) = unityfs.get_file_reader_by_path(&file_name) {
-                    return Some(Box::new(file_reader));
-                }
-            }
-        } else {
-            if let Some(search_path) = search_path {
-                let path = PathBuf::from(search_path).join(&file_name);
-                if let Ok(file) = OpenOptions::new().read(true).open(path) {
-                    return Some(Box::new(BufReader::new(file)));
-                }
-            }
-            if let Some(serialized_file) = serialized_file {
-                if let Some(search_path) = &serialized_file.resource_search_path {
-                    let path = PathBuf::from(search_path).join(&file_name);
-                    if let Ok(file) = OpenOptions::new().read(true).open(path) {
-                        return Some(Box::new(BufReader::new(file)));
-                    }
-                }
-            }
-            if let Some(unityfs) = unityfs {
-                if let Some(search_path) = &unityfs.resource_search_path {
-                    let path = PathBuf::from(search_path).join(&file_name);
-                    if let Ok(file) = OpenOptions::new().read(true).open(path) {
-                        return Some(Box::new(BufReader::new(file)));
-                    }
+        .map(|f| f.to_string_lossy().into_owned())?;
+    if path.starts_with("archive:/") {
+        let file = open_if_exists(unityfs?, &file_name)?;
+        return Some(file);
+    }
+    let search_paths = [
+        search_path,
+        serialized_file.and_then(|serialized_file| serialized_file.resource_search_path.as_ref()),
+        unityfs.and_then(|unityfs| unityfs.resource_search_path.as_ref()),
+    ];
+    for search_path in search_paths.into_iter().flatten() {
+        if let Some(file) = open_if_exists(&DirectorySource::new(search_path), &file_name) {
+            return Some(file);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, io::Read};
+
+    use super::*;
+    use crate::{
+        asset_source::MemorySource,
+        test_data::{
+            build_unity_fs, read_prefabs_cab, ASSET_BUNDLE, DATA_UNITY3D, GAME_DLL,
+            MANIFEST_BUNDLE, PICKUP, PICKUP_TRANSFORM, PLAYER, PLAYER_TRANSFORM, PREFABS_BUNDLE,
+            PREFABS_CAB, SCRIPTS_ASSETS, SCRIPT_TYPES_ASSETS, WEAPON, WEAPON_TRANSFORM,
+        },
+        type_tree::{
+            managed::{ManagedAssembly, ManagedAssemblyTypeTreeProvider},
+            provider::TypeTreeProvider,
+        },
+    };
+
+    #[test]
+    fn test_container_glob() {
+        let regex = glob_to_regex("assets/**/*.png");
+        assert!(regex.is_match("assets/textures/ui/icon.png"));
+        assert!(!regex.is_match("assets/textures/icon.png.meta"));
+        let regex = glob_to_regex("assets/*.prefab");
+        assert!(regex.is_match("assets/a (1).prefab"));
+        assert!(!regex.is_match("assets/sub/a.prefab"));
+    }
+
+    #[test]
+    fn test_asset_bundle_dependencies() {
+        let info = |dependencies: &[&str]| AssetBundleInfo {
+            hash: [0; 16],
+            dependencies: dependencies.iter().map(|name| name.to_string()).collect(),
+        };
+        let mut viewer = UnityAssetViewer::new();
+        viewer.asset_bundle_manifest = Some(AssetBundleManifestEntry {
+            asset_source_id: 0,
+            dir: String::new(),
+            asset_bundle_infos: BTreeMap::from([
+                ("ui".to_owned(), info(&["atlas", "shader"])),
+                ("atlas".to_owned(), info(&["shader", "ui"])),
+                ("shader".to_owned(), info(&[])),
+                ("broken".to_owned(), info(&["missing"])),
+            ]),
+        });
+        assert_eq!(
+            viewer.get_asset_bundle_dependencies("ui").unwrap(),
+            ["shader", "atlas"]
+        );
+        assert!(viewer
+            .get_asset_bundle_dependencies("shader")
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            viewer.get_asset_bundle_dependencies("broken"),
+            Err(Error::AssetBundleNotFound(name)) if name == "missing"
+        ));
+    }
+
+    #[test]
+    fn test_load_asset_bundle() {
+        let ui_cab = format!("CAB-{}", "a".repeat(32));
+        let source = Arc::new(MemorySource::new());
+        source.insert("Bundles/Bundles", MANIFEST_BUNDLE.to_vec());
+        source.insert("Bundles/prefabs", PREFABS_BUNDLE.to_vec());
+        source.insert(
+            "Bundles/ui",
+            build_unity_fs(&[(&ui_cab, &read_prefabs_cab())]),
+        );
+        let mut viewer = UnityAssetViewer::new();
+        viewer
+            .add_asset_bundle_manifest(source, "Bundles/Bundles")
+            .unwrap();
+
+        let asset_bundle_infos = viewer.get_asset_bundle_infos().unwrap();
+        assert_eq!(
+            asset_bundle_infos.keys().collect::<Vec<_>>(),
+            ["prefabs", "ui"]
+        );
+        let prefabs = &asset_bundle_infos["prefabs"];
+        assert_eq!(prefabs.hash, std::array::from_fn(|i| i as u8));
+        assert!(prefabs.dependencies.is_empty());
+        let ui = &asset_bundle_infos["ui"];
+        // md5 of "ui"
+        assert_eq!(
+            ui.hash,
+            [
+                0x7d, 0x5c, 0x00, 0x9e, 0x4e, 0xb8, 0xbb, 0xc7, 0x86, 0x47, 0xca, 0xec, 0xa3, 0x08,
+                0xe6, 0x1b
+            ]
+        );
+        assert_eq!(ui.dependencies, ["prefabs"]);
+
+        let report = viewer.load_asset_bundle("ui").unwrap();
+        assert!(!report.has_error());
+        let paths: Vec<_> = report.files.iter().map(|file| file.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("Bundles/prefabs"),
+                PathBuf::from("Bundles/ui")
+            ]
+        );
+        assert!(viewer
+            .get_unity_fs_by_asset_bundle_name("prefabs")
+            .is_some());
+        assert!(viewer.cab_maps.contains_key(PREFABS_CAB));
+        assert!(viewer.cab_maps.contains_key(&ui_cab));
+        // both are added already.
+        let report = viewer.load_asset_bundle("prefabs").unwrap();
+        assert!(report.files.is_empty());
+    }
+
+    #[test]
+    fn test_archive_nodes_of_other_bundles() {
+        let cab_a = format!("CAB-{}", "a".repeat(32));
+        let cab_b = format!("CAB-{}", "b".repeat(32));
+        let cab_c = format!("CAB-{}", "c".repeat(32));
+        let cab = read_prefabs_cab();
+        let source = MemorySource::new();
+        for (path, cab_name, res_s_name, res_s) in [
+            ("a.bundle", &cab_a, &cab_a, [1u8; 16]),
+            ("b.bundle", &cab_b, &cab_b, [2; 16]),
+            // the stream data of b again, like a bundle built twice.
+            ("c.bundle", &cab_c, &cab_b, [3; 16]),
+        ] {
+            let res_s_path = format!("{res_s_name}.resS");
+            source.insert(
+                path,
+                build_unity_fs(&[(cab_name, &cab), (&res_s_path, &res_s)]),
+            );
+        }
+        let mut viewer = UnityAssetViewer::new();
+        let report = viewer
+            .add_asset_source(Arc::new(source), "", false)
+            .unwrap();
+        assert!(!report.has_error());
+        let res_s_path = format!("archive:/{cab_b}/{cab_b}.resS");
+        let duplicate_nodes: Vec<_> = report
+            .files
+            .iter()
+            .map(|file| file.duplicate_nodes.clone())
+            .collect();
+        assert_eq!(duplicate_nodes, [vec![], vec![], vec![res_s_path.clone()]]);
+
+        let serialized_file_id = viewer.cab_maps[&cab_a];
+        let mut res_s = Vec::new();
+        viewer
+            .get_resource_file_by_serialized_file_id_and_path(serialized_file_id, &res_s_path)
+            .unwrap()
+            .read_to_end(&mut res_s)
+            .unwrap();
+        assert_eq!(res_s, [2; 16]);
+
+        let missing_path = format!("archive:/{cab_c}/{cab_c}.resS");
+        assert!(matches!(
+            viewer.get_resource_file_by_serialized_file_id_and_path(serialized_file_id, &missing_path),
+            Err(Error::ResourceNotFound(path)) if path == missing_path
+        ));
+    }
+
+    #[test]
+    fn test_resource_files_of_asset_source() {
+        let source = MemorySource::new();
+        source.insert("Data/prefabs.bundle", PREFABS_BUNDLE.to_vec());
+        source.insert("Data/prefabs.resS", vec![7; 8]);
+        let mut viewer = UnityAssetViewer::new();
+        viewer
+            .add_asset_source(Arc::new(source), "", false)
+            .unwrap();
+        let serialized_file_id = viewer.cab_maps[PREFABS_CAB];
+        let mut res_s = Vec::new();
+        viewer
+            .get_resource_file_by_serialized_file_id_and_path(
+                serialized_file_id,
+                &"Assets/prefabs.resS".to_owned(),
+            )
+            .unwrap()
+            .read_to_end(&mut res_s)
+            .unwrap();
+        assert_eq!(res_s, [7; 8]);
+
+        // the working dir of the tests has it, but it is not in the source.
+        let missing_path = "Cargo.toml".to_owned();
+        assert!(matches!(
+            viewer.get_resource_file_by_serialized_file_id_and_path(serialized_file_id, &missing_path),
+            Err(Error::ResourceNotFound(path)) if path == missing_path
+        ));
+    }
+
+    #[test]
+    fn test_build_reference_graph() {
+        let source = MemorySource::new();
+        source.insert("prefabs.bundle", PREFABS_BUNDLE.to_vec());
+        let mut viewer = UnityAssetViewer::new();
+        viewer
+            .add_asset_source(Arc::new(source), "", false)
+            .unwrap();
+        let serialized_file_id = viewer.cab_maps[PREFABS_CAB];
+        let id = |path_id| ObjectId::new(serialized_file_id, path_id);
+
+        let graph = viewer.build_reference_graph();
+        assert!(graph.errors.is_empty());
+        assert!(graph.unresolved_references.is_empty());
+        let references = |path_id| graph.get_references(id(path_id)).collect::<Vec<_>>();
+        assert_eq!(references(PLAYER), [id(PLAYER_TRANSFORM)]);
+        // m_Father of the player transform is null and not a reference.
+        assert_eq!(
+            references(PLAYER_TRANSFORM),
+            [id(PLAYER), id(WEAPON_TRANSFORM)]
+        );
+        assert_eq!(
+            references(WEAPON_TRANSFORM),
+            [id(WEAPON), id(PLAYER_TRANSFORM)]
+        );
+        assert_eq!(
+            graph.get_dependencies(id(PLAYER)),
+            BTreeSet::from([
+                id(PLAYER),
+                id(PLAYER_TRANSFORM),
+                id(WEAPON),
+                id(WEAPON_TRANSFORM)
+            ])
+        );
+        assert_eq!(
+            graph.get_unreachable([id(PLAYER)]),
+            [id(PICKUP_TRANSFORM), id(ASSET_BUNDLE), id(PICKUP)]
+        );
+        assert!(graph.get_unreachable([id(ASSET_BUNDLE)]).is_empty());
+    }
+
+    #[test]
+    fn test_lazy_container_index() {
+        let mut viewer = UnityAssetViewer::new_lazy(4);
+        let source = MemorySource::new();
+        source.insert("prefabs.bundle", PREFABS_BUNDLE.to_vec());
+        viewer
+            .add_asset_source(Arc::new(source), "", false)
+            .unwrap();
+        let serialized_file_id = viewer.cab_maps[PREFABS_CAB];
+        let container_names: Vec<_> = viewer
+            .iter_containers()
+            .map(|entry| (entry.container_name.clone(), entry.get_path_id().unwrap()))
+            .collect();
+        assert_eq!(
+            container_names,
+            [
+                ("assets/prefabs/pickup.prefab".to_owned(), PICKUP),
+                ("assets/prefabs/player.prefab".to_owned(), PLAYER),
+            ]
+        );
+        let is_parsed = |viewer: &UnityAssetViewer, serialized_file_id| {
+            viewer.serialized_file_map[&serialized_file_id]
+                .serialized_file
+                .get()
+                .is_some()
+        };
+        assert!(!is_parsed(&viewer, serialized_file_id));
+
+        // the index is kept and get the containers of the new bundle.
+        let cab_name = format!("CAB-{}", "a".repeat(32));
+        let source = MemorySource::new();
+        source.insert(
+            "copy.bundle",
+            build_unity_fs(&[(&cab_name, &read_prefabs_cab())]),
+        );
+        viewer
+            .add_asset_source(Arc::new(source), "", false)
+            .unwrap();
+        let copy_serialized_file_id = viewer.cab_maps[&cab_name];
+        let serialized_file_ids: Vec<_> = viewer
+            .get_container_entries(&"assets/prefabs/player.prefab".to_owned())
+            .map(|entry| entry.serialized_file_id)
+            .collect();
+        assert_eq!(
+            serialized_file_ids,
+            [serialized_file_id, copy_serialized_file_id]
+        );
+        assert!(!is_parsed(&viewer, copy_serialized_file_id));
+        assert_eq!(
+            viewer
+                .get_container_name_by_serialized_file_id_and_path_id(
+                    copy_serialized_file_id,
+                    PICKUP
+                )
+                .unwrap(),
+            "assets/prefabs/pickup.prefab"
+        );
+    }
+
+    #[test]
+    fn test_bundle_nodes_without_cab_name() {
+        for lazy in [false, true] {
+            let mut viewer = if lazy {
+                UnityAssetViewer::new_lazy(4)
+            } else {
+                UnityAssetViewer::new()
+            };
+            let source = MemorySource::new();
+            source.insert("Game_Data/data.unity3d", DATA_UNITY3D.to_vec());
+            let report = viewer
+                .add_asset_source(Arc::new(source), "Game_Data", false)
+                .unwrap();
+            assert!(!report.has_error());
+            let level0 = viewer.cab_maps["level0"];
+            let sharedassets0 = viewer.cab_maps["sharedassets0.assets"];
+            assert_eq!(report.files[0].serialized_file_ids, [level0, sharedassets0]);
+
+            assert_eq!(viewer.get_object_class_id(level0, 1), Some(1));
+            assert_eq!(viewer.get_object_class_id(level0, 2), Some(4));
+            assert_eq!(viewer.get_object_class_id(sharedassets0, 1), Some(150));
+            assert_eq!(viewer.get_object_class_id(sharedassets0, 2), Some(49));
+
+            let externals = viewer
+                .get_serialized_file_by_id(level0)
+                .unwrap()
+                .get_externals()
+                .into_owned();
+            let external = viewer
+                .get_serialized_file_by_path(&externals[0].path.to_string())
+                .unwrap();
+            assert_eq!(external.get_serialized_file_id(), sharedassets0);
+        }
+    }
+
+    #[test]
+    fn test_strict_failed_file() {
+        let cab = read_prefabs_cab();
+        let source = Arc::new(MemorySource::new());
+        source.insert("a.bundle", PREFABS_BUNDLE.to_vec());
+        // the second serialized file is cut after its header.
+        let cab_names = ["b", "c"].map(|name| format!("CAB-{}", name.repeat(32)));
+        source.insert(
+            "b.bundle",
+            build_unity_fs(&[(&cab_names[0], &cab), (&cab_names[1], &cab[..64])]),
+        );
+
+        let mut viewer = UnityAssetViewer::new();
+        viewer.set_strict(true);
+        let error = viewer
+            .add_asset_source(source.clone(), "", false)
+            .unwrap_err();
+        assert!(
+            matches!(error, Error::FileLoadError { path, .. } if path == Path::new("b.bundle"))
+        );
+        assert_eq!(viewer.cab_maps.keys().collect::<Vec<_>>(), [PREFABS_CAB]);
+        assert_eq!(
+            viewer.get_serialized_file_ids(),
+            [viewer.cab_maps[PREFABS_CAB]]
+        );
+        assert_eq!(viewer.unity_fs_map.len(), 1);
+        assert!(viewer
+            .resource_node_map
+            .keys()
+            .all(|archive_path| archive_path.contains(PREFABS_CAB)));
+        assert_eq!(
+            viewer.file_records.keys().collect::<Vec<_>>(),
+            [Path::new("a.bundle")]
+        );
+
+        // not strict, the failed file is recorded with nothing added.
+        let mut viewer = UnityAssetViewer::new();
+        let report = viewer.add_asset_source(source, "", false).unwrap();
+        let failed_file = report.get_failed_files().next().unwrap();
+        assert_eq!(failed_file.path, Path::new("b.bundle"));
+        assert!(failed_file.unity_fs_id.is_none() && failed_file.serialized_file_ids.is_empty());
+        assert_eq!(viewer.get_serialized_file_ids().len(), 1);
+        assert_eq!(viewer.file_records.len(), 2);
+    }
+
+    #[test]
+    fn test_unload_serialized_files() {
+        let mut viewer = UnityAssetViewer::new_lazy(4);
+        let cab_names = ["a", "b", "c"].map(|name| format!("CAB-{}", name.repeat(32)));
+        let source = MemorySource::new();
+        for cab_name in &cab_names {
+            source.insert(
+                &format!("{}.bundle", cab_name),
+                build_unity_fs(&[(cab_name, &read_prefabs_cab())]),
+            );
+        }
+        viewer
+            .add_asset_source(Arc::new(source), "", false)
+            .unwrap();
+        let serialized_file_ids = cab_names.clone().map(|cab_name| viewer.cab_maps[&cab_name]);
+        for serialized_file_id in [2, 0, 1].map(|index| serialized_file_ids[index]) {
+            viewer
+                .get_serialized_file_by_id(serialized_file_id)
+                .unwrap();
+        }
+
+        assert_eq!(viewer.unload_serialized_files(1), 2);
+        let is_parsed = |viewer: &UnityAssetViewer, serialized_file_id| {
+            viewer.serialized_file_map[&serialized_file_id]
+                .serialized_file
+                .get()
+                .is_some()
+        };
+        assert_eq!(
+            serialized_file_ids.map(|serialized_file_id| is_parsed(&viewer, serialized_file_id)),
+            [false, true, false]
+        );
+        assert_eq!(viewer.unload_serialized_files(1), 0);
+
+        // unloaded files are parsed again.
+        let object = viewer
+            .get_type_tree_object_by_serialized_file_id_and_path_id(serialized_file_ids[2], PLAYER)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            String::try_cast_from(&object, "/Base/m_Name").unwrap(),
+            "Player"
+        );
+        assert!(is_parsed(&viewer, serialized_file_ids[2]));
+        assert_eq!(viewer.unload_serialized_files(0), 2);
+
+        // files added by add_serialized_file can not be read again.
+        let mut viewer = UnityAssetViewer::new_lazy(4);
+        let serialized_file_id = viewer
+            .add_serialized_file(Box::new(Cursor::new(read_prefabs_cab())), None)
+            .unwrap();
+        assert_eq!(viewer.unload_serialized_files(0), 0);
+        assert!(is_parsed(&viewer, serialized_file_id));
+    }
+
+    #[test]
+    fn test_viewer_index_reopen() {
+        let source = Arc::new(MemorySource::new());
+        source.insert("prefabs.bundle", PREFABS_BUNDLE.to_vec());
+        source.insert("a.resS", vec![0; 16]);
+        source.insert("b.bin", vec![1; 16]);
+
+        let mut viewer = UnityAssetViewer::new_lazy(4);
+        let report = viewer.add_asset_source(source.clone(), "", false).unwrap();
+        assert!(report.files.iter().all(|file| !file.from_index));
+        let mut index = Cursor::new(Vec::new());
+        viewer.write_index(&mut index).unwrap();
+
+        source.insert("b.bin", vec![1; 32]);
+        index.set_position(0);
+        assert!(UnityAssetViewer::new().read_index(&mut index).is_err());
+        let mut viewer = UnityAssetViewer::new_lazy(4);
+        viewer.read_index(&mut index).unwrap();
+        let report = viewer.add_asset_source(source, "", false).unwrap();
+        let files: Vec<_> = report
+            .files
+            .iter()
+            .map(|file| (file.path.clone(), file.kind, file.from_index))
+            .collect();
+        assert_eq!(
+            files,
+            [
+                ("a.resS".into(), FileKind::Resource, true),
+                ("b.bin".into(), FileKind::Unknown, false),
+                ("prefabs.bundle".into(), FileKind::UnityFS, true),
+            ]
+        );
+        assert_eq!(
+            viewer.get_object_class_id(viewer.cab_maps[PREFABS_CAB], PLAYER),
+            Some(1)
+        );
+        let objects = viewer
+            .get_object_class_ids(viewer.cab_maps[PREFABS_CAB])
+            .unwrap();
+        let class_ids: Vec<_> = objects.iter().map(|(_, class_id)| *class_id).collect();
+        assert_eq!(class_ids, [1, 1, 4, 142, 4, 1, 4]);
+        let mut parsed_viewer = UnityAssetViewer::new();
+        parsed_viewer
+            .add_bundle_file(Box::new(Cursor::new(PREFABS_BUNDLE)), None)
+            .unwrap();
+        assert_eq!(
+            parsed_viewer
+                .get_object_class_ids(parsed_viewer.cab_maps[PREFABS_CAB])
+                .unwrap(),
+            objects
+        );
+        let containers: Vec<_> = viewer
+            .iter_containers()
+            .map(|entry| entry.container_name.clone())
+            .collect();
+        assert_eq!(
+            containers,
+            [
+                "assets/prefabs/pickup.prefab",
+                "assets/prefabs/player.prefab"
+            ]
+        );
+        // the index answer these without opening the bundle.
+        assert!(viewer
+            .unity_fs_map
+            .values()
+            .all(|entry| entry.unity_fs.get().is_none()));
+    }
+
+    #[test]
+    fn test_index_left_out_files() {
+        let cab_name = format!("CAB-{}", "a".repeat(32));
+        let source = Arc::new(MemorySource::new());
+        source.insert("prefabs.bundle", PREFABS_BUNDLE.to_vec());
+        source.insert(
+            "broken.bundle",
+            build_unity_fs(&[(&cab_name, &read_prefabs_cab()[..64])]),
+        );
+        let mut viewer = UnityAssetViewer::new_lazy(4);
+        let report = viewer.add_asset_source(source, "", false).unwrap();
+        assert!(!report.has_error());
+
+        let mut index = Cursor::new(Vec::new());
+        let report = viewer.write_index(&mut index).unwrap();
+        let failed_files: Vec<_> = report
+            .get_failed_files()
+            .map(|file| (file.path.clone(), file.kind))
+            .collect();
+        assert_eq!(
+            failed_files,
+            [(PathBuf::from("broken.bundle"), FileKind::UnityFS)]
+        );
+        index.set_position(0);
+        let index = ViewerIndex::read_from(&mut index).unwrap();
+        let paths: Vec<_> = index.files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, ["prefabs.bundle"]);
+
+        viewer.set_strict(true);
+        assert!(matches!(
+            viewer.write_index(&mut Cursor::new(Vec::new())),
+            Err(Error::FileLoadError { path, .. }) if path == Path::new("broken.bundle")
+        ));
+    }
+
+    #[test]
+    fn test_reload_changed_files() {
+        let source = Arc::new(MemorySource::new());
+        for name in ["a.bin", "b.bin", "c.bin"] {
+            source.insert(name, vec![0; 16]);
+        }
+        source.insert("prefabs.bundle", PREFABS_BUNDLE.to_vec());
+
+        let mut viewer = UnityAssetViewer::new();
+        viewer.add_asset_source(source.clone(), "", false).unwrap();
+        assert!(viewer.reload_changed_files().unwrap().files.is_empty());
+        let serialized_file_id = viewer.cab_maps[PREFABS_CAB];
+
+        source.insert("a.bin", vec![0; 32]);
+        source.remove("b.bin");
+        let cab_name = format!("CAB-{}", "a".repeat(32));
+        source.insert(
+            "prefabs.bundle",
+            build_unity_fs(&[(&cab_name, &read_prefabs_cab())]),
+        );
+        let report = viewer.reload_changed_files().unwrap();
+        let paths: Vec<_> = report.files.iter().map(|file| file.path.clone()).collect();
+        assert_eq!(paths, [PathBuf::from("a.bin"), "prefabs.bundle".into()]);
+        assert!(viewer.reload_file("b.bin").is_err());
+        assert!(!viewer.reload_file("c.bin").unwrap().has_error());
+
+        // the serialized file of the old bundle is removed.
+        assert!(!viewer.cab_maps.contains_key(PREFABS_CAB));
+        assert!(viewer
+            .get_serialized_file_by_id(serialized_file_id)
+            .is_err());
+        let object = viewer
+            .get_type_tree_object_by_serialized_file_id_and_path_id(
+                viewer.cab_maps[&cab_name],
+                PLAYER,
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            String::try_cast_from(&object, "/Base/m_Name").unwrap(),
+            "Player"
+        );
+    }
+
+    #[test]
+    fn test_mono_behaviour_script_fields() {
+        /// Base type trees of MonoScript and MonoBehaviour, as a type tree package would give.
+        struct ScriptTypeTrees(Vec<TypeTreeObjectBinReadClassArgs>);
+        impl TypeTreeProvider for ScriptTypeTrees {
+            fn get_type_object_args_by_version_class_id(
+                &self,
+                version: &str,
+                class_id: i32,
+            ) -> Option<TypeTreeObjectBinReadClassArgs> {
+                if version != "2018.4.36f1" {
+                    return None;
                 }
-            }
-            let path = PathBuf::from(".").join(&file_name);
-            if let Ok(file) = OpenOptions::new().read(true).open(path) {
-                return Some(Box::new(BufReader::new(file)));
+                self.0
+                    .iter()
+                    .find(|args| args.get_class_id() == class_id)
+                    .cloned()
             }
         }
-    }
 
-    None
+        let script_types =
+            SerializedFile::read(Box::new(Cursor::new(SCRIPT_TYPES_ASSETS)), 0, None).unwrap();
+        let base_class_args = [1, 2].map(|path_id| {
+            script_types
+                .get_type_object_args_by_path_id(path_id)
+                .unwrap()
+        });
+        provider::add_type_tree_provider(Box::new(ScriptTypeTrees(base_class_args.to_vec())));
+        let mut managed = ManagedAssemblyTypeTreeProvider::new();
+        managed.add_assembly(ManagedAssembly::read(&mut Cursor::new(GAME_DLL)).unwrap());
+        provider::add_type_tree_provider(Box::new(managed));
+
+        let mut viewer = UnityAssetViewer::new();
+        let serialized_file_id = viewer
+            .add_serialized_file(Box::new(Cursor::new(SCRIPTS_ASSETS)), None)
+            .unwrap();
+        let serialized_file = viewer
+            .get_serialized_file_by_id(serialized_file_id)
+            .unwrap();
+        assert!(!serialized_file.get_enable_type_tree());
+        // without the script the fields after the base are not known.
+        let base = serialized_file
+            .get_tt_object_by_path_id(2)
+            .unwrap()
+            .unwrap();
+        assert!(i32::try_cast_from(&base, "/Base/level").is_err());
+
+        let hero = TypeTreeObjectRef::from(
+            viewer
+                .get_type_tree_object_by_serialized_file_id_and_path_id(serialized_file_id, 2)
+                .unwrap()
+                .unwrap(),
+        );
+        assert_eq!(i32::try_cast_from(&hero, "/Base/level").unwrap(), 7);
+        assert_eq!(
+            String::try_cast_from(&hero, "/Base/title").unwrap(),
+            "Knight"
+        );
+        assert_eq!(f32::try_cast_from(&hero, "/Base/speed").unwrap(), 2.5);
+        let scores: Vec<i32> = (0..3)
+            .map(|index| {
+                i32::try_cast_from(&hero, format!("/Base/scores/Array/{index}").as_str()).unwrap()
+            })
+            .collect();
+        assert_eq!(scores, [3, 5, 8]);
+        assert_eq!(
+            i32::try_cast_from(&hero, "/Base/items/Array/1/id").unwrap(),
+            2
+        );
+        assert!(i32::try_cast_from(&hero, "/Base/hidden").is_err());
+    }
 }
@@ -1,3 +1,4 @@
+use crate::load_report::{detect_file_kind, FileKind};
 use crate::until::binrw_parser::position_parser;
 use binrw::{binrw, BinResult, Endian, NullString};
 use binrw::{io::Cursor, BinRead};
@@ -5,8 +6,9 @@ use lz4::block::decompress;
 use modular_bitfield::specifiers::{B22, B9};
 use modular_bitfield::{bitfield, BitfieldSpecifier};
 use num_enum::TryFromPrimitive;
-use std::collections::BTreeMap;
+use std::collections::VecDeque;
 use std::io::{prelude::*, ErrorKind, SeekFrom};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 
 pub trait UnityResource: std::io::Read + std::io::Seek {}
@@ -101,7 +103,7 @@ impl UnityFS {
                     storage_blocks: self.content.blocks_info.storage_blocks.clone(),
                     storage_blocks_start_positions: self.storage_blocks_start_positions.clone(),
                     storage_block_position: self.content.position,
-                    storage_blocks_cache: BTreeMap::new(),
+                    storage_blocks_cache: VecDeque::new(),
                     node_info: node.clone(),
                     current_position: 0,
                 });
@@ -159,6 +161,25 @@ impl UnityFS {
         paths
     }
 
+    /// Paths of nodes which are serialized files, the CAB of an asset bundle
+    /// or nodes detected by header like level0 and sharedassets0.assets of data.unity3d.
+    pub fn get_serialized_file_paths(&self) -> Vec<String> {
+        let mut paths = vec![];
+        for file in &self.content.blocks_info.directory_info {
+            let path = file.path();
+            let is_cab = path.starts_with("CAB-") && (path.len() == 36);
+            if is_cab
+                || self.get_file_reader_by_path(&path).is_some_and(|mut node| {
+                    detect_file_kind(&mut node, Some(Path::new(&path)))
+                        .is_ok_and(|kind| kind == FileKind::SerializedFile)
+                })
+            {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
     pub fn get_file_paths(&self) -> Vec<String> {
         let mut paths = vec![];
         for file in &self.content.blocks_info.directory_info {
@@ -289,6 +310,9 @@ fn blocks_info_parser<R: Read + Seek>(
     BlocksInfo::read(&mut blocks_info_reader)
 }
 
+/// Decompressed storage blocks kept by a node reader, least recently used are dropped first.
+const MAX_CACHED_STORAGE_BLOCKS: usize = 4;
+
 #[derive(Clone)]
 pub struct UnityFSNode {
     file_reader: Arc<Mutex<Box<dyn UnityResource + Send>>>,
@@ -297,11 +321,19 @@ pub struct UnityFSNode {
     storage_block_position: u64,
     node_info: Node,
     current_position: u64,
-    storage_blocks_cache: BTreeMap<u64, Vec<u8>>,
+    /// Uncompressed offset and data of recently read storage blocks, most recent last.
+    storage_blocks_cache: VecDeque<(u64, Vec<u8>)>,
 }
 
 impl Read for UnityFSNode {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // do not read the data of the next node.
+        let remaining_size = (self.node_info.size as u64).saturating_sub(self.current_position);
+        if remaining_size == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+        let buf_len = buf.len().min(remaining_size as usize);
+        let buf = &mut buf[..buf_len];
         let uncompressed_data_read_start_offset =
             (self.node_info.offset as u64) + self.current_position;
         let storage_blocks_index = match self.storage_blocks_start_positions.binary_search_by_key(
@@ -318,10 +350,14 @@ impl Read for UnityFSNode {
             if (uncompressed_data_offset + (sb.uncompressed_size as u64))
                 >= ((self.node_info.offset as u64) + self.current_position)
             {
-                let blocks_info_uncompressedd_stream = if let Some(cache_block) =
-                    self.storage_blocks_cache.get(&uncompressed_data_offset)
-                {
-                    cache_block
+                let cache_index = self
+                    .storage_blocks_cache
+                    .iter()
+                    .position(|(offset, _)| *offset == uncompressed_data_offset);
+                let blocks_info_uncompressedd_stream = if let Some(cache_index) = cache_index {
+                    let cache_block = self.storage_blocks_cache.remove(cache_index).unwrap();
+                    self.storage_blocks_cache.push_back(cache_block);
+                    &self.storage_blocks_cache.back().unwrap().1
                 } else {
                     let mut blocks_infocompressedd_stream = vec![0u8; sb.compressed_size as usize];
                     if let Ok(mut file_reader) = self.file_reader.lock() {
@@ -338,11 +374,12 @@ impl Read for UnityFSNode {
                         sb.flags.compression_type(),
                         blocks_infocompressedd_stream,
                     )?;
+                    if self.storage_blocks_cache.len() >= MAX_CACHED_STORAGE_BLOCKS {
+                        self.storage_blocks_cache.pop_front();
+                    }
                     self.storage_blocks_cache
-                        .insert(uncompressed_data_offset, blocks_info_uncompressedd_stream);
-                    self.storage_blocks_cache
-                        .get(&uncompressed_data_offset)
-                        .unwrap()
+                        .push_back((uncompressed_data_offset, blocks_info_uncompressedd_stream));
+                    &self.storage_blocks_cache.back().unwrap().1
                 };
 
                 if uncompressed_data_offset
@@ -400,3 +437,31 @@ impl Seek for UnityFSNode {
         Ok(self.current_position)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::build_unity_fs_with_block_size;
+
+    #[test]
+    fn test_node_block_cache() {
+        let node_data: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        let bundle = build_unity_fs_with_block_size(&[("a", &[1u8; 50]), ("b", &node_data)], 64);
+        let unity_fs = UnityFS::read(Box::new(Cursor::new(bundle)), None).unwrap();
+        let mut node = unity_fs.get_file_reader_by_path(&"b".to_owned()).unwrap();
+
+        let mut data = Vec::new();
+        node.read_to_end(&mut data).unwrap();
+        assert_eq!(data, node_data);
+        assert_eq!(node.storage_blocks_cache.len(), MAX_CACHED_STORAGE_BLOCKS);
+
+        // blocks dropped from the cache are read again.
+        for offset in [10u64, 900, 300, 5] {
+            let mut buf = [0u8; 100];
+            node.seek(SeekFrom::Start(offset)).unwrap();
+            node.read_exact(&mut buf).unwrap();
+            assert_eq!(buf, node_data[offset as usize..offset as usize + 100]);
+        }
+        assert_eq!(node.storage_blocks_cache.len(), MAX_CACHED_STORAGE_BLOCKS);
+    }
+}
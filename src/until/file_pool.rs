@@ -0,0 +1,148 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::{File, OpenOptions},
+    io::{Error, ErrorKind, Read, Result, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Keep at most max_open_files files open, least recently used file is closed first.
+#[derive(Debug)]
+pub struct FilePool {
+    max_open_files: usize,
+    open_files: HashMap<u64, File>,
+    recently_used: VecDeque<u64>,
+    next_id: u64,
+}
+
+impl FilePool {
+    pub fn new(max_open_files: usize) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(FilePool {
+            max_open_files: max_open_files.max(1),
+            open_files: HashMap::new(),
+            recently_used: VecDeque::new(),
+            next_id: 0,
+        }))
+    }
+
+    fn get_or_open(&mut self, id: u64, path: &Path) -> Result<&mut File> {
+        if self.open_files.contains_key(&id) {
+            self.recently_used.retain(|i| *i != id);
+        } else {
+            while self.open_files.len() >= self.max_open_files {
+                match self.recently_used.pop_front() {
+                    Some(least_recently_used) => {
+                        self.open_files.remove(&least_recently_used);
+                    }
+                    None => break,
+                }
+            }
+            self.open_files
+                .insert(id, OpenOptions::new().read(true).open(path)?);
+        }
+        self.recently_used.push_back(id);
+        self.open_files
+            .get_mut(&id)
+            .ok_or_else(|| Error::from(ErrorKind::NotFound))
+    }
+
+    fn close(&mut self, id: u64) {
+        self.open_files.remove(&id);
+        self.recently_used.retain(|i| *i != id);
+    }
+}
+
+/// File reader which reopen the file from pool when it has been closed.
+#[derive(Debug)]
+pub struct PooledFile {
+    pool: Arc<Mutex<FilePool>>,
+    id: u64,
+    path: PathBuf,
+    position: u64,
+    len: u64,
+}
+
+impl PooledFile {
+    pub fn open<P: AsRef<Path>>(pool: &Arc<Mutex<FilePool>>, path: P) -> Result<Self> {
+        let len = std::fs::metadata(&path)?.len();
+        let id = {
+            let mut pool = pool.lock().map_err(|_| Error::from(ErrorKind::Other))?;
+            pool.next_id += 1;
+            pool.next_id
+        };
+        Ok(PooledFile {
+            pool: pool.clone(),
+            id,
+            path: path.as_ref().to_path_buf(),
+            position: 0,
+            len,
+        })
+    }
+}
+
+impl Read for PooledFile {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut pool = self
+            .pool
+            .lock()
+            .map_err(|_| Error::from(ErrorKind::Other))?;
+        let file = pool.get_or_open(self.id, &self.path)?;
+        file.seek(SeekFrom::Start(self.position))?;
+        let size = file.read(buf)?;
+        self.position += size as u64;
+        Ok(size)
+    }
+}
+
+impl Seek for PooledFile {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        self.position = position.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
+
+impl Drop for PooledFile {
+    fn drop(&mut self) {
+        if let Ok(mut pool) = self.pool.lock() {
+            pool.close(self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_pool() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = FilePool::new(1);
+        let mut files = Vec::new();
+        for i in 0..3u8 {
+            let path = dir.path().join(format!("{i}"));
+            std::fs::write(&path, [i; 8]).unwrap();
+            files.push(PooledFile::open(&pool, &path).unwrap());
+        }
+        for _ in 0..2 {
+            for (i, file) in files.iter_mut().enumerate() {
+                let mut buf = [0u8; 4];
+                file.read_exact(&mut buf).unwrap();
+                assert_eq!(buf, [i as u8; 4]);
+                assert_eq!(pool.lock().unwrap().open_files.len(), 1);
+            }
+        }
+        assert_eq!(files[0].seek(SeekFrom::End(-2)).unwrap(), 6);
+        drop(files);
+        assert!(pool.lock().unwrap().open_files.is_empty());
+    }
+}
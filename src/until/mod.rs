@@ -1,4 +1,5 @@
 pub mod binrw_parser;
+pub mod file_pool;
 
 use std::error::Error;
 
@@ -21,6 +22,7 @@ impl UnityVersion {
         }
     }
 
+    /// Version like 2018.4.36f1, an empty or broken number is an error.
     pub fn from_str(version: &str) -> Result<Self, Box<dyn Error>> {
         static BUILD_TYPE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"([^\d.])").unwrap());
         static VERSION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\D").unwrap());
@@ -28,8 +30,8 @@ impl UnityVersion {
         Ok(UnityVersion {
             version: VERSION_REGEX
                 .split(version)
-                .map(|d| d.parse::<u32>().unwrap())
-                .collect(),
+                .map(|d| d.parse::<u32>())
+                .collect::<Result<_, _>>()?,
             build_type: BUILD_TYPE_REGEX
                 .captures(version)
                 .and_then(|c| c.get(0))
@@ -63,3 +65,21 @@ impl From<&Endian> for binrw::Endian {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unity_version_from_str() {
+        let version = UnityVersion::from_str("2018.4.36f1").unwrap();
+        assert_eq!(
+            version,
+            UnityVersion::new(vec![2018, 4, 36, 1], Some("f".to_owned()))
+        );
+        assert!(version < UnityVersion::from_str("2019.1.0a1").unwrap());
+        for version in ["", "2018.x", "2018..4", "99999999999.1"] {
+            assert!(UnityVersion::from_str(version).is_err(), "{version:?}");
+        }
+    }
+}
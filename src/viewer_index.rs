@@ -0,0 +1,267 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{BufReader, BufWriter, ErrorKind, Read, Seek, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use binrw::{binrw, BinRead, BinResult, BinWrite};
+
+use crate::{error::ReadResult, load_report::FileKind};
+
+/// Bumped when the layout change, older index files are rejected.
+pub const VIEWER_INDEX_VERSION: u32 = 1;
+
+/// What a viewer know about the files it read, saved to skip parsing unchanged files on reopen.
+#[binrw]
+#[brw(little, magic = b"IOUNITYINDEX")]
+#[derive(Debug, Default)]
+pub struct ViewerIndex {
+    #[br(temp, assert(version == VIEWER_INDEX_VERSION, "unsupported viewer index version {}", version))]
+    #[bw(calc = VIEWER_INDEX_VERSION)]
+    version: u32,
+    #[br(temp)]
+    #[bw(calc = files.len() as u32)]
+    file_count: u32,
+    #[br(count = file_count)]
+    pub files: Vec<IndexedFile>,
+}
+
+impl ViewerIndex {
+    pub fn read_from<R: Read + Seek>(reader: &mut R) -> ReadResult<Self> {
+        Ok(ViewerIndex::read(reader)?)
+    }
+
+    pub fn write_to<W: Write + Seek>(&self, writer: &mut W) -> ReadResult<()> {
+        self.write(writer)?;
+        Ok(())
+    }
+
+    pub fn read_from_path<P: AsRef<Path>>(path: P) -> ReadResult<Self> {
+        let file = OpenOptions::new().read(true).open(path)?;
+        Ok(ViewerIndex::read(&mut BufReader::new(file))?)
+    }
+
+    /// The index is written to a temp file next to path and renamed,
+    /// a failed write keep the index written before.
+    pub fn write_to_path<P: AsRef<Path>>(&self, path: P) -> ReadResult<()> {
+        let path = path.as_ref();
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+        if let Err(err) = self.write_to_file(&temp_path) {
+            fs::remove_file(&temp_path).ok();
+            return Err(err);
+        }
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    fn write_to_file(&self, path: &Path) -> ReadResult<()> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        let mut writer = BufWriter::new(file);
+        self.write(&mut writer)?;
+        let file = writer.into_inner().map_err(|err| err.into_error())?;
+        file.sync_all()?;
+        Ok(())
+    }
+}
+
+/// A file of an asset source, it is up to date while its size and modified time are the same.
+#[binrw]
+#[derive(Debug, Clone)]
+pub struct IndexedFile {
+    #[br(parse_with = read_string)]
+    #[bw(write_with = write_string)]
+    pub path: String,
+    pub size: u64,
+    #[br(map = |(secs, nanos): (u64, u32)| UNIX_EPOCH + Duration::new(secs, nanos))]
+    #[bw(map = |time: &SystemTime| {
+        let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        (duration.as_secs(), duration.subsec_nanos())
+    })]
+    pub modified: SystemTime,
+    pub kind: FileKind,
+    /// Nodes of a bundle, including its serialized files.
+    #[br(temp)]
+    #[bw(calc = node_paths.len() as u32)]
+    node_path_count: u32,
+    #[br(parse_with = read_strings, args(node_path_count))]
+    #[bw(write_with = write_strings)]
+    pub node_paths: Vec<String>,
+    #[br(temp)]
+    #[bw(calc = serialized_files.len() as u32)]
+    serialized_file_count: u32,
+    #[br(count = serialized_file_count)]
+    pub serialized_files: Vec<IndexedSerializedFile>,
+}
+
+impl IndexedFile {
+    pub fn is_up_to_date(&self, size: u64, modified: SystemTime) -> bool {
+        self.size == size && self.modified == modified
+    }
+}
+
+/// Object table and containers of a serialized file.
+#[binrw]
+#[derive(Debug, Clone, Default)]
+pub struct IndexedSerializedFile {
+    /// Path of the node in its bundle, empty for a serialized file read directly.
+    #[br(parse_with = read_string)]
+    #[bw(write_with = write_string)]
+    pub path: String,
+    /// File names of externals, file id n refer to the n - 1 external.
+    #[br(temp)]
+    #[bw(calc = externals.len() as u32)]
+    external_count: u32,
+    #[br(parse_with = read_strings, args(external_count))]
+    #[bw(write_with = write_strings)]
+    pub externals: Vec<String>,
+    #[br(temp)]
+    #[bw(calc = objects.len() as u32)]
+    object_count: u32,
+    #[br(count = object_count)]
+    pub objects: Vec<IndexedObject>,
+    #[br(temp)]
+    #[bw(calc = containers.len() as u32)]
+    container_count: u32,
+    #[br(count = container_count)]
+    pub containers: Vec<IndexedContainer>,
+}
+
+#[binrw]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexedObject {
+    pub path_id: i64,
+    pub class_id: i32,
+}
+
+/// m_Container entry of an AssetBundle or ResourceManager.
+#[binrw]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedContainer {
+    #[br(parse_with = read_string)]
+    #[bw(write_with = write_string)]
+    pub name: String,
+    pub file_id: i32,
+    pub path_id: i64,
+}
+
+/// The size is checked by reading at most the bytes left, a broken size do not allocate it.
+#[binrw::parser(reader, endian)]
+fn read_string() -> BinResult<String> {
+    let size = u32::read_options(reader, endian, ())?;
+    let pos = reader.stream_position()?;
+    let mut data = Vec::new();
+    reader.take(size as u64).read_to_end(&mut data)?;
+    if data.len() != size as usize {
+        return Err(binrw::Error::Io(std::io::Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "string size {size} at {pos} is larger than the {} bytes left",
+                data.len()
+            ),
+        )));
+    }
+    String::from_utf8(data).map_err(|err| binrw::Error::Custom {
+        pos,
+        err: Box::new(err),
+    })
+}
+
+#[binrw::writer(writer, endian)]
+fn write_string(value: &String) -> BinResult<()> {
+    (value.len() as u32).write_options(writer, endian, ())?;
+    value.as_bytes().write_options(writer, endian, ())
+}
+
+#[binrw::parser(reader, endian)]
+fn read_strings(count: u32) -> BinResult<Vec<String>> {
+    (0..count)
+        .map(|_| read_string(reader, endian, ()))
+        .collect()
+}
+
+#[binrw::writer(writer, endian)]
+fn write_strings(values: &Vec<String>) -> BinResult<()> {
+    for value in values {
+        write_string(value, writer, endian, ())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_viewer_index() {
+        let modified = UNIX_EPOCH + Duration::new(1_700_000_000, 123);
+        let index = ViewerIndex {
+            files: vec![IndexedFile {
+                path: "Data/data.unity3d".to_owned(),
+                size: 1024,
+                modified,
+                kind: FileKind::UnityFS,
+                node_paths: vec!["CAB-0".to_owned(), "CAB-0.resS".to_owned()],
+                serialized_files: vec![IndexedSerializedFile {
+                    path: "CAB-0".to_owned(),
+                    externals: vec!["unity default resources".to_owned()],
+                    objects: vec![IndexedObject {
+                        path_id: 1,
+                        class_id: 142,
+                    }],
+                    containers: vec![IndexedContainer {
+                        name: "assets/icon.png".to_owned(),
+                        file_id: 0,
+                        path_id: -42,
+                    }],
+                }],
+            }],
+        };
+        let mut data = Cursor::new(Vec::new());
+        index.write(&mut data).unwrap();
+        data.set_position(0);
+        let index = ViewerIndex::read(&mut data).unwrap();
+
+        let file = &index.files[0];
+        assert!(file.is_up_to_date(1024, modified));
+        assert!(!file.is_up_to_date(1024, modified + Duration::from_secs(1)));
+        assert_eq!(file.node_paths, ["CAB-0", "CAB-0.resS"]);
+        assert_eq!(file.serialized_files[0].containers[0].path_id, -42);
+        assert_eq!(
+            file.serialized_files[0].externals,
+            ["unity default resources"]
+        );
+
+        // size of the last container name is broken.
+        let data = data.into_inner();
+        let mut broken = data.clone();
+        let name_size = broken.len() - 12 - "assets/icon.png".len() - 4;
+        broken[name_size..name_size + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let err = ViewerIndex::read(&mut Cursor::new(broken)).unwrap_err();
+        let binrw::Error::Io(err) = err.root_cause() else {
+            panic!("broken string size is not an io error");
+        };
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        let mut data = data;
+        data[12] = 0;
+        assert!(ViewerIndex::read(&mut Cursor::new(data)).is_err());
+
+        let path = std::env::temp_dir().join(format!("io_unity_{}.index", std::process::id()));
+        index.write_to_path(&path).unwrap();
+        let read_index = ViewerIndex::read_from_path(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read_index.unwrap().files[0].node_paths, file.node_paths);
+        let mut temp_path = path.into_os_string();
+        temp_path.push(".tmp");
+        assert!(!Path::new(&temp_path).exists());
+    }
+}
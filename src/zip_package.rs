@@ -0,0 +1,521 @@
+use std::{
+    collections::BTreeMap,
+    io::{Cursor, ErrorKind, Read, Seek, SeekFrom},
+    sync::{Arc, Mutex},
+};
+
+use binrw::{binrw, BinRead};
+use flate2::read::DeflateDecoder;
+
+use crate::{
+    error::{Error, ReadResult},
+    unityfs::UnityResource,
+};
+
+const COMPRESSION_METHOD_STORED: u16 = 0;
+const COMPRESSION_METHOD_DEFLATED: u16 = 8;
+const ZIP64_EXTRA_FIELD_ID: u16 = 0x0001;
+
+#[binrw]
+#[brw(little, magic = b"PK\x05\x06")]
+#[derive(Debug)]
+struct EndOfCentralDirectory {
+    disk_number: u16,
+    central_directory_disk: u16,
+    entries_on_disk: u16,
+    total_entries: u16,
+    central_directory_size: u32,
+    central_directory_offset: u32,
+    comment_length: u16,
+}
+
+#[binrw]
+#[brw(little, magic = b"PK\x06\x07")]
+#[derive(Debug)]
+struct Zip64EndOfCentralDirectoryLocator {
+    disk_number: u32,
+    end_of_central_directory_offset: u64,
+    total_disks: u32,
+}
+
+#[binrw]
+#[brw(little, magic = b"PK\x06\x06")]
+#[derive(Debug)]
+struct Zip64EndOfCentralDirectory {
+    size: u64,
+    version_made_by: u16,
+    version_needed: u16,
+    disk_number: u32,
+    central_directory_disk: u32,
+    entries_on_disk: u64,
+    total_entries: u64,
+    central_directory_size: u64,
+    central_directory_offset: u64,
+}
+
+#[binrw]
+#[brw(little, magic = b"PK\x01\x02")]
+#[derive(Debug)]
+struct CentralDirectoryHeader {
+    version_made_by: u16,
+    version_needed: u16,
+    flags: u16,
+    compression_method: u16,
+    last_modified_time: u16,
+    last_modified_date: u16,
+    crc32: u32,
+    compressed_size: u32,
+    uncompressed_size: u32,
+    file_name_length: u16,
+    extra_field_length: u16,
+    comment_length: u16,
+    disk_number_start: u16,
+    internal_attributes: u16,
+    external_attributes: u32,
+    local_header_offset: u32,
+    #[br(args { count: file_name_length.into(), inner: () })]
+    file_name: Vec<u8>,
+    #[br(args { count: extra_field_length.into(), inner: () })]
+    extra_field: Vec<u8>,
+    #[br(args { count: comment_length.into(), inner: () })]
+    comment: Vec<u8>,
+}
+
+#[binrw]
+#[brw(little, magic = b"PK\x03\x04")]
+#[derive(Debug)]
+struct LocalFileHeader {
+    version_needed: u16,
+    flags: u16,
+    compression_method: u16,
+    last_modified_time: u16,
+    last_modified_date: u16,
+    crc32: u32,
+    compressed_size: u32,
+    uncompressed_size: u32,
+    file_name_length: u16,
+    extra_field_length: u16,
+}
+
+impl CentralDirectoryHeader {
+    /// Sizes and offset saturated to 0xFFFFFFFF are stored in the zip64 extra field.
+    fn to_entry(&self) -> ZipEntry {
+        let mut uncompressed_size = self.uncompressed_size as u64;
+        let mut compressed_size = self.compressed_size as u64;
+        let mut local_header_offset = self.local_header_offset as u64;
+
+        let mut extra_field = self.extra_field.as_slice();
+        while extra_field.len() >= 4 {
+            let id = u16::from_le_bytes([extra_field[0], extra_field[1]]);
+            let size = u16::from_le_bytes([extra_field[2], extra_field[3]]) as usize;
+            let data = &extra_field[4..extra_field.len().min(4 + size)];
+            if id == ZIP64_EXTRA_FIELD_ID {
+                let mut values = data
+                    .chunks_exact(8)
+                    .map(|b| u64::from_le_bytes(b.try_into().unwrap()));
+                for value in [
+                    &mut uncompressed_size,
+                    &mut compressed_size,
+                    &mut local_header_offset,
+                ] {
+                    if *value == u32::MAX as u64 {
+                        if let Some(v) = values.next() {
+                            *value = v;
+                        }
+                    }
+                }
+            }
+            extra_field = &extra_field[extra_field.len().min(4 + size)..];
+        }
+
+        ZipEntry {
+            compression_method: self.compression_method,
+            compressed_size,
+            uncompressed_size,
+            local_header_offset,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ZipEntry {
+    pub compression_method: u16,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    local_header_offset: u64,
+}
+
+/// Zip based package like apk and obb.
+/// Stored entries are read in place, deflated entries are inflated when opened.
+pub struct ZipPackage {
+    file_reader: Arc<Mutex<Box<dyn UnityResource + Send + Sync>>>,
+    entries: BTreeMap<String, ZipEntry>,
+    /// Unity split big files into name.split0, name.split1 ... on android.
+    split_files: BTreeMap<String, Vec<String>>,
+}
+
+impl std::fmt::Debug for ZipPackage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZipPackage")
+            .field("entries", &self.entries)
+            .field("split_files", &self.split_files)
+            .finish()
+    }
+}
+
+impl ZipPackage {
+    pub fn read(mut file: Box<dyn UnityResource + Send + Sync>) -> ReadResult<ZipPackage> {
+        let (central_directory_offset, total_entries) = read_central_directory_location(&mut file)?;
+        file.seek(SeekFrom::Start(central_directory_offset))?;
+        let mut entries = BTreeMap::new();
+        for _ in 0..total_entries {
+            let header = CentralDirectoryHeader::read(&mut file)?;
+            let path = String::from_utf8_lossy(&header.file_name).to_string();
+            if !path.ends_with('/') {
+                entries.insert(path, header.to_entry());
+            }
+        }
+
+        let mut split_files: BTreeMap<String, Vec<(u32, String)>> = BTreeMap::new();
+        for path in entries.keys() {
+            if let Some((name, index)) = path.rsplit_once(".split") {
+                if let Ok(index) = index.parse() {
+                    split_files
+                        .entry(name.to_owned())
+                        .or_default()
+                        .push((index, path.clone()));
+                }
+            }
+        }
+        let split_files = split_files
+            .into_iter()
+            .filter(|(name, _)| !entries.contains_key(name))
+            .map(|(name, mut parts)| {
+                parts.sort();
+                (name, parts.into_iter().map(|(_, path)| path).collect())
+            })
+            .collect();
+
+        Ok(ZipPackage {
+            file_reader: Arc::new(Mutex::new(file)),
+            entries,
+            split_files,
+        })
+    }
+
+    /// Paths of all files, split files are listed once by their joined name instead of the parts.
+    pub fn get_file_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .entries
+            .keys()
+            .filter(|path| {
+                !path
+                    .rsplit_once(".split")
+                    .is_some_and(|(name, _)| self.split_files.contains_key(name))
+            })
+            .cloned()
+            .collect();
+        paths.extend(self.split_files.keys().cloned());
+        paths.sort();
+        paths
+    }
+
+    pub fn get_entry(&self, path: &str) -> Option<&ZipEntry> {
+        self.entries.get(path)
+    }
+
+    pub fn get_file_size(&self, path: &str) -> Option<u64> {
+        if let Some(parts) = self.split_files.get(path) {
+            return Some(
+                parts
+                    .iter()
+                    .filter_map(|part| self.entries.get(part))
+                    .map(|entry| entry.uncompressed_size)
+                    .sum(),
+            );
+        }
+        self.entries.get(path).map(|entry| entry.uncompressed_size)
+    }
+
+    fn get_entry_data_offset(&self, entry: &ZipEntry) -> ReadResult<u64> {
+        let mut file_reader = self
+            .file_reader
+            .lock()
+            .map_err(|_| std::io::Error::from(ErrorKind::BrokenPipe))?;
+        file_reader.seek(SeekFrom::Start(entry.local_header_offset))?;
+        let local_header = LocalFileHeader::read(&mut *file_reader)?;
+        Ok(entry.local_header_offset
+            + 30
+            + local_header.file_name_length as u64
+            + local_header.extra_field_length as u64)
+    }
+
+    fn get_compressed_reader(&self, entry: &ZipEntry) -> ReadResult<ZipEntryReader> {
+        Ok(ZipEntryReader {
+            file_reader: self.file_reader.clone(),
+            offset: self.get_entry_data_offset(entry)?,
+            size: entry.compressed_size,
+            position: 0,
+        })
+    }
+
+    fn get_entry_reader(
+        &self,
+        entry: &ZipEntry,
+    ) -> ReadResult<Box<dyn UnityResource + Send + Sync>> {
+        let compressed_reader = self.get_compressed_reader(entry)?;
+        match entry.compression_method {
+            COMPRESSION_METHOD_STORED => Ok(Box::new(compressed_reader)),
+            COMPRESSION_METHOD_DEFLATED => {
+                let mut data = Vec::with_capacity(entry.uncompressed_size as usize);
+                DeflateDecoder::new(compressed_reader).read_to_end(&mut data)?;
+                Ok(Box::new(Cursor::new(data)))
+            }
+            method => Err(Error::Other(format!(
+                "unsupported zip compression method {method}"
+            ))),
+        }
+    }
+
+    /// Open a file, the whole file is inflated here when it is deflated.
+    pub fn get_file_reader_by_path(
+        &self,
+        path: &str,
+    ) -> ReadResult<Box<dyn UnityResource + Send + Sync>> {
+        if let Some(entry) = self.entries.get(path) {
+            return self.get_entry_reader(entry);
+        }
+        let parts = self
+            .split_files
+            .get(path)
+            .ok_or_else(|| Error::ResourceNotFound(path.to_owned()))?;
+        let mut data = Vec::new();
+        for part in parts {
+            let entry = self
+                .entries
+                .get(part)
+                .ok_or_else(|| Error::ResourceNotFound(part.to_owned()))?;
+            self.get_entry_reader(entry)?.read_to_end(&mut data)?;
+        }
+        Ok(Box::new(Cursor::new(data)))
+    }
+
+    /// Read at most size bytes from the start of a file, without inflating the whole file.
+    pub fn read_file_header(&self, path: &str, size: usize) -> ReadResult<Vec<u8>> {
+        let entry = match self.entries.get(path) {
+            Some(entry) => entry,
+            None => self
+                .split_files
+                .get(path)
+                .and_then(|parts| parts.first())
+                .and_then(|part| self.entries.get(part))
+                .ok_or_else(|| Error::ResourceNotFound(path.to_owned()))?,
+        };
+        let compressed_reader = self.get_compressed_reader(entry)?;
+        let mut header = Vec::with_capacity(size);
+        match entry.compression_method {
+            COMPRESSION_METHOD_STORED => compressed_reader
+                .take(size as u64)
+                .read_to_end(&mut header)?,
+            COMPRESSION_METHOD_DEFLATED => DeflateDecoder::new(compressed_reader)
+                .take(size as u64)
+                .read_to_end(&mut header)?,
+            method => {
+                return Err(Error::Other(format!(
+                    "unsupported zip compression method {method}"
+                )))
+            }
+        };
+        Ok(header)
+    }
+}
+
+fn read_central_directory_location<R: Read + Seek>(file: &mut R) -> ReadResult<(u64, u64)> {
+    // end of central directory is 22 bytes with a comment at most 65535 bytes.
+    let file_size = file.seek(SeekFrom::End(0))?;
+    let tail_size = file_size.min(22 + u16::MAX as u64);
+    file.seek(SeekFrom::Start(file_size - tail_size))?;
+    let mut tail = vec![0u8; tail_size as usize];
+    file.read_exact(&mut tail)?;
+    let end_of_central_directory_position = tail
+        .windows(4)
+        .rposition(|magic| magic == b"PK\x05\x06")
+        .map(|position| file_size - tail_size + position as u64)
+        .ok_or_else(|| Error::Other("zip end of central directory not found".to_owned()))?;
+
+    file.seek(SeekFrom::Start(end_of_central_directory_position))?;
+    let end_of_central_directory = EndOfCentralDirectory::read(file)?;
+    if end_of_central_directory.central_directory_offset != u32::MAX
+        && end_of_central_directory.total_entries != u16::MAX
+    {
+        return Ok((
+            end_of_central_directory.central_directory_offset as u64,
+            end_of_central_directory.total_entries as u64,
+        ));
+    }
+
+    let locator_position = end_of_central_directory_position
+        .checked_sub(20)
+        .ok_or_else(|| Error::Other("zip64 end of central directory not found".to_owned()))?;
+    file.seek(SeekFrom::Start(locator_position))?;
+    let locator = Zip64EndOfCentralDirectoryLocator::read(file)?;
+    file.seek(SeekFrom::Start(locator.end_of_central_directory_offset))?;
+    let end_of_central_directory = Zip64EndOfCentralDirectory::read(file)?;
+    Ok((
+        end_of_central_directory.central_directory_offset,
+        end_of_central_directory.total_entries,
+    ))
+}
+
+/// Window of the package file, which is the data of a stored entry.
+#[derive(Clone)]
+pub struct ZipEntryReader {
+    file_reader: Arc<Mutex<Box<dyn UnityResource + Send + Sync>>>,
+    offset: u64,
+    size: u64,
+    position: u64,
+}
+
+impl Read for ZipEntryReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remain = self.size.saturating_sub(self.position);
+        let read_size = (buf.len() as u64).min(remain) as usize;
+        if read_size == 0 {
+            return Ok(0);
+        }
+        let mut file_reader = self
+            .file_reader
+            .lock()
+            .map_err(|_| std::io::Error::from(ErrorKind::BrokenPipe))?;
+        file_reader.seek(SeekFrom::Start(self.offset + self.position))?;
+        let size = file_reader.read(&mut buf[..read_size])?;
+        self.position += size as u64;
+        Ok(size)
+    }
+}
+
+impl Seek for ZipEntryReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        self.position = position.ok_or_else(|| {
+            std::io::Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::DeflateEncoder, Compression};
+
+    use super::*;
+
+    fn build_zip(files: &[(&str, &[u8], bool)]) -> Vec<u8> {
+        let mut zip = Vec::new();
+        let mut central_directory = Vec::new();
+        for (name, data, deflate) in files {
+            let (method, stored_data) = if *deflate {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(data).unwrap();
+                (COMPRESSION_METHOD_DEFLATED, encoder.finish().unwrap())
+            } else {
+                (COMPRESSION_METHOD_STORED, data.to_vec())
+            };
+            let offset = zip.len() as u32;
+            let mut header = Vec::new();
+            header.extend(0u16.to_le_bytes()); // flags
+            header.extend(method.to_le_bytes());
+            header.extend([0u8; 8]); // time, date and crc
+            header.extend((stored_data.len() as u32).to_le_bytes());
+            header.extend((data.len() as u32).to_le_bytes());
+            header.extend((name.len() as u16).to_le_bytes());
+            header.extend(0u16.to_le_bytes()); // extra field
+
+            zip.extend(b"PK\x03\x04");
+            zip.extend(20u16.to_le_bytes());
+            zip.extend(&header);
+            zip.extend(name.as_bytes());
+            zip.extend(&stored_data);
+
+            central_directory.extend(b"PK\x01\x02");
+            central_directory.extend(20u16.to_le_bytes());
+            central_directory.extend(20u16.to_le_bytes());
+            central_directory.extend(&header);
+            central_directory.extend([0u8; 10]); // comment, disk and attributes
+            central_directory.extend(offset.to_le_bytes());
+            central_directory.extend(name.as_bytes());
+        }
+        let central_directory_offset = zip.len() as u32;
+        zip.extend(&central_directory);
+        zip.extend(b"PK\x05\x06");
+        zip.extend([0u8; 4]);
+        zip.extend((files.len() as u16).to_le_bytes());
+        zip.extend((files.len() as u16).to_le_bytes());
+        zip.extend((central_directory.len() as u32).to_le_bytes());
+        zip.extend(central_directory_offset.to_le_bytes());
+        zip.extend(0u16.to_le_bytes());
+        zip
+    }
+
+    #[test]
+    fn test_zip_package() {
+        let zip = build_zip(&[
+            ("assets/bin/Data/level0", b"stored data", false),
+            ("assets/bin/Data/level1", &[7u8; 1000], true),
+            ("assets/bin/Data/big.assets.split1", b"world", true),
+            ("assets/bin/Data/big.assets.split0", b"hello ", false),
+        ]);
+        let package = ZipPackage::read(Box::new(Cursor::new(zip))).unwrap();
+        assert_eq!(
+            package.get_file_paths(),
+            [
+                "assets/bin/Data/big.assets",
+                "assets/bin/Data/level0",
+                "assets/bin/Data/level1",
+            ]
+        );
+
+        let mut reader = package
+            .get_file_reader_by_path("assets/bin/Data/level0")
+            .unwrap();
+        reader.seek(SeekFrom::Start(7)).unwrap();
+        let mut data = String::new();
+        reader.read_to_string(&mut data).unwrap();
+        assert_eq!(data, "data");
+
+        let mut data = Vec::new();
+        package
+            .get_file_reader_by_path("assets/bin/Data/level1")
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+        assert_eq!(data, [7u8; 1000]);
+        assert_eq!(
+            package
+                .read_file_header("assets/bin/Data/level1", 4)
+                .unwrap(),
+            [7u8; 4]
+        );
+
+        let mut data = String::new();
+        package
+            .get_file_reader_by_path("assets/bin/Data/big.assets")
+            .unwrap()
+            .read_to_string(&mut data)
+            .unwrap();
+        assert_eq!(data, "hello world");
+        assert_eq!(
+            package.get_file_size("assets/bin/Data/big.assets"),
+            Some(11)
+        );
+    }
+}
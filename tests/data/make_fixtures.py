@@ -0,0 +1,691 @@
+#!/usr/bin/env python3
+"""Write the test fixtures of this dir.
+
+prefabs.bundle is an uncompressed asset bundle of Unity 2018.4 (UnityFS 6, serialized file 17)
+with type trees, data.unity3d is an uncompressed player data bundle without type trees,
+manifest.bundle is the AssetBundleManifest bundle of a build,
+sprites.assets is a serialized file with type trees holding a Material and a Sprite.
+Type trees follow the layout written by Unity 2018.4 for these classes.
+
+Game.dll is a managed assembly with the metadata tables a C# compiler write for
+Game.Hero (see make_game_dll), scripts.assets hold a MonoBehaviour of it without type trees,
+script_types.assets hold the same classes with type trees for the MonoBehaviour base fields.
+"""
+import hashlib
+import os
+import re
+import struct
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+UNITY_VERSION = "2018.4.36f1"
+STANDALONE_WINDOWS64 = 19
+
+
+def read_common_strings():
+    with open(os.path.join(HERE, "../../src/serialized_file/mod.rs")) as f:
+        source = f.read()
+    return {name: int(offset) for offset, name in re.findall(r'\((\d+), "([^"]+)"\)', source)}
+
+
+COMMON_STRINGS = read_common_strings()
+
+
+class Node:
+    def __init__(self, type_name, name, byte_size, meta_flag=0, children=(), is_array=False, version=1):
+        self.type_name = type_name
+        self.name = name
+        self.byte_size = byte_size
+        self.meta_flag = meta_flag
+        self.children = list(children)
+        self.is_array = is_array
+        self.version = version
+
+
+def array(item, meta_flag=0):
+    return Node("Array", "Array", -1, meta_flag, [Node("int", "size", 4, meta_flag & 0x41), item], is_array=True)
+
+
+def string(name, meta_flag=0x8000):
+    return Node("string", name, -1, meta_flag, [array(Node("char", "data", 1, 1), 0x4001)])
+
+
+def pptr(type_name, name, meta_flag=0):
+    return Node(type_name, name, 12, meta_flag, [
+        Node("int", "m_FileID", 4, meta_flag),
+        Node("SInt64", "m_PathID", 8, meta_flag),
+    ])
+
+
+def vector(name, item, meta_flag=0):
+    return Node("vector", name, -1, meta_flag, [array(item, meta_flag)])
+
+
+def vector3(name):
+    return Node("Vector3f", name, 12, 0, [Node("float", axis, 4) for axis in "xyz"])
+
+
+def asset_info(name):
+    return Node("AssetInfo", name, 20, 0, [
+        Node("int", "preloadIndex", 4),
+        Node("int", "preloadSize", 4),
+        pptr("PPtr<Object>", "asset"),
+    ])
+
+
+GAME_OBJECT = Node("GameObject", "Base", -1, 0x8000, [
+    vector("m_Component", Node("ComponentPair", "data", 12, 0x41, [pptr("PPtr<Component>", "component", 0x41)]), 0x41),
+    Node("unsigned int", "m_Layer", 4),
+    string("m_Name"),
+    Node("UInt16", "m_Tag", 2),
+    Node("bool", "m_IsActive", 1),
+], version=5)
+
+TRANSFORM = Node("Transform", "Base", -1, 0, [
+    pptr("PPtr<GameObject>", "m_GameObject", 0x41),
+    Node("Quaternionf", "m_LocalRotation", 16, 0, [Node("float", axis, 4) for axis in "xyzw"]),
+    vector3("m_LocalPosition"),
+    vector3("m_LocalScale"),
+    vector("m_Children", pptr("PPtr<Transform>", "data", 0x41), 0x41),
+    pptr("PPtr<Transform>", "m_Father", 0x41),
+])
+
+ASSET_BUNDLE = Node("AssetBundle", "Base", -1, 0x8000, [
+    string("m_Name"),
+    vector("m_PreloadTable", pptr("PPtr<Object>", "data")),
+    Node("map", "m_Container", -1, 0x8000, [array(
+        Node("pair", "data", -1, 0x8000, [string("first"), asset_info("second")]), 0x8000)]),
+    asset_info("m_MainAsset"),
+    Node("unsigned int", "m_RuntimeCompatibility", 4),
+    string("m_AssetBundleName"),
+    vector("m_Dependencies", string("data"), 0x8000),
+    Node("bool", "m_IsStreamedSceneAssetBundle", 1, 0x4000),
+    Node("int", "m_ExplicitDataLayout", 4),
+    Node("int", "m_PathFlags", 4),
+    Node("map", "m_SceneHashes", -1, 0x8000, [array(
+        Node("pair", "data", -1, 0x8000, [string("first"), string("second")]), 0x8000)]),
+], version=3)
+
+TEXT_ASSET = Node("TextAsset", "Base", -1, 0x8000, [string("m_Name"), string("m_Script")])
+
+PRELOAD_DATA = Node("PreloadData", "Base", -1, 0x8000, [
+    string("m_Name"),
+    vector("m_Assets", pptr("PPtr<Object>", "data")),
+    vector("m_Dependencies", string("data"), 0x8000),
+], version=2)
+
+
+def hash128(name):
+    return Node("Hash128", name, 16, 0, [Node("UInt8", f"bytes[{i}]", 1) for i in range(16)])
+
+
+ASSET_BUNDLE_MANIFEST = Node("AssetBundleManifest", "Base", -1, 0x8000, [
+    string("m_Name"),
+    Node("map", "AssetBundleNames", -1, 0x8000, [array(
+        Node("pair", "data", -1, 0x8000, [Node("int", "first", 4), string("second")]), 0x8000)]),
+    vector("AssetBundlesWithVariant", Node("int", "data", 4)),
+    Node("map", "AssetBundleInfos", -1, 0, [array(
+        Node("pair", "data", -1, 0, [Node("int", "first", 4), Node("AssetBundleInfo", "second", -1, 0, [
+            hash128("AssetBundleHash"),
+            vector("AssetBundleDependencies", Node("int", "data", 4)),
+        ])]))]),
+])
+
+PRIMITIVES = {
+    "int": "<i", "unsigned int": "<I", "float": "<f", "SInt64": "<q",
+    "UInt16": "<H", "bool": "<?", "char": "<B", "UInt8": "<B",
+}
+
+
+def write_value(node, value, buff):
+    if node.is_array:
+        item = node.children[1]
+        buff += struct.pack("<i", len(value))
+        for item_value in value:
+            write_value(item, item_value, buff)
+    elif node.type_name in PRIMITIVES:
+        buff += struct.pack(PRIMITIVES[node.type_name], value)
+    elif node.type_name == "string":
+        write_value(node.children[0], list(value.encode()), buff)
+    elif node.type_name in ("vector", "map"):
+        write_value(node.children[0], value, buff)
+    else:
+        if not isinstance(value, dict):
+            value = dict(zip((child.name for child in node.children), value))
+        for child in node.children:
+            write_value(child, value[child.name], buff)
+    if node.meta_flag & 0x4000:
+        while len(buff) % 4:
+            buff.append(0)
+
+
+def flatten(node, level=0, nodes=None):
+    nodes = [] if nodes is None else nodes
+    nodes.append((level, node))
+    for child in node.children:
+        flatten(child, level + 1, nodes)
+    return nodes
+
+
+def type_tree_blob(root):
+    local_strings = bytearray()
+    local_offsets = {}
+
+    def string_offset(s):
+        if s in COMMON_STRINGS:
+            return 0x80000000 | COMMON_STRINGS[s]
+        if s not in local_offsets:
+            local_offsets[s] = len(local_strings)
+            local_strings.extend(s.encode() + b"\0")
+        return local_offsets[s]
+
+    nodes = bytearray()
+    flat = flatten(root)
+    for index, (level, node) in enumerate(flat):
+        nodes += struct.pack(
+            "<HBBIIiii", node.version, level, 1 if node.is_array else 0,
+            string_offset(node.type_name), string_offset(node.name),
+            node.byte_size, index, node.meta_flag)
+    return struct.pack("<ii", len(flat), len(local_strings)) + nodes + local_strings
+
+
+def serialized_file(types, objects, externals, enable_type_tree):
+    """types: [(class_id, root)], objects: [(path_id, type index, value)] in path id order."""
+    metadata = bytearray(UNITY_VERSION.encode() + b"\0")
+    metadata += struct.pack("<iBi", STANDALONE_WINDOWS64, enable_type_tree, len(types))
+    for class_id, root in types:
+        blob = type_tree_blob(root)
+        metadata += struct.pack("<iBh", class_id, 0, -1)
+        if class_id == 114:
+            # script id, the hash of the script class.
+            metadata += hashlib.md5(b"Game.Hero").digest()
+        metadata += hashlib.md5(blob).digest()
+        if enable_type_tree:
+            metadata += blob
+
+    data = bytearray()
+    object_infos = []
+    for path_id, type_index, value in objects:
+        while len(data) % 8:
+            data.append(0)
+        start = len(data)
+        write_value(types[type_index][1], value, data)
+        object_infos.append((path_id, start, len(data) - start, type_index))
+
+    header_size = 20
+    metadata += struct.pack("<i", len(object_infos))
+    for path_id, start, size, type_index in object_infos:
+        while (header_size + len(metadata)) % 4:
+            metadata.append(0)
+        metadata += struct.pack("<qIIi", path_id, start, size, type_index)
+    metadata += struct.pack("<ii", 0, len(externals))
+    for path in externals:
+        metadata += b"\0" + bytes(16) + struct.pack("<i", 0) + path.encode() + b"\0"
+    metadata += b"\0"
+
+    data_offset = header_size + len(metadata)
+    data_offset = (data_offset + 15) // 16 * 16
+    file_size = data_offset + len(data)
+    header = struct.pack(">IIII", len(metadata), file_size, 17, data_offset) + b"\0\0\0\0"
+    out = header + metadata
+    out += bytes(data_offset - len(out))
+    return bytes(out + data)
+
+
+def unity_fs(nodes):
+    """nodes: [(path, flags, data)], stored in one uncompressed block."""
+    data = bytearray()
+    node_infos = bytearray()
+    for path, flags, node_data in nodes:
+        node_infos += struct.pack(">qqI", len(data), len(node_data), flags) + path.encode() + b"\0"
+        data += node_data
+    blocks_info = bytes(16) + struct.pack(">IIiH", 1, len(data), len(data), 0x40)
+    blocks_info += struct.pack(">I", len(nodes)) + node_infos
+    header = b"UnityFS\0" + struct.pack(">I", 6) + b"5.x.x\0" + UNITY_VERSION.encode() + b"\0"
+    size = len(header) + 8 + 12 + len(blocks_info) + len(data)
+    header += struct.pack(">qIII", size, len(blocks_info), len(blocks_info), 0x40)
+    return header + blocks_info + bytes(data)
+
+
+def cab_name(name):
+    return "CAB-" + hashlib.md5(name.encode()).hexdigest()
+
+
+def pptr_value(path_id, file_id=0):
+    return {"m_FileID": file_id, "m_PathID": path_id}
+
+
+def transform_value(game_object, position, children, father):
+    return {
+        "m_GameObject": pptr_value(game_object),
+        "m_LocalRotation": {"x": 0.0, "y": 0.0, "z": 0.0, "w": 1.0},
+        "m_LocalPosition": dict(zip("xyz", position)),
+        "m_LocalScale": {"x": 1.0, "y": 1.0, "z": 1.0},
+        "m_Children": [pptr_value(child) for child in children],
+        "m_Father": pptr_value(father),
+    }
+
+
+def game_object_value(name, components, layer=0):
+    return {
+        "m_Component": [{"component": pptr_value(component)} for component in components],
+        "m_Layer": layer,
+        "m_Name": name,
+        "m_Tag": 0,
+        "m_IsActive": True,
+    }
+
+
+def make_prefabs_bundle():
+    player, player_transform = -6838291376052283548, 3429483095702384135
+    weapon, weapon_transform = -2473851946298134412, 8124637702648359025
+    pickup, pickup_transform = 5216790040817766452, -1470312270960719185
+    preload_table = [pickup, pickup_transform, player, player_transform, weapon, weapon_transform]
+
+    def info(preload_index, preload_size, asset):
+        return {"preloadIndex": preload_index, "preloadSize": preload_size, "asset": pptr_value(asset)}
+
+    asset_bundle = {
+        "m_Name": "prefabs",
+        "m_PreloadTable": [pptr_value(path_id) for path_id in preload_table],
+        "m_Container": [
+            ("assets/prefabs/pickup.prefab", info(0, 2, pickup)),
+            ("assets/prefabs/player.prefab", info(2, 4, player)),
+        ],
+        "m_MainAsset": info(0, 0, 0),
+        "m_RuntimeCompatibility": 1,
+        "m_AssetBundleName": "prefabs",
+        "m_Dependencies": [],
+        "m_IsStreamedSceneAssetBundle": False,
+        "m_ExplicitDataLayout": 0,
+        "m_PathFlags": 7,
+        "m_SceneHashes": [],
+    }
+    objects = [
+        (player, 1, game_object_value("Player", [player_transform], layer=8)),
+        (weapon, 1, game_object_value("Weapon", [weapon_transform])),
+        (pickup_transform, 2, transform_value(pickup, (4.0, 0.0, -2.5), [], 0)),
+        (1, 0, asset_bundle),
+        (player_transform, 2, transform_value(player, (1.0, 2.0, 3.0), [weapon_transform], 0)),
+        (pickup, 1, game_object_value("Pickup", [pickup_transform])),
+        (weapon_transform, 2, transform_value(weapon, (0.5, 1.25, 0.0), [], player_transform)),
+    ]
+    objects.sort(key=lambda obj: obj[0])
+    cab = serialized_file(
+        [(142, ASSET_BUNDLE), (1, GAME_OBJECT), (4, TRANSFORM)], objects, [], True)
+    return unity_fs([(cab_name("prefabs"), 4, cab)])
+
+
+def make_data_unity3d():
+    level0 = serialized_file(
+        [(1, GAME_OBJECT), (4, TRANSFORM)],
+        [
+            (1, 0, game_object_value("Main Camera", [2])),
+            (2, 1, transform_value(1, (0.0, 1.0, -10.0), [], 0)),
+        ],
+        ["sharedassets0.assets"],
+        False,
+    )
+    sharedassets0 = serialized_file(
+        [(150, PRELOAD_DATA), (49, TEXT_ASSET)],
+        [
+            (1, 0, {"m_Name": "", "m_Assets": [pptr_value(2)], "m_Dependencies": []}),
+            (2, 1, {"m_Name": "credits", "m_Script": "Made with Unity\n"}),
+        ],
+        [],
+        False,
+    )
+    return unity_fs([("level0", 4, level0), ("sharedassets0.assets", 4, sharedassets0)])
+
+
+def make_manifest_bundle():
+    """Manifest bundle of a build with the bundles prefabs and ui, ui depend on prefabs."""
+    manifest = {
+        "m_Name": "AssetBundleManifest",
+        "AssetBundleNames": [(0, "prefabs"), (1, "ui")],
+        "AssetBundlesWithVariant": [],
+        "AssetBundleInfos": [
+            (0, {"AssetBundleHash": list(range(16)), "AssetBundleDependencies": []}),
+            (1, {"AssetBundleHash": list(hashlib.md5(b"ui").digest()), "AssetBundleDependencies": [0]}),
+        ],
+    }
+    cab = serialized_file([(290, ASSET_BUNDLE_MANIFEST)], [(1, 0, manifest)], [], True)
+    return unity_fs([(cab_name("Bundles"), 4, cab)])
+
+
+def vector2(name):
+    return Node("Vector2f", name, 8, 0, [Node("float", axis, 4) for axis in "xy"])
+
+
+def vector4(name):
+    return Node("Vector4f", name, 16, 0, [Node("float", axis, 4) for axis in "xyzw"])
+
+
+def rect(name):
+    return Node("Rectf", name, 16, 0, [Node("float", axis, 4) for axis in ("x", "y", "width", "height")])
+
+
+def string_map(name, value):
+    return Node("map", name, -1, 0, [array(Node("pair", "data", -1, 0, [string("first"), value]))])
+
+
+MATERIAL = Node("Material", "Base", -1, 0x8000, [
+    string("m_Name"),
+    pptr("PPtr<Shader>", "m_Shader"),
+    string("m_ShaderKeywords"),
+    Node("unsigned int", "m_LightmapFlags", 4),
+    Node("bool", "m_EnableInstancingVariants", 1),
+    Node("bool", "m_DoubleSidedGI", 1, 0x4000),
+    Node("int", "m_CustomRenderQueue", 4),
+    Node("map", "stringTagMap", -1, 0, [array(Node("pair", "data", -1, 0, [string("first"), string("second")]))]),
+    vector("disabledShaderPasses", string("data")),
+    Node("UnityPropertySheet", "m_SavedProperties", -1, 0, [
+        string_map("m_TexEnvs", Node("UnityTexEnv", "second", -1, 0, [
+            pptr("PPtr<Texture>", "m_Texture"),
+            vector2("m_Scale"),
+            vector2("m_Offset"),
+        ])),
+        string_map("m_Floats", Node("float", "second", 4)),
+        string_map("m_Colors", Node("ColorRGBA", "second", 16, 0, [Node("float", c, 4) for c in "rgba"])),
+    ]),
+], version=6)
+
+# m_RD leave out the mesh data (m_SubMeshes, m_IndexBuffer, m_VertexData and m_Bindpose),
+# and m_Bones is left out, the other fields follow Unity 2018.4.
+SPRITE = Node("Sprite", "Base", -1, 0x8000, [
+    string("m_Name"),
+    rect("m_Rect"),
+    vector2("m_Offset"),
+    vector4("m_Border"),
+    Node("float", "m_PixelsToUnits", 4),
+    vector2("m_Pivot"),
+    Node("unsigned int", "m_Extrude", 4),
+    Node("bool", "m_IsPolygon", 1, 0x4000),
+    Node("pair", "m_RenderDataKey", -1, 0, [
+        Node("GUID", "first", 16, 0, [Node("unsigned int", f"data[{i}]", 4) for i in range(4)]),
+        Node("SInt64", "second", 8),
+    ]),
+    vector("m_AtlasTags", string("data")),
+    pptr("PPtr<SpriteAtlas>", "m_SpriteAtlas"),
+    Node("SpriteRenderData", "m_RD", -1, 0, [
+        pptr("PPtr<Texture2D>", "texture"),
+        pptr("PPtr<Texture2D>", "alphaTexture"),
+        rect("textureRect"),
+        vector2("textureRectOffset"),
+        vector2("atlasRectOffset"),
+        Node("unsigned int", "settingsRaw", 4),
+        vector4("uvTransform"),
+        Node("float", "downscaleMultiplier", 4),
+    ]),
+    vector("m_PhysicsShape", vector("data", vector2("data"))),
+], version=2)
+
+
+def make_sprites_assets():
+    """Material and Sprite of unity 2018.4 with type trees, the texture is in sharedassets0.assets."""
+    material = {
+        "m_Name": "Outline",
+        "m_Shader": pptr_value(46, 2),
+        "m_ShaderKeywords": "ETC1_EXTERNAL_ALPHA",
+        "m_LightmapFlags": 4,
+        "m_EnableInstancingVariants": False,
+        "m_DoubleSidedGI": False,
+        "m_CustomRenderQueue": 3000,
+        "stringTagMap": [("RenderType", "Transparent")],
+        "disabledShaderPasses": [],
+        "m_SavedProperties": {
+            "m_TexEnvs": [("_MainTex", {
+                "m_Texture": pptr_value(2, 1),
+                "m_Scale": {"x": 1.0, "y": 1.0},
+                "m_Offset": {"x": 0.0, "y": 0.0},
+            })],
+            "m_Floats": [("_OutlineWidth", 0.125), ("PixelSnap", 0.0)],
+            "m_Colors": [("_Color", {"r": 1.0, "g": 0.5, "b": 0.25, "a": 1.0})],
+        },
+    }
+    sprite = {
+        "m_Name": "hero_idle",
+        "m_Rect": {"x": 32.0, "y": 64.0, "width": 16.0, "height": 24.0},
+        "m_Offset": {"x": 0.5, "y": -1.0},
+        "m_Border": dict(zip("xyzw", (0.0, 0.0, 0.0, 0.0))),
+        "m_PixelsToUnits": 100.0,
+        "m_Pivot": {"x": 0.5, "y": 0.25},
+        "m_Extrude": 1,
+        "m_IsPolygon": False,
+        "m_RenderDataKey": ([0x12345678, 0, 0, 0x9abcdef0], 21300000),
+        "m_AtlasTags": [],
+        "m_SpriteAtlas": pptr_value(0),
+        "m_RD": {
+            "texture": pptr_value(2, 1),
+            "alphaTexture": pptr_value(0),
+            "textureRect": {"x": 32.0, "y": 64.0, "width": 16.0, "height": 24.0},
+            "textureRectOffset": {"x": 0.0, "y": 0.0},
+            "atlasRectOffset": {"x": -1.0, "y": -1.0},
+            "settingsRaw": 64,
+            "uvTransform": dict(zip("xyzw", (100.0, 40.0, 100.0, 70.0))),
+            "downscaleMultiplier": 1.0,
+        },
+        "m_PhysicsShape": [[{"x": -0.08, "y": -0.06}, {"x": 0.08, "y": -0.06}, {"x": 0.0, "y": 0.18}]],
+    }
+    return serialized_file(
+        [(21, MATERIAL), (213, SPRITE)],
+        [(1, 0, material), (2, 1, sprite)],
+        ["sharedassets0.assets", "Resources/unity_builtin_extra"],
+        True,
+    )
+
+
+MONO_BEHAVIOUR = Node("MonoBehaviour", "Base", -1, 0x8000, [
+    pptr("PPtr<GameObject>", "m_GameObject"),
+    Node("UInt8", "m_Enabled", 1, 0x4000),
+    pptr("PPtr<MonoScript>", "m_Script"),
+    string("m_Name"),
+])
+
+MONO_SCRIPT = Node("MonoScript", "Base", -1, 0x8000, [
+    string("m_Name"),
+    Node("int", "m_ExecutionOrder", 4),
+    hash128("m_PropertiesHash"),
+    string("m_ClassName"),
+    string("m_Namespace"),
+    string("m_AssemblyName"),
+])
+
+
+def script_vector(name, item):
+    """vector as MonoBehaviourTypeTreeGenerator write it."""
+    return Node("vector", name, -1, 0x8000, [
+        Node("Array", "Array", -1, 0x4000, [Node("int", "size", 4), item], is_array=True)])
+
+
+# MonoBehaviour with the script fields of Game.Hero.
+HERO = Node("MonoBehaviour", "Base", -1, 0x8000, MONO_BEHAVIOUR.children + [
+    Node("int", "level", 4),
+    string("title"),
+    Node("float", "speed", 4),
+    script_vector("scores", Node("int", "data", 4)),
+    script_vector("items", Node("Item", "data", -1, 0, [Node("int", "id", 4)])),
+])
+
+
+def make_game_dll():
+    """PE32 dll with one .text section holding the CLI header and the metadata of
+
+        namespace Game {
+            public class Hero : UnityEngine.MonoBehaviour {
+                public int level;
+                public string title;
+                [UnityEngine.SerializeField] private float speed;
+                private int hidden;
+                public System.Collections.Generic.List<int> scores;
+                public Item[] items;
+
+                [System.Serializable] public class Item { public int id; }
+            }
+        }
+
+    Methods and IL are left out, they are not read for type trees.
+    """
+    strings = bytearray(b"\0")
+    string_offsets = {}
+
+    def string_index(value):
+        if value not in string_offsets:
+            string_offsets[value] = len(strings)
+            strings.extend(value.encode() + b"\0")
+        return string_offsets[value]
+
+    blobs = bytearray(b"\0")
+
+    def blob_index(value):
+        offset = len(blobs)
+        blobs.extend(bytes([len(value)]) + value)
+        return offset
+
+    # (table id, rows), every index is 2 bytes as the heaps and tables are small.
+    assembly_refs = [
+        (0, 0, 0, 0, 0, 0, string_index("mscorlib"), 0, 0),
+        (0, 0, 0, 0, 0, 0, string_index("UnityEngine.CoreModule"), 0, 0),
+    ]
+    mscorlib, unity_engine = (1 << 2) | 2, (2 << 2) | 2  # ResolutionScope AssemblyRef
+    type_refs = [
+        (unity_engine, string_index("MonoBehaviour"), string_index("UnityEngine")),
+        (mscorlib, string_index("Object"), string_index("System")),
+        (mscorlib, string_index("List`1"), string_index("System.Collections.Generic")),
+        (unity_engine, string_index("SerializeField"), string_index("UnityEngine")),
+    ]
+    mono_behaviour_ref, object_ref, list_ref, serialize_field_ref = 1, 2, 3, 4
+
+    def type_def_or_ref(table, row):
+        return row << 2 | {"TypeDef": 0, "TypeRef": 1, "TypeSpec": 2}[table]
+
+    field = 0x06
+    fields = [
+        (0x6, string_index("level"), blob_index(bytes([field, 0x08]))),
+        (0x6, string_index("title"), blob_index(bytes([field, 0x0E]))),
+        (0x1, string_index("speed"), blob_index(bytes([field, 0x0C]))),
+        (0x1, string_index("hidden"), blob_index(bytes([field, 0x08]))),
+        (0x6, string_index("scores"), blob_index(
+            bytes([field, 0x15, 0x12, type_def_or_ref("TypeRef", list_ref), 1, 0x08]))),
+        (0x6, string_index("items"), blob_index(
+            bytes([field, 0x1D, 0x12, type_def_or_ref("TypeDef", 3)]))),
+        (0x6, string_index("id"), blob_index(bytes([field, 0x08]))),
+    ]
+    public, nested_public, serializable, before_field_init = 0x1, 0x2, 0x2000, 0x100000
+    type_defs = [
+        (0, string_index("<Module>"), 0, 0, 1, 1),
+        (public | before_field_init, string_index("Hero"), string_index("Game"),
+         type_def_or_ref("TypeRef", mono_behaviour_ref), 1, 1),
+        (nested_public | serializable | before_field_init, string_index("Item"), 0,
+         type_def_or_ref("TypeRef", object_ref), 7, 1),
+    ]
+    # SerializeField::.ctor(), MemberRefParent TypeRef.
+    member_refs = [(serialize_field_ref << 3 | 1, string_index(".ctor"), blob_index(bytes([0x20, 0, 0x01])))]
+    # HasCustomAttribute Field speed, CustomAttributeType MemberRef.
+    custom_attributes = [(3 << 5 | 1, 1 << 3 | 3, blob_index(bytes([1, 0, 0, 0])))]
+    assemblies = [(0x8004, 1, 0, 0, 0, 0, 0, string_index("Game"), 0)]
+    nested_classes = [(3, 2)]
+    modules = [(0, string_index("Game.dll"), 1, 0, 0)]
+
+    tables = [
+        (0x00, modules, "<HHHHH"),
+        (0x01, type_refs, "<HHH"),
+        (0x02, type_defs, "<IHHHHH"),
+        (0x04, fields, "<HHH"),
+        (0x0A, member_refs, "<HHH"),
+        (0x0C, custom_attributes, "<HHH"),
+        (0x20, assemblies, "<IHHHHIHHH"),
+        (0x23, assembly_refs, "<HHHHIHHHH"),
+        (0x29, nested_classes, "<HH"),
+    ]
+    valid = sum(1 << table for table, _, _ in tables)
+    table_stream = struct.pack("<IBBBBQQ", 0, 2, 0, 0, 1, valid, 0)
+    table_stream += b"".join(struct.pack("<I", len(rows)) for _, rows, _ in tables)
+    for _, rows, row_format in tables:
+        table_stream += b"".join(struct.pack(row_format, *row) for row in rows)
+
+    def pad4(data):
+        return bytes(data) + bytes(-len(data) % 4)
+
+    guids = hashlib.md5(b"Game.dll").digest()
+    streams = [("#~", pad4(table_stream)), ("#Strings", pad4(strings)), ("#GUID", guids),
+               ("#Blob", pad4(blobs))]
+    version = pad4(b"v4.0.30319\0")
+    headers_size = sum(8 + len(pad4(name.encode() + b"\0")) for name, _ in streams)
+    offset = 16 + len(version) + 4 + headers_size
+    metadata = b"BSJB" + struct.pack("<HHII", 1, 1, 0, len(version)) + version
+    metadata += struct.pack("<HH", 0, len(streams))
+    for name, data in streams:
+        metadata += struct.pack("<II", offset, len(data)) + pad4(name.encode() + b"\0")
+        offset += len(data)
+    metadata += b"".join(data for _, data in streams)
+
+    section_rva, file_alignment = 0x2000, 0x200
+    cli_header_size = 72
+    cli_header = struct.pack("<IHHIIII", cli_header_size, 2, 5, section_rva + cli_header_size,
+                             len(metadata), 1, 0)
+    text = cli_header + bytes(cli_header_size - len(cli_header)) + metadata
+    raw_size = -(-len(text) // file_alignment) * file_alignment
+
+    dos_header = b"MZ" + bytes(0x3A) + struct.pack("<I", 0x80)
+    dos_header += bytes(0x80 - len(dos_header))
+    coff_header = b"PE\0\0" + struct.pack("<HHIIIHH", 0x14C, 1, 0, 0, 0, 0xE0, 0x2102)
+    data_directories = [(0, 0)] * 16
+    data_directories[14] = (section_rva, cli_header_size)
+    optional_header = struct.pack(
+        "<HBBIIIIIIIIIHHHHHHIIIIHHIIIIII", 0x10B, 8, 0, raw_size, 0, 0, 0, section_rva,
+        section_rva, 0x10000000, 0x2000, file_alignment, 4, 0, 0, 0, 4, 0, 0,
+        section_rva + 0x2000, file_alignment, 0, 3, 0x8540, 0x100000, 0x1000, 0x100000,
+        0x1000, 0, 16)
+    optional_header += b"".join(struct.pack("<II", *directory) for directory in data_directories)
+    section_header = b".text\0\0\0" + struct.pack(
+        "<IIIIIIHHI", len(text), section_rva, raw_size, file_alignment, 0, 0, 0, 0, 0x60000020)
+    headers = dos_header + coff_header + optional_header + section_header
+    return headers + bytes(file_alignment - len(headers)) + text + bytes(raw_size - len(text))
+
+
+def make_script_assets(enable_type_tree):
+    hero = {
+        "m_GameObject": pptr_value(0),
+        "m_Enabled": 1,
+        "m_Script": pptr_value(1),
+        "m_Name": "",
+    }
+    mono_behaviour = MONO_BEHAVIOUR
+    if not enable_type_tree:
+        # the script fields are only known from Game.dll.
+        hero.update({
+            "level": 7,
+            "title": "Knight",
+            "speed": 2.5,
+            "scores": [3, 5, 8],
+            "items": [{"id": 1}, {"id": 2}],
+        })
+        mono_behaviour = HERO
+    mono_script = {
+        "m_Name": "Hero",
+        "m_ExecutionOrder": 0,
+        "m_PropertiesHash": [0] * 16,
+        "m_ClassName": "Hero",
+        "m_Namespace": "Game",
+        "m_AssemblyName": "Game.dll",
+    }
+    return serialized_file(
+        [(115, MONO_SCRIPT), (114, mono_behaviour)],
+        [(1, 0, mono_script), (2, 1, hero)],
+        [],
+        enable_type_tree,
+    )
+
+
+if __name__ == "__main__":
+    for name, data in [
+        ("prefabs.bundle", make_prefabs_bundle()),
+        ("data.unity3d", make_data_unity3d()),
+        ("manifest.bundle", make_manifest_bundle()),
+        ("sprites.assets", make_sprites_assets()),
+        ("Game.dll", make_game_dll()),
+        ("scripts.assets", make_script_assets(False)),
+        ("script_types.assets", make_script_assets(True)),
+    ]:
+        with open(os.path.join(HERE, name), "wb") as f:
+            f.write(data)